@@ -8,4 +8,10 @@ pub enum Error {
     DecimalsMismatch,
     #[msg("Amount out < min")]
     MinAmountOut,
+    #[msg("Invalid max price impact")]
+    InvalidMaxPriceImpact,
+    #[msg("Invalid max fee")]
+    InvalidMaxFee,
+    #[msg("Price impact > max")]
+    MaxPriceImpact,
 }
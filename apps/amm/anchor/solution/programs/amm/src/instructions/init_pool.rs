@@ -17,7 +17,7 @@ pub struct InitPool<'info> {
     #[account(
         init,
         payer = payer,
-        space = 8 + Pool::INIT_SPACE,
+        space = 8 + std::mem::size_of::<Pool>(),
         seeds = [
             constants::POOL_AUTH_SEED_PREFIX,
             mint_a.key().as_ref(),
@@ -26,7 +26,7 @@ pub struct InitPool<'info> {
         ],
         bump,
     )]
-    pub pool: Account<'info, Pool>,
+    pub pool: AccountLoader<'info, Pool>,
 
     pub mint_a: InterfaceAccount<'info, Mint>,
     pub mint_b: InterfaceAccount<'info, Mint>,
@@ -67,9 +67,24 @@ pub struct InitPool<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn init_pool(ctx: Context<InitPool>, fee: u16) -> Result<()> {
+pub fn init_pool(
+    ctx: Context<InitPool>,
+    fee: u16,
+    max_price_impact_bps: u16,
+    max_fee: u16,
+) -> Result<()> {
     // Check fee <= constants::MAX_POOL_FEE
     require!(fee <= constants::MAX_POOL_FEE, error::Error::InvalidFee);
+    // Check max_price_impact_bps <= constants::MAX_PRICE_IMPACT
+    require!(
+        max_price_impact_bps <= constants::MAX_PRICE_IMPACT,
+        error::Error::InvalidMaxPriceImpact
+    );
+    // Check max_fee <= constants::MAX_POOL_FEE
+    require!(
+        max_fee <= constants::MAX_POOL_FEE,
+        error::Error::InvalidMaxFee
+    );
     // Check mint_a.decimals == mint_b.decimals
     require!(
         ctx.accounts.mint_a.decimals == ctx.accounts.mint_b.decimals,
@@ -77,9 +92,11 @@ pub fn init_pool(ctx: Context<InitPool>, fee: u16) -> Result<()> {
     );
 
     // Store Pool state
-    let pool = &mut ctx.accounts.pool;
+    let pool = &mut ctx.accounts.pool.load_init()?;
     pool.mint_a = ctx.accounts.mint_a.key();
     pool.mint_b = ctx.accounts.mint_b.key();
+    pool.max_price_impact_bps = max_price_impact_bps;
+    pool.max_fee = max_fee;
 
     Ok(())
 }
@@ -5,6 +5,7 @@ use anchor_spl::{
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
+use crate::constants;
 use crate::state::Pool;
 
 pub fn transfer<'info>(
@@ -31,7 +32,7 @@ pub fn transfer_from_pool<'info>(
     token_program: &Interface<'info, TokenInterface>,
     pool: &InterfaceAccount<'info, TokenAccount>,
     dst: &InterfaceAccount<'info, TokenAccount>,
-    auth: &Account<'info, Pool>,
+    auth: &AccountLoader<'info, Pool>,
     amount: u64,
     seeds: &[&[u8]],
 ) -> Result<()> {
@@ -53,7 +54,7 @@ pub fn mint<'info>(
     token_program: &Interface<'info, TokenInterface>,
     mint: &InterfaceAccount<'info, Mint>,
     dst: &InterfaceAccount<'info, TokenAccount>,
-    auth: &Account<'info, Pool>,
+    auth: &AccountLoader<'info, Pool>,
     amount: u64,
     seeds: &[&[u8]],
 ) -> Result<()> {
@@ -90,3 +91,28 @@ pub fn burn<'info>(
         amount,
     )
 }
+
+// Imbalance of reserves in basis points
+// 0 = balanced, constants::MAX_PRICE_IMPACT = all liquidity on one side
+pub fn get_imbalance(reserve_a: u64, reserve_b: u64) -> u16 {
+    let total = (reserve_a as u128) + (reserve_b as u128);
+    if total == 0 {
+        return 0;
+    }
+
+    let diff = (reserve_a as u128).abs_diff(reserve_b as u128);
+    (diff * (constants::MAX_PRICE_IMPACT as u128) / total) as u16
+}
+
+// Swap fee at the given imbalance
+// Rises linearly from fee at balanced reserves to max_fee at one-sided reserves
+pub fn get_fee(fee: u16, max_fee: u16, imbalance: u16) -> u16 {
+    if max_fee <= fee {
+        return fee;
+    }
+
+    let extra = (max_fee - fee) as u32 * imbalance as u32
+        / constants::MAX_PRICE_IMPACT as u32;
+
+    fee + extra as u16
+}
@@ -26,7 +26,7 @@ pub struct Swap<'info> {
         has_one = mint_a,
         has_one = mint_b,
     )]
-    pub pool: Account<'info, Pool>,
+    pub pool: AccountLoader<'info, Pool>,
 
     pub mint_a: InterfaceAccount<'info, Mint>,
     pub mint_b: InterfaceAccount<'info, Mint>,
@@ -71,16 +71,6 @@ pub fn swap(
     amount_in: u64,
     min_amount_out: u64,
 ) -> Result<()> {
-    // Calculate amount out with fee
-    // amount_out = amount_in * (1 - fee)
-    let mut amount_out = amount_in;
-    let amount_out_fee = amount_out.checked_mul(fee as u64).unwrap()
-        / (constants::MAX_POOL_FEE as u64);
-    amount_out -= amount_out_fee;
-
-    // Check amount_out >= min_amount_out
-    require!(amount_out >= min_amount_out, error::Error::MinAmountOut);
-
     let (pool_in, pool_out, payer_in, payer_out) = if a_for_b {
         (
             &ctx.accounts.pool_a,
@@ -97,6 +87,46 @@ pub fn swap(
         )
     };
 
+    let (max_price_impact_bps, max_fee) = {
+        let pool = ctx.accounts.pool.load()?;
+        (pool.max_price_impact_bps, pool.max_fee)
+    };
+
+    let reserve_in = pool_in.amount;
+    let reserve_out = pool_out.amount;
+    let reserve_in_after = reserve_in.checked_add(amount_in).unwrap();
+
+    // Calculate swap fee
+    // Dynamic fee is based on the imbalance of the trade before fees
+    let imbalance = lib::get_imbalance(
+        reserve_in_after,
+        reserve_out.saturating_sub(amount_in),
+    );
+    let swap_fee = lib::get_fee(fee, max_fee, imbalance);
+
+    // Calculate amount out with fee
+    // amount_out = amount_in * (1 - fee)
+    let mut amount_out = amount_in;
+    let amount_out_fee = amount_out.checked_mul(swap_fee as u64).unwrap()
+        / (constants::MAX_POOL_FEE as u64);
+    amount_out -= amount_out_fee;
+
+    // Check amount_out >= min_amount_out
+    require!(amount_out >= min_amount_out, error::Error::MinAmountOut);
+
+    // Check price impact
+    // Trades that reduce the imbalance are always allowed
+    let imbalance_before = lib::get_imbalance(reserve_in, reserve_out);
+    let imbalance_after = lib::get_imbalance(
+        reserve_in_after,
+        reserve_out.checked_sub(amount_out).unwrap(),
+    );
+    require!(
+        imbalance_after <= max_price_impact_bps
+            || imbalance_after <= imbalance_before,
+        error::Error::MaxPriceImpact
+    );
+
     // Transfer token in from user to pool
     lib::transfer(
         &ctx.accounts.token_program,
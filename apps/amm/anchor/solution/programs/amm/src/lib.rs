@@ -12,8 +12,13 @@ pub mod amm {
     pub use super::instructions::*;
     use super::*;
 
-    pub fn init_pool(ctx: Context<InitPool>, fee: u16) -> Result<()> {
-        instructions::init_pool(ctx, fee)?;
+    pub fn init_pool(
+        ctx: Context<InitPool>,
+        fee: u16,
+        max_price_impact_bps: u16,
+        max_fee: u16,
+    ) -> Result<()> {
+        instructions::init_pool(ctx, fee, max_price_impact_bps, max_fee)?;
         Ok(())
     }
 
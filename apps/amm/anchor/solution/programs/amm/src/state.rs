@@ -1,8 +1,13 @@
 use anchor_lang::prelude::*;
 
-#[account]
-#[derive(InitSpace)]
+#[account(zero_copy)]
 pub struct Pool {
     pub mint_a: Pubkey,
     pub mint_b: Pubkey,
+    // Max imbalance of reserves after a swap, 10000 = 100%
+    pub max_price_impact_bps: u16,
+    // Dynamic fee curve
+    // Swap fee rises linearly from the pool fee at balanced reserves
+    // to max_fee at one-sided reserves. Disabled if max_fee <= pool fee.
+    pub max_fee: u16,
 }
@@ -24,6 +24,8 @@ fn test_add_liquidity() {
         pool_a,
         pool_b,
         fee,
+        max_price_impact_bps,
+        max_fee,
         atas_a,
         atas_b,
         atas_pool,
@@ -45,7 +47,11 @@ fn test_add_liquidity() {
             system_program: system_program::ID,
         })
         .signer(&users[0])
-        .args(amm::instruction::InitPool { fee })
+        .args(amm::instruction::InitPool {
+            fee,
+            max_price_impact_bps,
+            max_fee,
+        })
         .send()
         .unwrap();
 
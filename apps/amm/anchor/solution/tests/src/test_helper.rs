@@ -25,6 +25,8 @@ pub struct Test<'a> {
     pub pool_a: Pubkey,
     pub pool_b: Pubkey,
     pub fee: u16,
+    pub max_price_impact_bps: u16,
+    pub max_fee: u16,
     pub atas_a: Vec<Pubkey>,
     pub atas_b: Vec<Pubkey>,
     pub atas_pool: Vec<Pubkey>,
@@ -103,6 +105,8 @@ pub fn set_up<'a>(payer: &'a Keypair) -> Test<'a> {
 
     // Calculate AMM PDA
     let fee: u16 = 30;
+    let max_price_impact_bps = amm::constants::MAX_PRICE_IMPACT;
+    let max_fee: u16 = 0;
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
         &[
             amm::constants::POOL_AUTH_SEED_PREFIX,
@@ -145,6 +149,8 @@ pub fn set_up<'a>(payer: &'a Keypair) -> Test<'a> {
         pool_a,
         pool_b,
         fee,
+        max_price_impact_bps,
+        max_fee,
         atas_a,
         atas_b,
         atas_pool,
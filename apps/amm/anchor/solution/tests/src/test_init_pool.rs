@@ -24,6 +24,8 @@ fn test_init() {
         pool_a,
         pool_b,
         fee,
+        max_price_impact_bps,
+        max_fee,
         atas_a,
         atas_b,
         atas_pool,
@@ -45,11 +47,20 @@ fn test_init() {
             system_program: system_program::ID,
         })
         .signer(&users[0])
-        .args(amm::instruction::InitPool { fee })
+        .args(amm::instruction::InitPool {
+            fee,
+            max_price_impact_bps,
+            max_fee,
+        })
         .send()
         .unwrap();
 
     let pool: amm::state::Pool = program.account(pool_pda).unwrap();
     assert_eq!(pool.mint_a, mint_a, "pool.mint_a");
     assert_eq!(pool.mint_b, mint_b, "pool.mint_b");
+    assert_eq!(
+        pool.max_price_impact_bps, max_price_impact_bps,
+        "pool.max_price_impact_bps"
+    );
+    assert_eq!(pool.max_fee, max_fee, "pool.max_fee");
 }
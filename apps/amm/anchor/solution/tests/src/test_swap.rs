@@ -24,6 +24,8 @@ fn test_swap() {
         pool_a,
         pool_b,
         fee,
+        max_price_impact_bps,
+        max_fee,
         atas_a,
         atas_b,
         atas_pool,
@@ -45,7 +47,11 @@ fn test_swap() {
             system_program: system_program::ID,
         })
         .signer(&users[0])
-        .args(amm::instruction::InitPool { fee })
+        .args(amm::instruction::InitPool {
+            fee,
+            max_price_impact_bps,
+            max_fee,
+        })
         .send()
         .unwrap();
 
@@ -131,3 +137,118 @@ fn test_swap() {
     assert!(pool_a_bal_after > pool_a_bal_before, "pool a balance");
     assert!(pool_b_bal_after < pool_b_bal_before, "pool b balance");
 }
+
+#[test]
+fn test_swap_max_price_impact() {
+    let anchor_wallet = std::env::var("ANCHOR_WALLET").unwrap();
+    let payer = read_keypair_file(&anchor_wallet).unwrap();
+
+    let test_helper::Test {
+        program,
+        token_program,
+        users,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_a,
+        pool_b,
+        mint_pool_pda,
+        fee,
+        max_fee,
+        atas_a,
+        atas_b,
+        atas_pool,
+        ..
+    } = test_helper::set_up(&payer);
+
+    // Init with 10% max imbalance
+    let max_price_impact_bps: u16 = 1000;
+
+    program
+        .request()
+        .accounts(amm::accounts::InitPool {
+            payer: users[0].pubkey(),
+            pool: pool_pda,
+            mint_a,
+            mint_b,
+            pool_a,
+            pool_b,
+            mint_pool: mint_pool_pda,
+            token_program: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&users[0])
+        .args(amm::instruction::InitPool {
+            fee,
+            max_price_impact_bps,
+            max_fee,
+        })
+        .send()
+        .unwrap();
+
+    // Add liquidity
+    program
+        .request()
+        .accounts(amm::accounts::AddLiquidity {
+            payer: users[0].pubkey(),
+            pool: pool_pda,
+            mint_a,
+            mint_b,
+            pool_a,
+            pool_b,
+            mint_pool: mint_pool_pda,
+            payer_a: atas_a[0],
+            payer_b: atas_b[0],
+            payer_liquidity: atas_pool[0],
+            token_program: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&users[0])
+        .args(amm::instruction::AddLiquidity {
+            fee,
+            amount_a: (10.0 * 1e6) as u64,
+            amount_b: (10.0 * 1e6) as u64,
+        })
+        .send()
+        .unwrap();
+
+    let swap = |amount_in: u64| {
+        program
+            .request()
+            .accounts(amm::accounts::Swap {
+                payer: users[1].pubkey(),
+                pool: pool_pda,
+                mint_a,
+                mint_b,
+                pool_a,
+                pool_b,
+                payer_a: atas_a[1],
+                payer_b: atas_b[1],
+                token_program: token::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+            })
+            .signer(&users[1])
+            .args(amm::instruction::Swap {
+                fee,
+                a_for_b: true,
+                amount_in,
+                min_amount_out: 1,
+            })
+            .send()
+    };
+
+    // Reserves after = 12 A and ~8 B, imbalance ~20%
+    assert!(swap((2.0 * 1e6) as u64).is_err(), "price impact > max");
+
+    // Reserves after = 10.5 A and ~9.5 B, imbalance ~5%
+    assert!(swap((0.5 * 1e6) as u64).is_ok(), "price impact <= max");
+
+    assert_eq!(
+        token_helper::get_balance(&token_program, &pool_a).unwrap(),
+        (10.5 * 1e6) as u64,
+        "pool a balance"
+    );
+}
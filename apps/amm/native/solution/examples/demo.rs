@@ -44,6 +44,8 @@ fn main() {
 
     // Pool PDA
     let fee: u16 = 500;
+    let max_price_impact_bps = amm::constants::MAX_PRICE_IMPACT;
+    let max_fee: u16 = 0;
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
         &[
             amm::constants::POOL_AUTH,
@@ -93,6 +95,8 @@ fn main() {
         program_id,
         payer.pubkey(),
         fee,
+        max_price_impact_bps,
+        max_fee,
         mint_a,
         mint_b,
         pool_pda,
@@ -390,6 +394,8 @@ fn create_init_pool_ix(
     program_id: Pubkey,
     payer: Pubkey,
     fee: u16,
+    max_price_impact_bps: u16,
+    max_fee: u16,
     mint_a: Pubkey,
     mint_b: Pubkey,
     pool: Pubkey,
@@ -401,6 +407,8 @@ fn create_init_pool_ix(
 ) -> Instruction {
     let cmd = Cmd::InitPool {
         fee,
+        max_price_impact_bps,
+        max_fee,
         pool_bump,
         mint_pool_bump,
     };
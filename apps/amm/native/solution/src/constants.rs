@@ -1,3 +1,4 @@
 pub const POOL_AUTH: &[u8] = b"pool_auth";
 pub const POOL_MINT: &[u8] = b"pool_mint";
 pub const MAX_POOL_FEE: u16 = 10000;
+pub const MAX_PRICE_IMPACT: u16 = 10000;
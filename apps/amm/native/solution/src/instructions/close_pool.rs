@@ -0,0 +1,142 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use solana_program_pack::Pack;
+use spl_token_interface;
+
+use super::lib;
+use crate::constants;
+use crate::state::Pool;
+
+pub fn close_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee: u16,
+    pool_bump: u8,
+    mint_pool_bump: u8,
+) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+
+    let payer = next_account_info(accounts_iter)?;
+    let pool = next_account_info(accounts_iter)?;
+    let mint_a = next_account_info(accounts_iter)?;
+    let mint_b = next_account_info(accounts_iter)?;
+    let pool_a = next_account_info(accounts_iter)?;
+    let pool_b = next_account_info(accounts_iter)?;
+    // Creator's token accounts, receive what is left in pool_a and pool_b
+    let payer_a = next_account_info(accounts_iter)?;
+    let payer_b = next_account_info(accounts_iter)?;
+    let mint_pool = next_account_info(accounts_iter)?;
+    let token_program = next_account_info(accounts_iter)?;
+    let sys_program = next_account_info(accounts_iter)?;
+
+    // Verify payer is signer
+    assert!(payer.is_signer, "payer not signer");
+
+    // Verify provided pool PDA matches the one calculated by lib::get_pool_pda
+    let expected_pool =
+        lib::get_pool_pda(program_id, mint_a.key, mint_b.key, fee, pool_bump)?;
+    assert!(*pool.key == expected_pool, "Invalid pool PDA");
+
+    // Verify provided mint_pool PDA matches the one calculated by lib::get_mint_pool_pda
+    let expected_mint_pool = lib::get_mint_pool_pda(
+        program_id,
+        mint_a.key,
+        mint_b.key,
+        fee,
+        mint_pool_bump,
+    )?;
+    assert!(
+        *mint_pool.key == expected_mint_pool,
+        "Invalid mint_pool PDA"
+    );
+
+    // Verify token accounts
+    lib::check_token_program(token_program)?;
+    lib::check_ata(token_program, pool_a, pool.key, mint_a.key)?;
+    lib::check_ata(token_program, pool_b, pool.key, mint_b.key)?;
+
+    // Get Pool state
+    let pool_state = {
+        let pool_data = pool.data.borrow();
+        *Pool::load(&pool_data)?
+    };
+
+    // Verify Pool state mint_a = mint_a from accounts_iter
+    assert!(pool_state.mint_a == *mint_a.key, "Invalid mint_a");
+    // Verify Pool state mint_b = mint_b from accounts_iter
+    assert!(pool_state.mint_b == *mint_b.key, "Invalid mint_b");
+    // Verify payer created the pool
+    assert!(pool_state.creator == *payer.key, "payer not pool creator");
+
+    // Check mint_pool supply is 0
+    let mint_pool_account = {
+        let mint_pool_data = mint_pool.data.borrow();
+        spl_token_interface::state::Mint::unpack(&mint_pool_data)
+            .map_err(|_| ProgramError::InvalidAccountData)?
+    };
+    assert!(mint_pool_account.supply == 0, "pool supply > 0");
+
+    let seeds = &[
+        constants::POOL_AUTH,
+        mint_a.key.as_ref(),
+        mint_b.key.as_ref(),
+        &fee.to_le_bytes(),
+        &[pool_bump],
+    ];
+
+    // No liquidity is left, anything in pool_a and pool_b was sent directly
+    // to the pool. Send it to the creator.
+    let amt_a = lib::get_token_balance(pool_a)?;
+    if amt_a > 0 {
+        lib::check_ata(token_program, payer_a, payer.key, mint_a.key)?;
+        lib::transfer_from_pool(
+            token_program,
+            pool_a,
+            payer_a,
+            pool,
+            amt_a,
+            seeds,
+        )?;
+    }
+    let amt_b = lib::get_token_balance(pool_b)?;
+    if amt_b > 0 {
+        lib::check_ata(token_program, payer_b, payer.key, mint_b.key)?;
+        lib::transfer_from_pool(
+            token_program,
+            pool_b,
+            payer_b,
+            pool,
+            amt_b,
+            seeds,
+        )?;
+    }
+
+    // Close pool_a and pool_b
+    lib::close_ata(token_program, pool_a, payer, pool, seeds)?;
+    lib::close_ata(token_program, pool_b, payer, pool, seeds)?;
+
+    // NOTE: mint_pool stays open, SPL token mints cannot be closed.
+    // InitPool reuses it if the pool is created again.
+
+    // Close pool
+    // Get PDA balance and transfer lamports directly
+    let pool_lamports = pool.lamports();
+
+    **pool.try_borrow_mut_lamports()? = 0;
+    **payer.try_borrow_mut_lamports()? = payer
+        .lamports()
+        .checked_add(pool_lamports)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // Clear out data
+    pool.resize(0)?;
+
+    // Assign the account to the System Program
+    pool.assign(sys_program.key);
+
+    Ok(())
+}
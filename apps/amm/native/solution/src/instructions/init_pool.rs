@@ -1,4 +1,4 @@
-use borsh::BorshSerialize;
+use solana_address::Address;
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
     program_error::ProgramError,
@@ -20,6 +20,8 @@ pub fn init_pool(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     fee: u16,
+    max_price_impact_bps: u16,
+    max_fee: u16,
     pool_bump: u8,
     mint_pool_bump: u8,
 ) -> Result<(), ProgramError> {
@@ -40,17 +42,29 @@ pub fn init_pool(
     // Verify payer is signer
     assert!(payer.is_signer, "payer not signer");
 
+    // Verify token_program, ata_program, mint_a and mint_b
+    lib::check_token_program(token_program)?;
+    lib::check_ata_program(ata_program)?;
+    lib::check_mint(token_program, mint_a)?;
+    lib::check_mint(token_program, mint_b)?;
+
     // Check token decimals are equal
     assert!(
         lib::get_decimals(mint_a) == lib::get_decimals(mint_b),
         "decimals mismatch"
     );
 
-    // Verify pool, pool_a, pool_b and mint_pool accounts are not initialized
+    // Check max_price_impact_bps and max_fee
+    assert!(
+        max_price_impact_bps <= constants::MAX_PRICE_IMPACT,
+        "invalid max price impact"
+    );
+    assert!(max_fee <= constants::MAX_POOL_FEE, "invalid max fee");
+
+    // Verify pool, pool_a and pool_b accounts are not initialized
     assert!(pool.lamports() == 0, "pool already initialized");
     assert!(pool_a.lamports() == 0, "pool_a already initialized");
     assert!(pool_b.lamports() == 0, "pool_b already initialized");
-    assert!(mint_pool.lamports() == 0, "mint_pool already initialized");
 
     // Verify provided pool PDA matches the one calculated by lib::get_pool_pda
     let expected_pool =
@@ -70,6 +84,14 @@ pub fn init_pool(
         "Invalid mint_pool PDA"
     );
 
+    // Verify pool_a and pool_b are the pool's associated token accounts
+    if *pool_a.key != lib::get_ata(pool.key, mint_a.key) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if *pool_b.key != lib::get_ata(pool.key, mint_b.key) {
+        return Err(ProgramError::InvalidArgument);
+    }
+
     // Create pool PDA
     let rent = Rent::get()?;
 
@@ -115,35 +137,53 @@ pub fn init_pool(
         rent_sysvar,
     )?;
 
-    // Create mint_pool PDA
-    invoke_signed(
-        &system_instruction::create_account(
-            payer.key,
-            mint_pool.key,
-            rent.minimum_balance(spl_token_interface::state::Mint::LEN),
-            spl_token_interface::state::Mint::LEN as u64,
-            token_program.key,
-        ),
-        &[payer.clone(), mint_pool.clone(), sys_program.clone()],
-        &[&[
-            constants::POOL_MINT,
-            mint_a.key.as_ref(),
-            mint_b.key.as_ref(),
-            fee.to_le_bytes().as_ref(),
-            &[mint_pool_bump],
-        ]],
-    )?;
-
-    // Initialize mint_pool
-    lib::init_mint(token_program, mint_pool, pool, rent_sysvar)?;
+    // mint_pool is left open when a pool is closed, reuse it if no LP
+    // token is in circulation and the pool PDA is still its authority
+    if mint_pool.lamports() > 0 {
+        lib::check_mint(token_program, mint_pool)?;
+        let mint_pool_account = {
+            let mint_pool_data = mint_pool.data.borrow();
+            spl_token_interface::state::Mint::unpack(&mint_pool_data)
+                .map_err(|_| ProgramError::InvalidAccountData)?
+        };
+        assert!(mint_pool_account.supply == 0, "pool supply > 0");
+        assert!(
+            Option::<Address>::from(mint_pool_account.mint_authority)
+                == Some(Address::from(pool.key.to_bytes())),
+            "Invalid mint_pool authority"
+        );
+    } else {
+        // Create mint_pool PDA
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                mint_pool.key,
+                rent.minimum_balance(spl_token_interface::state::Mint::LEN),
+                spl_token_interface::state::Mint::LEN as u64,
+                token_program.key,
+            ),
+            &[payer.clone(), mint_pool.clone(), sys_program.clone()],
+            &[&[
+                constants::POOL_MINT,
+                mint_a.key.as_ref(),
+                mint_b.key.as_ref(),
+                fee.to_le_bytes().as_ref(),
+                &[mint_pool_bump],
+            ]],
+        )?;
+
+        // Initialize mint_pool
+        lib::init_mint(token_program, mint_pool, pool, rent_sysvar)?;
+    }
 
     // Initialize pool state
     let mut data = pool.data.borrow_mut();
-    let pool_state = Pool {
-        mint_a: *mint_a.key,
-        mint_b: *mint_b.key,
-    };
-    pool_state.serialize(&mut &mut data[..])?;
+    let pool_state = Pool::init(&mut data)?;
+    pool_state.mint_a = *mint_a.key;
+    pool_state.mint_b = *mint_b.key;
+    pool_state.creator = *payer.key;
+    pool_state.max_price_impact_bps = max_price_impact_bps;
+    pool_state.max_fee = max_fee;
 
     Ok(())
 }
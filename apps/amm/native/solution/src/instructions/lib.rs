@@ -185,6 +185,65 @@ pub fn get_ata(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
     Pubkey::from(addr.to_bytes())
 }
 
+// Verify token_program is the SPL token program
+pub fn check_token_program<'a>(
+    token_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    if token_program.key.to_bytes() != spl_token_interface::ID.to_bytes() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+// Verify ata_program is the associated token account program
+pub fn check_ata_program<'a>(ata_program: &AccountInfo<'a>) -> ProgramResult {
+    if ata_program.key.to_bytes() != spl_ata::program::ID.to_bytes() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+// Verify mint is a mint account owned by token_program
+pub fn check_mint<'a>(
+    token_program: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+) -> ProgramResult {
+    if mint.owner != token_program.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+    spl_token_interface::state::Mint::unpack(&mint.data.borrow())
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    Ok(())
+}
+
+// Verify ata is the associated token account of wallet for mint
+pub fn check_ata<'a>(
+    token_program: &AccountInfo<'a>,
+    ata: &AccountInfo<'a>,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+) -> ProgramResult {
+    if *ata.key != get_ata(wallet, mint) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    if ata.owner != token_program.key {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let account =
+        spl_token_interface::state::Account::unpack(&ata.data.borrow())
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    if account.mint.to_bytes() != mint.to_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if account.owner.to_bytes() != wallet.to_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+
+    Ok(())
+}
+
 pub fn transfer<'a>(
     token_program: &AccountInfo<'a>,
     src: &AccountInfo<'a>,
@@ -380,3 +439,33 @@ pub fn get_decimals<'a>(mint: &AccountInfo<'a>) -> Result<u8, ProgramError> {
 
     Ok(mint_data.decimals)
 }
+
+// Imbalance of reserves in basis points
+// 0 = balanced, constants::MAX_PRICE_IMPACT = all liquidity on one side
+pub fn get_imbalance(
+    reserve_a: u64,
+    reserve_b: u64,
+) -> Result<u16, ProgramError> {
+    let total = (reserve_a as u128) + (reserve_b as u128);
+    if total == 0 {
+        return Ok(0);
+    }
+
+    let diff = (reserve_a as u128).abs_diff(reserve_b as u128);
+    let imbalance = diff * (constants::MAX_PRICE_IMPACT as u128) / total;
+
+    u16::try_from(imbalance).map_err(|_| ProgramError::ArithmeticOverflow)
+}
+
+// Swap fee at the given imbalance
+// Rises linearly from fee at balanced reserves to max_fee at one-sided reserves
+pub fn get_fee(fee: u16, max_fee: u16, imbalance: u16) -> u16 {
+    if max_fee <= fee {
+        return fee;
+    }
+
+    let extra = (max_fee - fee) as u32 * imbalance as u32
+        / constants::MAX_PRICE_IMPACT as u32;
+
+    fee + extra as u16
+}
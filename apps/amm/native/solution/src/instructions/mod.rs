@@ -1,10 +1,12 @@
 pub mod add_liquidity;
+pub mod close_pool;
 pub mod init_pool;
 pub mod lib;
 pub mod remove_liquidity;
 pub mod swap;
 
 pub use add_liquidity::*;
+pub use close_pool::*;
 pub use init_pool::*;
 pub use remove_liquidity::*;
 pub use swap::*;
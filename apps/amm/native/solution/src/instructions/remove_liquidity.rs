@@ -1,4 +1,3 @@
-use borsh::BorshDeserialize;
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
     entrypoint::ProgramResult,
@@ -60,7 +59,7 @@ pub fn remove_liquidity(
     // Get Pool state
     let pool_state = {
         let pool_data = pool.data.borrow();
-        Pool::try_from_slice(&pool_data)?
+        *Pool::load(&pool_data)?
     };
 
     // Verify Pool state mint_a = mint_a from accounts_iter
@@ -68,6 +67,14 @@ pub fn remove_liquidity(
     // Verify Pool state mint_b = mint_b from accounts_iter
     assert!(pool_state.mint_b == *mint_b.key, "Invalid mint_b");
 
+    // Verify token accounts
+    lib::check_token_program(token_program)?;
+    lib::check_ata(token_program, pool_a, pool.key, mint_a.key)?;
+    lib::check_ata(token_program, pool_b, pool.key, mint_b.key)?;
+    lib::check_ata(token_program, payer_a, payer.key, mint_a.key)?;
+    lib::check_ata(token_program, payer_b, payer.key, mint_b.key)?;
+    lib::check_ata(token_program, payer_liq, payer.key, mint_pool.key)?;
+
     // Get pool_a and pool_b amounts
     let pool_a_account = {
         let pool_a_data = pool_a.data.borrow();
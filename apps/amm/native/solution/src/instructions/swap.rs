@@ -1,4 +1,3 @@
-use borsh::BorshDeserialize;
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
     entrypoint::ProgramResult,
@@ -42,7 +41,7 @@ pub fn swap(
     // Get Pool state
     let pool_state = {
         let pool_data = pool.data.borrow();
-        Pool::try_from_slice(&pool_data)?
+        *Pool::load(&pool_data)?
     };
 
     // Verify Pool state mint_a = mint_a from accounts_iter
@@ -50,10 +49,41 @@ pub fn swap(
     // Verify Pool state mint_b = mint_b from accounts_iter
     assert!(pool_state.mint_b == *mint_b.key, "Invalid mint_b");
 
+    // Verify token accounts
+    lib::check_token_program(token_program)?;
+    lib::check_ata(token_program, pool_a, pool.key, mint_a.key)?;
+    lib::check_ata(token_program, pool_b, pool.key, mint_b.key)?;
+    lib::check_ata(token_program, payer_a, payer.key, mint_a.key)?;
+    lib::check_ata(token_program, payer_b, payer.key, mint_b.key)?;
+
+    // Determine swap direction
+    let (mint_in, mint_out, pool_in, pool_out, payer_in, payer_out) = if a_for_b
+    {
+        (mint_a, mint_b, pool_a, pool_b, payer_a, payer_b)
+    } else {
+        (mint_b, mint_a, pool_b, pool_a, payer_b, payer_a)
+    };
+
+    // Get pool reserves
+    let reserve_in = lib::get_token_balance(pool_in)?;
+    let reserve_out = lib::get_token_balance(pool_out)?;
+
+    let reserve_in_after = reserve_in
+        .checked_add(amount_in)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // Calculate swap fee
+    // Dynamic fee is based on the imbalance of the trade before fees
+    let imbalance = lib::get_imbalance(
+        reserve_in_after,
+        reserve_out.saturating_sub(amount_in),
+    )?;
+    let swap_fee = lib::get_fee(fee, pool_state.max_fee, imbalance);
+
     // Calculate amount out with fee
     let mut amount_out = amount_in;
     let amount_out_fee = amount_out
-        .checked_mul(fee as u64)
+        .checked_mul(swap_fee as u64)
         .ok_or(ProgramError::ArithmeticOverflow)?
         .checked_div(constants::MAX_POOL_FEE as u64)
         .ok_or(ProgramError::ArithmeticOverflow)?;
@@ -65,13 +95,19 @@ pub fn swap(
     // Check amount out is >= minimum amount specified by payer
     assert!(amount_out >= min_amount_out, "amount out < min");
 
-    // Determine swap direction
-    let (mint_in, mint_out, pool_in, pool_out, payer_in, payer_out) = if a_for_b
-    {
-        (mint_a, mint_b, pool_a, pool_b, payer_a, payer_b)
-    } else {
-        (mint_b, mint_a, pool_b, pool_a, payer_b, payer_a)
-    };
+    // Check price impact
+    // Trades that reduce the imbalance are always allowed
+    let reserve_out_after = reserve_out
+        .checked_sub(amount_out)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let imbalance_before = lib::get_imbalance(reserve_in, reserve_out)?;
+    let imbalance_after =
+        lib::get_imbalance(reserve_in_after, reserve_out_after)?;
+    assert!(
+        imbalance_after <= pool_state.max_price_impact_bps
+            || imbalance_after <= imbalance_before,
+        "price impact > max"
+    );
 
     // Transfer token from payer to pool
     lib::transfer(token_program, payer_in, pool_in, payer, amount_in)?;
@@ -12,6 +12,8 @@ pub mod state;
 pub enum Cmd {
     InitPool {
         fee: u16,
+        max_price_impact_bps: u16,
+        max_fee: u16,
         pool_bump: u8,
         mint_pool_bump: u8,
     },
@@ -37,8 +39,14 @@ pub enum Cmd {
         min_amount_out: u64,
         pool_bump: u8,
     },
+    ClosePool {
+        fee: u16,
+        pool_bump: u8,
+        mint_pool_bump: u8,
+    },
 }
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -51,6 +59,8 @@ pub fn process_instruction(
     match ix {
         Cmd::InitPool {
             fee,
+            max_price_impact_bps,
+            max_fee,
             pool_bump,
             mint_pool_bump,
         } => {
@@ -58,6 +68,8 @@ pub fn process_instruction(
                 program_id,
                 accounts,
                 fee,
+                max_price_impact_bps,
+                max_fee,
                 pool_bump,
                 mint_pool_bump,
             )?;
@@ -115,6 +127,19 @@ pub fn process_instruction(
                 pool_bump,
             )?;
         }
+        Cmd::ClosePool {
+            fee,
+            pool_bump,
+            mint_pool_bump,
+        } => {
+            instructions::close_pool(
+                program_id,
+                accounts,
+                fee,
+                pool_bump,
+                mint_pool_bump,
+            )?;
+        }
     }
 
     Ok(())
@@ -1,13 +1,68 @@
-use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+// Zero copy account, stored after an 8 byte discriminator
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Pool {
     pub mint_a: Pubkey,
     pub mint_b: Pubkey,
+    // Pool creator, allowed to close the pool once it is empty
+    pub creator: Pubkey,
+    // Max imbalance of reserves after a swap, 10000 = 100%
+    pub max_price_impact_bps: u16,
+    // Dynamic fee curve
+    // Swap fee rises linearly from the pool fee at balanced reserves
+    // to max_fee at one-sided reserves. Disabled if max_fee <= pool fee.
+    pub max_fee: u16,
 }
 
 impl Pool {
-    // 32 + 32
-    pub const SPACE: u64 = 64;
+    // sha256("account:Pool")[..8], same as Anchor
+    pub const DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+    // 8 + 32 + 32 + 32 + 2 + 2
+    pub const SPACE: u64 = 108;
+
+    // Write discriminator to uninitialized account data
+    pub fn init(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if data.len() != Self::SPACE as usize {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if data[..8] != [0; 8] {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        data[..8].copy_from_slice(&Self::DISCRIMINATOR);
+        Self::load_mut(data)
+    }
+
+    pub fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+        if data.len() != Self::SPACE as usize || data[..8] != Self::DISCRIMINATOR
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        bytemuck::try_from_bytes(&data[8..])
+            .map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    pub fn load_mut(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if data.len() != Self::SPACE as usize || data[..8] != Self::DISCRIMINATOR
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        bytemuck::try_from_bytes_mut(&mut data[8..])
+            .map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    // Copy out of account data that may not be aligned (clients and tests)
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != Self::SPACE as usize || data[..8] != Self::DISCRIMINATOR
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        bytemuck::try_pod_read_unaligned(&data[8..])
+            .map_err(|_| ProgramError::InvalidAccountData)
+    }
 }
+
+const _: () =
+    assert!(Pool::SPACE as usize == 8 + std::mem::size_of::<Pool>());
@@ -63,6 +63,8 @@ pub fn create_init_pool_ix(
     program_id: Pubkey,
     payer: Pubkey,
     fee: u16,
+    max_price_impact_bps: u16,
+    max_fee: u16,
     mint_a: Pubkey,
     mint_b: Pubkey,
     pool: Pubkey,
@@ -74,6 +76,8 @@ pub fn create_init_pool_ix(
 ) -> Instruction {
     let cmd = Cmd::InitPool {
         fee,
+        max_price_impact_bps,
+        max_fee,
         pool_bump,
         mint_pool_bump,
     };
@@ -419,6 +423,88 @@ pub fn create_swap_ix(
     )
 }
 
+pub fn create_close_pool_ix(
+    program_id: Pubkey,
+    payer: Pubkey,
+    fee: u16,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    pool: Pubkey,
+    pool_bump: u8,
+    mint_pool: Pubkey,
+    mint_pool_bump: u8,
+    pool_a: Pubkey,
+    pool_b: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::ClosePool {
+        fee,
+        pool_bump,
+        mint_pool_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: pool,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_a,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_b,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: pool_a,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: pool_b,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_a, &payer),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_b, &payer),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_pool,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
 #[derive(Debug)]
 pub struct Test {
     pub program_id: Pubkey,
@@ -429,6 +515,8 @@ pub struct Test {
     pub atas_a: Vec<Pubkey>,
     pub atas_b: Vec<Pubkey>,
     pub fee: u16,
+    pub max_price_impact_bps: u16,
+    pub max_fee: u16,
     pub pool_pda: Pubkey,
     pub pool_bump: u8,
     pub mint_pool_pda: Pubkey,
@@ -462,6 +550,8 @@ pub fn setup(svm: &mut LiteSVM) -> Test {
 
     // Pool PDA
     let fee: u16 = 500;
+    let max_price_impact_bps = amm::constants::MAX_PRICE_IMPACT;
+    let max_fee: u16 = 0;
     let (pool_pda, pool_bump) = Pubkey::find_program_address(
         &[
             amm::constants::POOL_AUTH,
@@ -513,6 +603,8 @@ pub fn setup(svm: &mut LiteSVM) -> Test {
         atas_a,
         atas_b,
         fee,
+        max_price_impact_bps,
+        max_fee,
         pool_pda,
         pool_bump,
         mint_pool_pda,
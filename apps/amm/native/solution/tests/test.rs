@@ -1,4 +1,3 @@
-use borsh::BorshDeserialize;
 use litesvm::LiteSVM;
 use solana_program::sysvar::clock::Clock;
 use solana_sdk::{signature::Signer, transaction::Transaction};
@@ -7,8 +6,9 @@ use amm::state::Pool;
 
 mod helper;
 use helper::{
-    Test, create_add_liquidity_ix, create_init_pool_ix,
-    create_remove_liquidity_ix, create_swap_ix, get_token_balance, setup,
+    Test, create_add_liquidity_ix, create_ata, create_close_pool_ix,
+    create_init_pool_ix, create_remove_liquidity_ix, create_swap_ix,
+    get_token_balance, mint_to, setup,
 };
 
 #[test]
@@ -23,6 +23,8 @@ fn test_init_pool() {
         atas_a,
         atas_b,
         fee,
+        max_price_impact_bps,
+        max_fee,
         pool_pda,
         pool_bump,
         mint_pool_pda,
@@ -36,6 +38,8 @@ fn test_init_pool() {
         program_id,
         payer.pubkey(),
         fee,
+        max_price_impact_bps,
+        max_fee,
         mint_a,
         mint_b,
         pool_pda,
@@ -55,10 +59,13 @@ fn test_init_pool() {
     assert!(res.is_ok());
 
     let data = svm.get_account(&pool_pda).unwrap().data;
-    let pool = Pool::try_from_slice(&data).unwrap();
+    let pool = Pool::unpack(&data).unwrap();
 
     assert_eq!(pool.mint_a, mint_a);
     assert_eq!(pool.mint_b, mint_b);
+    assert_eq!(pool.creator, payer.pubkey());
+    assert_eq!(pool.max_price_impact_bps, max_price_impact_bps);
+    assert_eq!(pool.max_fee, max_fee);
     assert!(svm.get_balance(&pool_pda).unwrap() > 0);
     assert!(svm.get_balance(&pool_a).unwrap() > 0);
     assert!(svm.get_balance(&pool_b).unwrap() > 0);
@@ -77,6 +84,8 @@ fn test_add_liquidity() {
         atas_a,
         atas_b,
         fee,
+        max_price_impact_bps,
+        max_fee,
         pool_pda,
         pool_bump,
         mint_pool_pda,
@@ -91,6 +100,8 @@ fn test_add_liquidity() {
         program_id,
         payer.pubkey(),
         fee,
+        max_price_impact_bps,
+        max_fee,
         mint_a,
         mint_b,
         pool_pda,
@@ -157,6 +168,8 @@ fn test_remove_liquidity() {
         atas_a,
         atas_b,
         fee,
+        max_price_impact_bps,
+        max_fee,
         pool_pda,
         pool_bump,
         mint_pool_pda,
@@ -171,6 +184,8 @@ fn test_remove_liquidity() {
         program_id,
         payer.pubkey(),
         fee,
+        max_price_impact_bps,
+        max_fee,
         mint_a,
         mint_b,
         pool_pda,
@@ -279,6 +294,8 @@ fn test_swap() {
         atas_a,
         atas_b,
         fee,
+        max_price_impact_bps,
+        max_fee,
         pool_pda,
         pool_bump,
         mint_pool_pda,
@@ -293,6 +310,8 @@ fn test_swap() {
         program_id,
         payer.pubkey(),
         fee,
+        max_price_impact_bps,
+        max_fee,
         mint_a,
         mint_b,
         pool_pda,
@@ -376,6 +395,9 @@ fn test_swap() {
         svm.latest_blockhash(),
     ));
     assert!(res.is_ok());
+    // CU budget of Swap, Pool is read in place
+    let cu = res.unwrap().compute_units_consumed;
+    assert!(cu < 50_000, "Swap used {cu} CU");
 
     let user_a_bal_after = get_token_balance(&svm, &atas_a[1]);
     let user_b_bal_after = get_token_balance(&svm, &atas_b[1]);
@@ -387,3 +409,703 @@ fn test_swap() {
     assert!(pool_a_bal_before < pool_a_bal_after);
     assert!(pool_b_bal_before > pool_b_bal_after);
 }
+
+#[test]
+fn test_swap_max_price_impact() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        users,
+        mint_a,
+        mint_b,
+        atas_a,
+        atas_b,
+        fee,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        atas_liq,
+        ..
+    } = setup(&mut svm);
+
+    // Init pool with 10% max imbalance
+    let max_price_impact_bps: u16 = 1000;
+    let max_fee: u16 = 0;
+
+    let ix = create_init_pool_ix(
+        program_id,
+        payer.pubkey(),
+        fee,
+        max_price_impact_bps,
+        max_fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Add liquidity
+    let amt_a = (10.0 * 1e6) as u64;
+    let amt_b = (10.0 * 1e6) as u64;
+
+    let ix = create_add_liquidity_ix(
+        program_id,
+        users[0].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        amt_a,
+        amt_b,
+        atas_a[0],
+        atas_b[0],
+        atas_liq[0],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[0].pubkey()),
+        &[&users[0]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Swap that leaves reserves more than 10% imbalanced
+    // Reserves after = 12 A and 8.1 B, imbalance = 19.4%
+    let ix = create_swap_ix(
+        program_id,
+        users[1].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        pool_a,
+        pool_b,
+        atas_a[1],
+        atas_b[1],
+        true,
+        (2.0 * 1e6) as u64,
+        1,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[1].pubkey()),
+        &[&users[1]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Swap within the limit
+    // Reserves after = 10.5 A and 9.525 B, imbalance = 4.8%
+    let ix = create_swap_ix(
+        program_id,
+        users[1].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        pool_a,
+        pool_b,
+        atas_a[1],
+        atas_b[1],
+        true,
+        (0.5 * 1e6) as u64,
+        1,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[1].pubkey()),
+        &[&users[1]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(get_token_balance(&svm, &pool_a), 10_500_000);
+    assert_eq!(get_token_balance(&svm, &pool_b), 9_525_000);
+}
+
+#[test]
+fn test_swap_dynamic_fee() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        users,
+        mint_a,
+        mint_b,
+        atas_a,
+        atas_b,
+        fee,
+        max_price_impact_bps,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        atas_liq,
+        ..
+    } = setup(&mut svm);
+
+    // Init pool with fee rising from 5% to 20%
+    let max_fee: u16 = 2000;
+
+    let ix = create_init_pool_ix(
+        program_id,
+        payer.pubkey(),
+        fee,
+        max_price_impact_bps,
+        max_fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Add liquidity
+    let amt_a = (10.0 * 1e6) as u64;
+    let amt_b = (10.0 * 1e6) as u64;
+
+    let ix = create_add_liquidity_ix(
+        program_id,
+        users[0].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        amt_a,
+        amt_b,
+        atas_a[0],
+        atas_b[0],
+        atas_liq[0],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[0].pubkey()),
+        &[&users[0]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Swap
+    // Imbalance before fee = (11 - 9) / 20 = 10%
+    // Fee = 5% + (20% - 5%) * 10% = 6.5%
+    let amt_in = 1e6 as u64;
+    let user_b_bal_before = get_token_balance(&svm, &atas_b[1]);
+
+    let ix = create_swap_ix(
+        program_id,
+        users[1].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        pool_a,
+        pool_b,
+        atas_a[1],
+        atas_b[1],
+        true,
+        amt_in,
+        1,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[1].pubkey()),
+        &[&users[1]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let user_b_bal_after = get_token_balance(&svm, &atas_b[1]);
+    assert_eq!(user_b_bal_after - user_b_bal_before, 935_000);
+}
+
+#[test]
+fn test_invalid_token_accounts() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        users,
+        mint_a,
+        mint_b,
+        atas_a,
+        atas_b,
+        fee,
+        max_price_impact_bps,
+        max_fee,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        atas_liq,
+    } = setup(&mut svm);
+
+    // Init pool
+    let ix = create_init_pool_ix(
+        program_id,
+        payer.pubkey(),
+        fee,
+        max_price_impact_bps,
+        max_fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Add liquidity - pool_a substituted with user's own token account
+    let amt_a = (10.0 * 1e6) as u64;
+    let amt_b = (10.0 * 1e6) as u64;
+
+    let ix = create_add_liquidity_ix(
+        program_id,
+        users[0].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        atas_a[0],
+        pool_b,
+        amt_a,
+        amt_b,
+        atas_a[0],
+        atas_b[0],
+        atas_liq[0],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[0].pubkey()),
+        &[&users[0]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Add liquidity
+    let ix = create_add_liquidity_ix(
+        program_id,
+        users[0].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        amt_a,
+        amt_b,
+        atas_a[0],
+        atas_b[0],
+        atas_liq[0],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[0].pubkey()),
+        &[&users[0]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let a_for_b = true;
+    let amt_in = 1e6 as u64;
+    let min_amt_out = 0;
+
+    // Swap - pool_a substituted with user's own token account
+    let ix = create_swap_ix(
+        program_id,
+        users[1].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        atas_a[1],
+        pool_b,
+        atas_a[1],
+        atas_b[1],
+        a_for_b,
+        amt_in,
+        min_amt_out,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[1].pubkey()),
+        &[&users[1]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Swap - payer_b with wrong mint
+    let ix = create_swap_ix(
+        program_id,
+        users[1].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        pool_a,
+        pool_b,
+        atas_a[1],
+        atas_a[1],
+        a_for_b,
+        amt_in,
+        min_amt_out,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[1].pubkey()),
+        &[&users[1]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Swap - payer_b owned by another user
+    let ix = create_swap_ix(
+        program_id,
+        users[1].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        pool_a,
+        pool_b,
+        atas_a[1],
+        atas_b[0],
+        a_for_b,
+        amt_in,
+        min_amt_out,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[1].pubkey()),
+        &[&users[1]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    assert_eq!(get_token_balance(&svm, &pool_a), amt_a);
+    assert_eq!(get_token_balance(&svm, &pool_b), amt_b);
+}
+
+#[test]
+fn test_close_pool() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        users,
+        mint_a,
+        mint_b,
+        atas_a,
+        atas_b,
+        fee,
+        max_price_impact_bps,
+        max_fee,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        atas_liq,
+    } = setup(&mut svm);
+
+    // Init pool
+    let ix = create_init_pool_ix(
+        program_id,
+        payer.pubkey(),
+        fee,
+        max_price_impact_bps,
+        max_fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Add liquidity
+    let amt_a = (10.0 * 1e6) as u64;
+    let amt_b = (10.0 * 1e6) as u64;
+
+    let ix = create_add_liquidity_ix(
+        program_id,
+        users[0].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        amt_a,
+        amt_b,
+        atas_a[0],
+        atas_b[0],
+        atas_liq[0],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[0].pubkey()),
+        &[&users[0]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Close pool with supply and reserves
+    let ix = create_close_pool_ix(
+        program_id,
+        payer.pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Remove all liquidity
+    let ix = create_remove_liquidity_ix(
+        program_id,
+        users[0].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        amt_a + amt_b,
+        1,
+        1,
+        atas_a[0],
+        atas_b[0],
+        atas_liq[0],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[0].pubkey()),
+        &[&users[0]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Close pool from an account that is not the creator
+    let ix = create_close_pool_ix(
+        program_id,
+        users[0].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[0].pubkey()),
+        &[&users[0]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Anyone can send tokens to pool_a and pool_b, they go to the creator
+    let payer_a = create_ata(&mut svm, &payer, &payer.pubkey(), &mint_a);
+    let payer_b = create_ata(&mut svm, &payer, &payer.pubkey(), &mint_b);
+    mint_to(&mut svm, &payer, &mint_a, &pool_a, 1);
+    mint_to(&mut svm, &payer, &mint_b, &pool_b, 2);
+    let pool_a_bal = get_token_balance(&svm, &pool_a);
+    let pool_b_bal = get_token_balance(&svm, &pool_b);
+
+    // Close pool
+    let payer_bal_before = svm.get_balance(&payer.pubkey()).unwrap();
+
+    let ix = create_close_pool_ix(
+        program_id,
+        payer.pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(svm.get_balance(&pool_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&pool_a).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&pool_b).unwrap_or(0), 0);
+    assert!(svm.get_balance(&payer.pubkey()).unwrap() > payer_bal_before);
+    assert_eq!(get_token_balance(&svm, &payer_a), pool_a_bal);
+    assert_eq!(get_token_balance(&svm, &payer_b), pool_b_bal);
+
+    // Pool can be created again, mint_pool is reused
+    svm.expire_blockhash();
+    let ix = create_init_pool_ix(
+        program_id,
+        payer.pubkey(),
+        fee,
+        max_price_impact_bps,
+        max_fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Add liquidity to the new pool
+    svm.expire_blockhash();
+    let ix = create_add_liquidity_ix(
+        program_id,
+        users[0].pubkey(),
+        fee,
+        mint_a,
+        mint_b,
+        pool_pda,
+        pool_bump,
+        mint_pool_pda,
+        mint_pool_bump,
+        pool_a,
+        pool_b,
+        amt_a,
+        amt_b,
+        atas_a[0],
+        atas_b[0],
+        atas_liq[0],
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&users[0].pubkey()),
+        &[&users[0]],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+    assert_eq!(get_token_balance(&svm, &pool_a), amt_a);
+    assert_eq!(get_token_balance(&svm, &pool_b), amt_b);
+}
@@ -20,4 +20,18 @@ pub enum Error {
     InvalidCurrentPrice,
     #[msg("Max price")]
     MaxPrice,
+    #[msg("Invalid buy amount")]
+    InvalidBuyAmount,
+    #[msg("Invalid auction id")]
+    InvalidId,
+    #[msg("Invalid price curve")]
+    InvalidCurve,
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Fee above cap")]
+    InvalidFee,
+    #[msg("Missing token account")]
+    MissingTokenAccount,
+    #[msg("Auction started")]
+    AuctionStarted,
 }
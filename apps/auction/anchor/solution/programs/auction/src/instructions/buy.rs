@@ -1,16 +1,19 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token_interface::{
-        close_account, CloseAccount, Mint, TokenAccount, TokenInterface,
+    associated_token::{
+        self, get_associated_token_address_with_program_id, AssociatedToken,
     },
+    token::spl_token::native_mint,
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 use super::lib;
 use crate::error;
+use crate::price::{pay_amount, price_at};
 use crate::state;
 
 #[derive(Accounts)]
+#[instruction(id: u64)]
 pub struct Buy<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
@@ -18,7 +21,10 @@ pub struct Buy<'info> {
     #[account(mut)]
     pub seller: UncheckedAccount<'info>,
 
+    // Writable to harvest withheld transfer fees before closing
+    #[account(mut, mint::token_program = token_program_sell)]
     pub mint_sell: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = token_program_buy)]
     pub mint_buy: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -27,31 +33,35 @@ pub struct Buy<'info> {
             state::Auction::SEED_PREFIX,
             seller.key().as_ref(),
             mint_sell.key().as_ref(),
-            mint_buy.key().as_ref()
+            mint_buy.key().as_ref(),
+            id.to_le_bytes().as_ref()
         ],
         bump,
-        close = seller,
     )]
-    pub auction: Account<'info, state::Auction>,
+    pub auction: AccountLoader<'info, state::Auction>,
 
     #[account(
         mut,
         associated_token::mint = mint_sell,
         associated_token::authority = auction,
+        associated_token::token_program = token_program_sell,
     )]
     pub auction_sell_ata: InterfaceAccount<'info, TokenAccount>,
 
+    // mint_buy ATAs are not used if the auction is paid in SOL
     #[account(
         mut,
         associated_token::mint = mint_buy,
         associated_token::authority = buyer,
+        associated_token::token_program = token_program_buy,
     )]
-    pub buyer_buy_ata: InterfaceAccount<'info, TokenAccount>,
+    pub buyer_buy_ata: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         associated_token::mint = mint_sell,
         associated_token::authority = buyer,
+        associated_token::token_program = token_program_sell,
     )]
     pub buyer_sell_ata: InterfaceAccount<'info, TokenAccount>,
 
@@ -59,19 +69,54 @@ pub struct Buy<'info> {
         mut,
         associated_token::mint = mint_buy,
         associated_token::authority = seller,
+        associated_token::token_program = token_program_buy,
     )]
-    pub seller_buy_ata: InterfaceAccount<'info, TokenAccount>,
+    pub seller_buy_ata: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    pub token_program: Interface<'info, TokenInterface>,
+    /// CHECK: ATA of seller for mint_sell, receives what is left in
+    /// auction_sell_ata when the auction sells out. Created if needed.
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &seller.key(),
+            &mint_sell.key(),
+            &token_program_sell.key(),
+        ),
+    )]
+    pub seller_sell_ata: UncheckedAccount<'info>,
+
+    pub token_program_sell: Interface<'info, TokenInterface>,
+    pub token_program_buy: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+
+    #[account(seeds = [state::Config::SEED_PREFIX], bump)]
+    pub config: Account<'info, state::Config>,
+
+    /// CHECK: Receiver of the protocol fee, checked against config
+    #[account(mut, address = config.treasury)]
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = mint_buy,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program_buy,
+    )]
+    pub treasury_buy_ata: Option<InterfaceAccount<'info, TokenAccount>>,
 }
 
-pub fn buy(ctx: Context<Buy>, max_price: u64) -> Result<()> {
+pub fn buy<'info>(
+    ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
+    id: u64,
+    max_price: u64,
+    buy_amt: u64,
+) -> Result<()> {
     let clock = Clock::get()?;
     let now = u64::try_from(clock.unix_timestamp).unwrap();
 
-    let auction = &mut ctx.accounts.auction;
+    let auction = *ctx.accounts.auction.load()?;
 
     // Check auction has started
     require!(auction.start_time <= now, error::Error::AuctionNotStarted);
@@ -79,11 +124,7 @@ pub fn buy(ctx: Context<Buy>, max_price: u64) -> Result<()> {
     require!(now < auction.end_time, error::Error::AuctionEnded);
 
     // Calculate price
-    let price_decrease = (auction.start_price - auction.end_price)
-        * (now - auction.start_time)
-        / (auction.end_time - auction.start_time);
-
-    let price = auction.start_price - price_decrease;
+    let price = price_at(&auction, now)?;
 
     // Check current price is greater than or equal to end_price
     require!(
@@ -94,47 +135,151 @@ pub fn buy(ctx: Context<Buy>, max_price: u64) -> Result<()> {
     // Check current price is less than or equal to max_price
     require!(price <= max_price, error::Error::MaxPrice);
 
+    // Check 0 < buy_amt <= remaining
+    require!(
+        0 < buy_amt && buy_amt <= auction.remaining,
+        error::Error::InvalidBuyAmount
+    );
+
     // Calculate amount of buy token to send to seller
-    let sell_amt = ctx.accounts.auction_sell_ata.amount;
-    let buy_amt = sell_amt * price / (1e6 as u64);
-
-    // Send buy token to seller
-    lib::transfer(
-        &ctx.accounts.token_program,
-        &ctx.accounts.buyer_buy_ata,
-        &ctx.accounts.seller_buy_ata,
-        &ctx.accounts.buyer,
+    // Round up so that small buys can not be paid with 0
+    let pay_amt = pay_amount(
         buy_amt,
+        price,
+        ctx.accounts.mint_sell.decimals,
+        ctx.accounts.mint_buy.decimals,
     )?;
 
+    let fee = ctx.accounts.config.fee(pay_amt);
+    // Native mint_buy is paid in lamports through the system program
+    if ctx.accounts.mint_buy.key() == native_mint::ID {
+        // Send protocol fee to treasury
+        if fee > 0 {
+            lib::transfer_lamports(
+                &ctx.accounts.system_program,
+                &ctx.accounts.buyer,
+                &ctx.accounts.treasury,
+                fee,
+            )?;
+        }
+        // Send the rest of the lamports to seller
+        lib::transfer_lamports(
+            &ctx.accounts.system_program,
+            &ctx.accounts.buyer,
+            &ctx.accounts.seller,
+            pay_amt - fee,
+        )?;
+    } else {
+        let buyer_buy_ata = ctx
+            .accounts
+            .buyer_buy_ata
+            .as_ref()
+            .ok_or(error::Error::MissingTokenAccount)?;
+        let seller_buy_ata = ctx
+            .accounts
+            .seller_buy_ata
+            .as_ref()
+            .ok_or(error::Error::MissingTokenAccount)?;
+        let treasury_buy_ata = ctx
+            .accounts
+            .treasury_buy_ata
+            .as_ref()
+            .ok_or(error::Error::MissingTokenAccount)?;
+
+        // Buyer pays the transfer fees, treasury and seller receive the
+        // full amounts
+        // Send protocol fee to treasury
+        if fee > 0 {
+            lib::transfer(
+                &ctx.accounts.token_program_buy,
+                buyer_buy_ata,
+                &ctx.accounts.mint_buy,
+                treasury_buy_ata,
+                &ctx.accounts.buyer,
+                lib::get_pre_fee_amount(&ctx.accounts.mint_buy, fee)?,
+                ctx.remaining_accounts,
+            )?;
+        }
+        // Send the rest of the buy token to seller
+        lib::transfer(
+            &ctx.accounts.token_program_buy,
+            buyer_buy_ata,
+            &ctx.accounts.mint_buy,
+            seller_buy_ata,
+            &ctx.accounts.buyer,
+            lib::get_pre_fee_amount(&ctx.accounts.mint_buy, pay_amt - fee)?,
+            ctx.remaining_accounts,
+        )?;
+    }
+
     // Send sell token to buyer
     let seeds: &[&[u8]] = &[
         state::Auction::SEED_PREFIX,
         &ctx.accounts.seller.key().to_bytes(),
         &ctx.accounts.mint_sell.key().to_bytes(),
         &ctx.accounts.mint_buy.key().to_bytes(),
+        &id.to_le_bytes(),
         &[ctx.bumps.auction],
     ];
 
+    // The transfer fee of mint_sell is withheld from buy_amt
     lib::transfer_from_pda(
-        &ctx.accounts.token_program,
+        &ctx.accounts.token_program_sell,
         &ctx.accounts.auction_sell_ata,
+        &ctx.accounts.mint_sell,
         &ctx.accounts.buyer_sell_ata,
         &ctx.accounts.auction,
-        sell_amt,
+        buy_amt,
+        seeds,
+        ctx.remaining_accounts,
+    )?;
+
+    let remaining = auction.remaining - buy_amt;
+
+    // Keep the auction open until everything is sold
+    if remaining > 0 {
+        ctx.accounts.auction.load_mut()?.remaining = remaining;
+        return Ok(());
+    }
+
+    // Anyone can send sell token to auction_sell_ata, send what is left to
+    // seller and close auction_sell_ata
+    if lib::get_token_balance(&ctx.accounts.auction_sell_ata.to_account_info())?
+        > 0
+    {
+        associated_token::create_idempotent(CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.buyer.to_account_info(),
+                associated_token: ctx
+                    .accounts
+                    .seller_sell_ata
+                    .to_account_info(),
+                authority: ctx.accounts.seller.to_account_info(),
+                mint: ctx.accounts.mint_sell.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx
+                    .accounts
+                    .token_program_sell
+                    .to_account_info(),
+            },
+        ))?;
+    }
+    lib::close_escrow(
+        &ctx.accounts.token_program_sell,
+        &ctx.accounts.auction_sell_ata,
+        &ctx.accounts.mint_sell,
+        &ctx.accounts.seller_sell_ata.to_account_info(),
+        &ctx.accounts.seller.to_account_info(),
+        &ctx.accounts.auction,
         seeds,
+        ctx.remaining_accounts,
     )?;
 
-    // Close auction_sell_ata
-    close_account(CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        CloseAccount {
-            account: ctx.accounts.auction_sell_ata.to_account_info(),
-            destination: ctx.accounts.seller.to_account_info(),
-            authority: ctx.accounts.auction.to_account_info(),
-        },
-        &[seeds],
-    ))?;
+    // Close auction
+    ctx.accounts
+        .auction
+        .close(ctx.accounts.seller.to_account_info())?;
 
     Ok(())
 }
@@ -1,20 +1,20 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token_interface::{
-        close_account, CloseAccount, Mint, TokenAccount, TokenInterface,
-    },
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 use super::lib;
-use crate::error;
 use crate::state;
 
 #[derive(Accounts)]
+#[instruction(id: u64)]
 pub struct Cancel<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    // Writable to harvest withheld transfer fees before closing
+    #[account(mut, mint::token_program = token_program)]
     pub mint_sell: InterfaceAccount<'info, Mint>,
     pub mint_buy: InterfaceAccount<'info, Mint>,
 
@@ -24,17 +24,19 @@ pub struct Cancel<'info> {
             state::Auction::SEED_PREFIX,
             payer.key().as_ref(),
             mint_sell.key().as_ref(),
-            mint_buy.key().as_ref()
+            mint_buy.key().as_ref(),
+            id.to_le_bytes().as_ref()
         ],
         bump,
         close = payer,
     )]
-    pub auction: Account<'info, state::Auction>,
+    pub auction: AccountLoader<'info, state::Auction>,
 
     #[account(
         mut,
         associated_token::mint = mint_sell,
         associated_token::authority = auction,
+        associated_token::token_program = token_program,
     )]
     pub auction_sell_ata: InterfaceAccount<'info, TokenAccount>,
 
@@ -42,43 +44,50 @@ pub struct Cancel<'info> {
         mut,
         associated_token::mint = mint_sell,
         associated_token::authority = payer,
+        associated_token::token_program = token_program,
     )]
     pub seller_sell_ata: InterfaceAccount<'info, TokenAccount>,
 
+    // Token program of mint_sell, the only token moved
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn cancel(ctx: Context<Cancel>) -> Result<()> {
+pub fn cancel<'info>(
+    ctx: Context<'_, '_, '_, 'info, Cancel<'info>>,
+    id: u64,
+) -> Result<()> {
     // Send sell token to seller
     let seeds: &[&[u8]] = &[
         state::Auction::SEED_PREFIX,
         &ctx.accounts.payer.key().to_bytes(),
         &ctx.accounts.mint_sell.key().to_bytes(),
         &ctx.accounts.mint_buy.key().to_bytes(),
+        &id.to_le_bytes(),
         &[ctx.bumps.auction],
     ];
 
     lib::transfer_from_pda(
         &ctx.accounts.token_program,
         &ctx.accounts.auction_sell_ata,
+        &ctx.accounts.mint_sell,
         &ctx.accounts.seller_sell_ata,
         &ctx.accounts.auction,
         ctx.accounts.auction_sell_ata.amount,
         seeds,
+        ctx.remaining_accounts,
     )?;
 
     // Close auction_sell_ata
-    close_account(CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        CloseAccount {
-            account: ctx.accounts.auction_sell_ata.to_account_info(),
-            destination: ctx.accounts.payer.to_account_info(),
-            authority: ctx.accounts.auction.to_account_info(),
-        },
-        &[seeds],
-    ))?;
+    lib::close_ata(
+        &ctx.accounts.token_program,
+        &ctx.accounts.auction_sell_ata,
+        &ctx.accounts.mint_sell,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.auction,
+        seeds,
+    )?;
 
     Ok(())
 }
@@ -9,26 +9,40 @@ use crate::error;
 use crate::state;
 
 #[derive(Accounts)]
+#[instruction(id: u64)]
 pub struct Init<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    #[account(mint::token_program = token_program_sell)]
     pub mint_sell: InterfaceAccount<'info, Mint>,
+    #[account(mint::token_program = token_program_buy)]
     pub mint_buy: InterfaceAccount<'info, Mint>,
 
+    // Created on the seller's first auction
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + std::mem::size_of::<state::Counter>(),
+        seeds = [state::Counter::SEED_PREFIX, payer.key().as_ref()],
+        bump,
+    )]
+    pub counter: Account<'info, state::Counter>,
+
     #[account(
         init,
         payer = payer,
-        space = 8 + state::Auction::INIT_SPACE,
+        space = 8 + std::mem::size_of::<state::Auction>(),
         seeds = [
             state::Auction::SEED_PREFIX,
             payer.key().as_ref(),
             mint_sell.key().as_ref(),
-            mint_buy.key().as_ref()
+            mint_buy.key().as_ref(),
+            id.to_le_bytes().as_ref()
         ],
         bump,
     )]
-    pub auction: Account<'info, state::Auction>,
+    pub auction: AccountLoader<'info, state::Auction>,
 
     // Associated token account to lock seller's sell token
     #[account(
@@ -36,6 +50,7 @@ pub struct Init<'info> {
         payer = payer,
         associated_token::mint = mint_sell,
         associated_token::authority = auction,
+        associated_token::token_program = token_program_sell,
     )]
     pub auction_sell_ata: InterfaceAccount<'info, TokenAccount>,
 
@@ -44,30 +59,36 @@ pub struct Init<'info> {
         mut,
         associated_token::mint = mint_sell,
         associated_token::authority = payer,
+        associated_token::token_program = token_program_sell,
     )]
     pub seller_sell_ata: InterfaceAccount<'info, TokenAccount>,
 
     // Associated token account where seller receives buy token
+    // Not needed if the auction is paid in SOL
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint_buy,
         associated_token::authority = payer,
+        associated_token::token_program = token_program_buy,
     )]
-    pub seller_buy_ata: InterfaceAccount<'info, TokenAccount>,
+    pub seller_buy_ata: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    pub token_program: Interface<'info, TokenInterface>,
+    pub token_program_sell: Interface<'info, TokenInterface>,
+    pub token_program_buy: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn init(
-    ctx: Context<Init>,
+pub fn init<'info>(
+    ctx: Context<'_, '_, '_, 'info, Init<'info>>,
+    id: u64,
     start_price: u64,
     end_price: u64,
     start_time: u64,
     end_time: u64,
     sell_amt: u64,
+    curve: state::PriceCurve,
 ) -> Result<()> {
     let clock = Clock::get()?;
     let now = u64::try_from(clock.unix_timestamp).unwrap();
@@ -78,33 +99,53 @@ pub fn init(
         error::Error::InvalidMints
     );
 
-    // Check start_price >= end_price
-    require!(start_price >= end_price, error::Error::InvalidPrices);
+    // Check prices, times and sell_amt
+    lib::check_params(
+        now,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+    )?;
 
-    // Check now <= start_time < end_time
-    require!(now <= start_time, error::Error::InvalidStartTime);
-    require!(start_time < end_time, error::Error::InvalidEndTime);
+    // Check half_life > 0 and step > 0
+    match curve {
+        state::PriceCurve::Linear => {}
+        state::PriceCurve::Exponential { half_life: param }
+        | state::PriceCurve::Stepped { step: param } => {
+            require!(param > 0, error::Error::InvalidCurve);
+        }
+    }
 
-    // Check sell_amt > 0
-    require!(sell_amt > 0, error::Error::InvalidSellAmount);
+    // Check id is the next id and hand out the one after
+    require!(id == ctx.accounts.counter.next_id, error::Error::InvalidId);
+    ctx.accounts.counter.next_id += 1;
 
     // Send sell token to auction_sell_ata
+    // Seller pays the transfer fee, the auction holds sell_amt
     lib::transfer(
-        &ctx.accounts.token_program,
+        &ctx.accounts.token_program_sell,
         &ctx.accounts.seller_sell_ata,
+        &ctx.accounts.mint_sell,
         &ctx.accounts.auction_sell_ata,
         &ctx.accounts.payer,
-        sell_amt,
+        lib::get_pre_fee_amount(&ctx.accounts.mint_sell, sell_amt)?,
+        ctx.remaining_accounts,
     )?;
 
     // Store Auction state
-    let auction = &mut ctx.accounts.auction;
+    let auction = &mut ctx.accounts.auction.load_init()?;
+    auction.seller = ctx.accounts.payer.key();
     auction.mint_sell = ctx.accounts.mint_sell.key();
     auction.mint_buy = ctx.accounts.mint_buy.key();
+    auction.id = id;
     auction.start_price = start_price;
     auction.end_price = end_price;
     auction.start_time = start_time;
     auction.end_time = end_time;
+    auction.remaining = sell_amt;
+    (auction.curve, auction.curve_param) = curve.pack();
 
     Ok(())
 }
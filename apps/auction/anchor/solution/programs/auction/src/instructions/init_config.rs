@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+
+use crate::error;
+use crate::state;
+
+#[derive(Accounts)]
+pub struct InitConfig<'info> {
+    // Becomes the admin, must be the upgrade authority of the program
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Any account can receive the fee
+    pub treasury: UncheckedAccount<'info>,
+
+    // Created once after deployment
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + std::mem::size_of::<state::Config>(),
+        seeds = [state::Config::SEED_PREFIX],
+        bump,
+    )]
+    pub config: Account<'info, state::Config>,
+
+    pub system_program: Program<'info, System>,
+
+    // Holds the upgrade authority, so that nobody else can create the config
+    // first
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(admin.key()),
+    )]
+    pub program_data: Account<'info, ProgramData>,
+}
+
+pub fn init_config(ctx: Context<InitConfig>, fee_bps: u64) -> Result<()> {
+    // Check fee_bps <= MAX_FEE_BPS
+    require!(
+        fee_bps <= state::Config::MAX_FEE_BPS,
+        error::Error::InvalidFee
+    );
+
+    let config = &mut ctx.accounts.config;
+    config.admin = ctx.accounts.admin.key();
+    config.treasury = ctx.accounts.treasury.key();
+    config.fee_bps = fee_bps;
+
+    Ok(())
+}
@@ -1,26 +1,125 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{
-    token,
-    token::{Burn, MintTo, Transfer},
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+            transfer_hook::TransferHook,
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+        onchain,
+    },
+    token_interface::{
+        self, close_account, get_mint_extension_data,
+        harvest_withheld_tokens_to_mint, CloseAccount,
+        HarvestWithheldTokensToMint, Mint, TokenAccount, TokenInterface,
+        TransferChecked,
+    },
 };
 
+use crate::error;
 use crate::state::Auction;
 
+// Checks shared by init and update
+pub fn check_params(
+    now: u64,
+    start_price: u64,
+    end_price: u64,
+    start_time: u64,
+    end_time: u64,
+    sell_amt: u64,
+) -> Result<()> {
+    // Check start_price >= end_price
+    require!(start_price >= end_price, error::Error::InvalidPrices);
+
+    // Check now <= start_time < end_time
+    require!(now <= start_time, error::Error::InvalidStartTime);
+    require!(start_time < end_time, error::Error::InvalidEndTime);
+
+    // Check sell_amt > 0
+    require!(sell_amt > 0, error::Error::InvalidSellAmount);
+
+    Ok(())
+}
+
+// transfer_checked through the token interface, works with the token
+// program and Token-2022. Mints with a transfer hook need the extra accounts
+// of the hook, they are passed as remaining accounts and resolved onchain.
+fn transfer_checked<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    src: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    dst: AccountInfo<'info>,
+    auth: AccountInfo<'info>,
+    amt: u64,
+    seeds: &[&[&[u8]]],
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if get_mint_extension_data::<TransferHook>(&mint.to_account_info()).is_ok()
+    {
+        onchain::invoke_transfer_checked(
+            token_program.key,
+            src,
+            mint.to_account_info(),
+            dst,
+            auth,
+            hook_accounts,
+            amt,
+            mint.decimals,
+            seeds,
+        )?;
+        return Ok(());
+    }
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: src,
+                mint: mint.to_account_info(),
+                to: dst,
+                authority: auth,
+            },
+            seeds,
+        ),
+        amt,
+        mint.decimals,
+    )
+}
+
 pub fn transfer<'info>(
     token_program: &Interface<'info, TokenInterface>,
     src: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
     dst: &InterfaceAccount<'info, TokenAccount>,
     auth: &Signer<'info>,
     amt: u64,
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    transfer_checked(
+        token_program,
+        src.to_account_info(),
+        mint,
+        dst.to_account_info(),
+        auth.to_account_info(),
+        amt,
+        &[],
+        hook_accounts,
+    )
+}
+
+pub fn transfer_lamports<'info>(
+    sys_program: &Program<'info, System>,
+    src: &Signer<'info>,
+    dst: &AccountInfo<'info>,
+    amt: u64,
 ) -> Result<()> {
-    token::transfer(
+    system_program::transfer(
         CpiContext::new(
-            token_program.to_account_info(),
-            Transfer {
+            sys_program.to_account_info(),
+            system_program::Transfer {
                 from: src.to_account_info(),
-                to: dst.to_account_info(),
-                authority: auth.to_account_info(),
+                to: dst.clone(),
             },
         ),
         amt,
@@ -30,21 +129,122 @@ pub fn transfer<'info>(
 pub fn transfer_from_pda<'info>(
     token_program: &Interface<'info, TokenInterface>,
     src: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
     dst: &InterfaceAccount<'info, TokenAccount>,
-    auth: &Account<'info, Auction>,
+    auth: &AccountLoader<'info, Auction>,
     amt: u64,
     seeds: &[&[u8]],
+    hook_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
-    token::transfer(
-        CpiContext::new_with_signer(
-            token_program.to_account_info(),
-            Transfer {
-                from: src.to_account_info(),
-                to: dst.to_account_info(),
-                authority: auth.to_account_info(),
-            },
-            &[&seeds[..]],
-        ),
+    transfer_checked(
+        token_program,
+        src.to_account_info(),
+        mint,
+        dst.to_account_info(),
+        auth.to_account_info(),
         amt,
+        &[seeds],
+        hook_accounts,
     )
 }
+
+// Amount to send so that amt arrives after the Token-2022 transfer fee of
+// mint is withheld
+pub fn get_pre_fee_amount(
+    mint: &InterfaceAccount<'_, Mint>,
+    amt: u64,
+) -> Result<u64> {
+    let Ok(config) =
+        get_mint_extension_data::<TransferFeeConfig>(&mint.to_account_info())
+    else {
+        return Ok(amt);
+    };
+    let fee = config
+        .calculate_inverse_epoch_fee(Clock::get()?.epoch, amt)
+        .ok_or(error::Error::MathOverflow)?;
+
+    Ok(amt.checked_add(fee).ok_or(error::Error::MathOverflow)?)
+}
+
+// Balance of a token account read from its data, the amount of an
+// InterfaceAccount is stale after a transfer in the same instruction
+pub fn get_token_balance(account: &AccountInfo<'_>) -> Result<u64> {
+    let data = account.try_borrow_data()?;
+    let state =
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+    Ok(state.base.amount)
+}
+
+// Send what is left in an ATA of the auction to dst and close it
+pub fn close_escrow<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    ata: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    dst: &AccountInfo<'info>,
+    // Receiver of the ATA rent
+    rent_dst: &AccountInfo<'info>,
+    auth: &AccountLoader<'info, Auction>,
+    seeds: &[&[u8]],
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let amt = get_token_balance(&ata.to_account_info())?;
+    if amt > 0 {
+        transfer_checked(
+            token_program,
+            ata.to_account_info(),
+            mint,
+            dst.clone(),
+            auth.to_account_info(),
+            amt,
+            &[seeds],
+            hook_accounts,
+        )?;
+    }
+    close_ata(token_program, ata, mint, rent_dst, auth, seeds)
+}
+
+// Close an ATA of the auction. Token-2022 accounts with withheld transfer
+// fees can not be closed, the fees are harvested to the mint first.
+pub fn close_ata<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    ata: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    dst: &AccountInfo<'info>,
+    auth: &AccountLoader<'info, Auction>,
+    seeds: &[&[u8]],
+) -> Result<()> {
+    let withheld = {
+        let info = ata.to_account_info();
+        let data = info.try_borrow_data()?;
+        let state =
+            StateWithExtensions::<spl_token_2022::state::Account>::unpack(
+                &data,
+            )?;
+        state
+            .get_extension::<TransferFeeAmount>()
+            .map(|ext| u64::from(ext.withheld_amount))
+            .unwrap_or(0)
+    };
+    if withheld > 0 {
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                token_program.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: token_program.to_account_info(),
+                    mint: mint.to_account_info(),
+                },
+            ),
+            vec![ata.to_account_info()],
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: ata.to_account_info(),
+            destination: dst.clone(),
+            authority: auth.to_account_info(),
+        },
+        &[seeds],
+    ))
+}
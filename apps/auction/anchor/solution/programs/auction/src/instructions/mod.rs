@@ -1,8 +1,14 @@
 mod buy;
 mod cancel;
 mod init;
+mod init_config;
 mod lib;
+mod set_fee;
+mod update;
 
 pub use buy::*;
 pub use cancel::*;
 pub use init::*;
+pub use init_config::*;
+pub use set_fee::*;
+pub use update::*;
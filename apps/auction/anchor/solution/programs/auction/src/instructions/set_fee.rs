@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::error;
+use crate::state;
+
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [state::Config::SEED_PREFIX],
+        bump,
+        has_one = admin,
+    )]
+    pub config: Account<'info, state::Config>,
+}
+
+pub fn set_fee(ctx: Context<SetFee>, fee_bps: u64) -> Result<()> {
+    // Check fee_bps <= MAX_FEE_BPS
+    require!(
+        fee_bps <= state::Config::MAX_FEE_BPS,
+        error::Error::InvalidFee
+    );
+
+    ctx.accounts.config.fee_bps = fee_bps;
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use super::lib;
+use crate::error;
+use crate::state;
+
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct Update<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(mint::token_program = token_program)]
+    pub mint_sell: InterfaceAccount<'info, Mint>,
+    pub mint_buy: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [
+            state::Auction::SEED_PREFIX,
+            payer.key().as_ref(),
+            mint_sell.key().as_ref(),
+            mint_buy.key().as_ref(),
+            id.to_le_bytes().as_ref()
+        ],
+        bump,
+    )]
+    pub auction: AccountLoader<'info, state::Auction>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_sell,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
+    )]
+    pub auction_sell_ata: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_sell,
+        associated_token::authority = payer,
+        associated_token::token_program = token_program,
+    )]
+    pub seller_sell_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // Token program of mint_sell, the only token moved
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// Fix the parameters of an auction that has not started yet, instead of
+// cancelling and creating it again
+pub fn update<'info>(
+    ctx: Context<'_, '_, '_, 'info, Update<'info>>,
+    id: u64,
+    start_price: u64,
+    end_price: u64,
+    start_time: u64,
+    end_time: u64,
+    sell_amt: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let now = u64::try_from(clock.unix_timestamp).unwrap();
+
+    let auction = *ctx.accounts.auction.load()?;
+
+    // Check auction has not started, so nothing was sold yet
+    require!(now < auction.start_time, error::Error::AuctionStarted);
+
+    // Check prices, times and sell_amt
+    lib::check_params(
+        now,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+    )?;
+
+    if sell_amt > auction.remaining {
+        // Top up
+        lib::transfer(
+            &ctx.accounts.token_program,
+            &ctx.accounts.seller_sell_ata,
+            &ctx.accounts.mint_sell,
+            &ctx.accounts.auction_sell_ata,
+            &ctx.accounts.payer,
+            lib::get_pre_fee_amount(
+                &ctx.accounts.mint_sell,
+                sell_amt - auction.remaining,
+            )?,
+            ctx.remaining_accounts,
+        )?;
+    } else if sell_amt < auction.remaining {
+        // Withdraw
+        let seeds: &[&[u8]] = &[
+            state::Auction::SEED_PREFIX,
+            &ctx.accounts.payer.key().to_bytes(),
+            &ctx.accounts.mint_sell.key().to_bytes(),
+            &ctx.accounts.mint_buy.key().to_bytes(),
+            &id.to_le_bytes(),
+            &[ctx.bumps.auction],
+        ];
+        lib::transfer_from_pda(
+            &ctx.accounts.token_program,
+            &ctx.accounts.auction_sell_ata,
+            &ctx.accounts.mint_sell,
+            &ctx.accounts.seller_sell_ata,
+            &ctx.accounts.auction,
+            auction.remaining - sell_amt,
+            seeds,
+            ctx.remaining_accounts,
+        )?;
+    }
+
+    let auction = &mut ctx.accounts.auction.load_mut()?;
+    auction.start_price = start_price;
+    auction.end_price = end_price;
+    auction.start_time = start_time;
+    auction.end_time = end_time;
+    auction.remaining = sell_amt;
+
+    Ok(())
+}
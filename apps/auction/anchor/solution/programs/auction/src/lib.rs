@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 
 pub mod error;
 pub mod instructions;
+pub mod price;
 pub mod state;
 
 declare_id!("Hp6iqFudQ9vr2Rz9cdXTk2gCHf4eu6Zr8jLyWe6vsPiL");
@@ -11,32 +12,75 @@ pub mod auction {
     pub use super::instructions::*;
     use super::*;
 
-    pub fn init(
-        ctx: Context<Init>,
+    pub fn init<'info>(
+        ctx: Context<'_, '_, '_, 'info, Init<'info>>,
+        id: u64,
         start_price: u64,
         end_price: u64,
         start_time: u64,
         end_time: u64,
         sell_amt: u64,
+        curve: state::PriceCurve,
     ) -> Result<()> {
         instructions::init(
             ctx,
+            id,
             start_price,
             end_price,
             start_time,
             end_time,
             sell_amt,
+            curve,
         )?;
         Ok(())
     }
 
-    pub fn buy(ctx: Context<Buy>, max_price: u64) -> Result<()> {
-        instructions::buy(ctx, max_price)?;
+    pub fn buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, Buy<'info>>,
+        id: u64,
+        max_price: u64,
+        buy_amt: u64,
+    ) -> Result<()> {
+        instructions::buy(ctx, id, max_price, buy_amt)?;
+        Ok(())
+    }
+
+    pub fn cancel<'info>(
+        ctx: Context<'_, '_, '_, 'info, Cancel<'info>>,
+        id: u64,
+    ) -> Result<()> {
+        instructions::cancel(ctx, id)?;
+        Ok(())
+    }
+
+    pub fn init_config(ctx: Context<InitConfig>, fee_bps: u64) -> Result<()> {
+        instructions::init_config(ctx, fee_bps)?;
+        Ok(())
+    }
+
+    pub fn set_fee(ctx: Context<SetFee>, fee_bps: u64) -> Result<()> {
+        instructions::set_fee(ctx, fee_bps)?;
         Ok(())
     }
 
-    pub fn cancel(ctx: Context<Cancel>) -> Result<()> {
-        instructions::cancel(ctx)?;
+    pub fn update<'info>(
+        ctx: Context<'_, '_, '_, 'info, Update<'info>>,
+        id: u64,
+        start_price: u64,
+        end_price: u64,
+        start_time: u64,
+        end_time: u64,
+        sell_amt: u64,
+    ) -> Result<()> {
+        instructions::update(
+            ctx,
+            id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+        )?;
         Ok(())
     }
 }
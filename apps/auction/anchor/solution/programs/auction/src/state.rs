@@ -1,17 +1,105 @@
 use anchor_lang::prelude::*;
 
-#[account]
-#[derive(InitSpace)]
+use crate::error;
+
+// How the price moves from start_price to end_price, see price::price_at
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum PriceCurve {
+    // Decreases linearly from start_time to end_time
+    Linear,
+    // Distance to end_price halves every half_life seconds
+    Exponential { half_life: u64 },
+    // Linear, but only drops every step seconds
+    Stepped { step: u64 },
+}
+
+impl PriceCurve {
+    pub const LINEAR: u64 = 0;
+    pub const EXPONENTIAL: u64 = 1;
+    pub const STEPPED: u64 = 2;
+
+    // (kind, param) stored in Auction
+    pub fn pack(&self) -> (u64, u64) {
+        match *self {
+            PriceCurve::Linear => (Self::LINEAR, 0),
+            PriceCurve::Exponential { half_life } => {
+                (Self::EXPONENTIAL, half_life)
+            }
+            PriceCurve::Stepped { step } => (Self::STEPPED, step),
+        }
+    }
+
+    pub fn unpack(kind: u64, param: u64) -> Result<Self> {
+        match kind {
+            Self::LINEAR => Ok(PriceCurve::Linear),
+            Self::EXPONENTIAL => {
+                Ok(PriceCurve::Exponential { half_life: param })
+            }
+            Self::STEPPED => Ok(PriceCurve::Stepped { step: param }),
+            _ => err!(error::Error::InvalidCurve),
+        }
+    }
+}
+
+#[account(zero_copy)]
 pub struct Auction {
+    // Stored first so that clients can filter auctions by seller
+    pub seller: Pubkey,
     pub mint_sell: Pubkey,
     pub mint_buy: Pubkey,
-    // Price of 1 sell token, 1e6 = 1 buy token
+    // Id handed out by the seller's Counter
+    pub id: u64,
+    // Price of 1 whole sell token, price::PRICE_SCALE = 1 whole buy token
     pub start_price: u64,
     pub end_price: u64,
     pub start_time: u64,
     pub end_time: u64,
+    // Amount of sell token left, buyers can take part of it
+    pub remaining: u64,
+    // PriceCurve packed as (kind, param)
+    pub curve: u64,
+    pub curve_param: u64,
 }
 
 impl Auction {
     pub const SEED_PREFIX: &'static [u8; 7] = b"auction";
+    // Offset of seller in account data, for getProgramAccounts filters
+    pub const SELLER_OFFSET: usize = 8;
+
+    pub fn price_curve(&self) -> Result<PriceCurve> {
+        PriceCurve::unpack(self.curve, self.curve_param)
+    }
+}
+
+// One per seller, hands out auction ids
+#[account]
+pub struct Counter {
+    // Id of the next auction created by the seller
+    pub next_id: u64,
+}
+
+impl Counter {
+    pub const SEED_PREFIX: &'static [u8; 7] = b"counter";
+}
+
+// Protocol fee taken from every buy, a single PDA for the program
+#[account]
+pub struct Config {
+    // Can change fee_bps
+    pub admin: Pubkey,
+    // Receives the fee in its mint_buy ATA
+    pub treasury: Pubkey,
+    // Fee in basis points of the payment, at most MAX_FEE_BPS
+    pub fee_bps: u64,
+}
+
+impl Config {
+    pub const SEED_PREFIX: &'static [u8; 6] = b"config";
+    // Hard cap on fee_bps, 10%
+    pub const MAX_FEE_BPS: u64 = 1_000;
+
+    // Part of amt sent to the treasury, rounded down
+    pub fn fee(&self, amt: u64) -> u64 {
+        (amt as u128 * self.fee_bps as u128 / 10_000) as u64
+    }
 }
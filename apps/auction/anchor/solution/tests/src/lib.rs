@@ -4,6 +4,8 @@ mod test_buy;
 mod test_cancel;
 #[cfg(test)]
 mod test_init;
+#[cfg(test)]
+mod test_update;
 
 mod test_helper;
 mod token_helper;
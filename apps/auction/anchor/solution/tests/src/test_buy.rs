@@ -1,9 +1,15 @@
 use anchor_client::solana_sdk::signature::Signer;
-use anchor_client::solana_sdk::{signature::read_keypair_file, system_program};
+use anchor_client::solana_sdk::{
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair},
+    system_program,
+};
 use anchor_spl::associated_token::{
-    get_associated_token_address, spl_associated_token_account,
+    get_associated_token_address, get_associated_token_address_with_program_id,
+    spl_associated_token_account,
 };
 use anchor_spl::token::{self};
+use anchor_spl::token_2022;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::test_helper;
@@ -21,6 +27,7 @@ fn test_buy() {
         buyer,
         auction_pda,
         auction_bump,
+        counter_pda,
         mint_sell,
         mint_buy,
         seller_sell_ata,
@@ -41,6 +48,9 @@ fn test_buy() {
     let sell_amt = 100 * (1e6 as u64);
     let auction_sell_ata =
         get_associated_token_address(&auction_pda, &mint_sell.pubkey());
+    let (config_pda, treasury) = test_helper::get_config(&program);
+    let treasury_buy_ata =
+        get_associated_token_address(&treasury, &mint_buy.pubkey());
 
     program
         .request()
@@ -48,21 +58,25 @@ fn test_buy() {
             payer: seller.pubkey(),
             mint_sell: mint_sell.pubkey(),
             mint_buy: mint_buy.pubkey(),
+            counter: counter_pda,
             auction: auction_pda,
             auction_sell_ata,
             seller_sell_ata,
-            seller_buy_ata,
-            token_program: token::ID,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
             associated_token_program: spl_associated_token_account::ID,
             system_program: system_program::ID,
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            id: 0,
             start_price,
             end_price,
             start_time,
             end_time,
             sell_amt,
+            curve: auction::state::PriceCurve::Linear,
         })
         .send()
         .unwrap();
@@ -81,16 +95,23 @@ fn test_buy() {
             mint_buy: mint_buy.pubkey(),
             auction: auction_pda,
             auction_sell_ata,
-            buyer_buy_ata,
+            buyer_buy_ata: Some(buyer_buy_ata),
             buyer_sell_ata,
-            seller_buy_ata,
-            token_program: token::ID,
+            seller_buy_ata: Some(seller_buy_ata),
+            seller_sell_ata,
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
             associated_token_program: spl_associated_token_account::ID,
             system_program: system_program::ID,
+            config: config_pda,
+            treasury,
+            treasury_buy_ata: Some(treasury_buy_ata),
         })
         .signer(&buyer)
         .args(auction::instruction::Buy {
+            id: 0,
             max_price: start_price - 1,
+            buy_amt: sell_amt,
         })
         .send()
         .unwrap();
@@ -117,3 +138,356 @@ fn test_buy() {
         "Auction sell ATA balance"
     );
 }
+
+#[test]
+fn test_buy_partial() {
+    let anchor_wallet = std::env::var("ANCHOR_WALLET").unwrap();
+    let payer = read_keypair_file(&anchor_wallet).unwrap();
+
+    let test_helper::Test {
+        program,
+        token_program,
+        seller,
+        buyer,
+        auction_pda,
+        auction_bump,
+        counter_pda,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        buyer_sell_ata,
+        seller_buy_ata,
+        buyer_buy_ata,
+    } = test_helper::set_up(&payer);
+
+    // Init
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.1 * 1e6) as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let sell_amt = 100 * (1e6 as u64);
+    let auction_sell_ata =
+        get_associated_token_address(&auction_pda, &mint_sell.pubkey());
+    let (config_pda, treasury) = test_helper::get_config(&program);
+    let treasury_buy_ata =
+        get_associated_token_address(&treasury, &mint_buy.pubkey());
+
+    program
+        .request()
+        .accounts(auction::accounts::Init {
+            payer: seller.pubkey(),
+            mint_sell: mint_sell.pubkey(),
+            mint_buy: mint_buy.pubkey(),
+            counter: counter_pda,
+            auction: auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&seller)
+        .args(auction::instruction::Init {
+            id: 0,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            curve: auction::state::PriceCurve::Linear,
+        })
+        .send()
+        .unwrap();
+
+    // Buy half
+    let wait_time = start_time - now + 2;
+    println!("Waiting {:?} seconds for auction to start", wait_time);
+    std::thread::sleep(std::time::Duration::from_secs(wait_time));
+
+    let buy = |buy_amt: u64| {
+        program
+            .request()
+            .accounts(auction::accounts::Buy {
+                buyer: buyer.pubkey(),
+                seller: seller.pubkey(),
+                mint_sell: mint_sell.pubkey(),
+                mint_buy: mint_buy.pubkey(),
+                auction: auction_pda,
+                auction_sell_ata,
+                buyer_buy_ata: Some(buyer_buy_ata),
+                buyer_sell_ata,
+                seller_buy_ata: Some(seller_buy_ata),
+                seller_sell_ata,
+                token_program_sell: token::ID,
+                token_program_buy: token::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+                config: config_pda,
+                treasury,
+                treasury_buy_ata: Some(treasury_buy_ata),
+            })
+            .signer(&buyer)
+            .args(auction::instruction::Buy {
+                id: 0,
+                max_price: start_price,
+                buy_amt,
+            })
+            .send()
+    };
+
+    buy(sell_amt / 2).unwrap();
+
+    let state = program
+        .account::<auction::state::Auction>(auction_pda)
+        .unwrap();
+    assert_eq!(state.remaining, sell_amt / 2, "Auction remaining");
+    assert_eq!(
+        token_helper::get_balance(&token_program, &auction_sell_ata).unwrap(),
+        sell_amt / 2,
+        "Auction sell ATA balance"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &buyer_sell_ata).unwrap(),
+        sell_amt / 2,
+        "Buyer sell ATA balance"
+    );
+
+    // Can not buy more than remaining
+    assert!(buy(sell_amt / 2 + 1).is_err(), "Buy > remaining");
+
+    // Anyone can send sell token to auction_sell_ata
+    token_helper::mint_to(
+        &token_program,
+        &payer,
+        &mint_sell.pubkey(),
+        &auction_sell_ata,
+        1,
+    )
+    .unwrap();
+
+    // Buy the rest, what is left in auction_sell_ata goes to seller
+    buy(sell_amt / 2).unwrap();
+
+    assert!(
+        program
+            .account::<auction::state::Auction>(auction_pda)
+            .is_err(),
+        "Auction not closed"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &buyer_sell_ata).unwrap(),
+        sell_amt,
+        "Buyer sell ATA balance"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &seller_sell_ata).unwrap(),
+        1,
+        "Seller sell ATA balance"
+    );
+}
+
+#[test]
+fn test_buy_token_2022() {
+    buy_with_token_programs(token_2022::ID, token_2022::ID);
+}
+
+// Token-2022 sell token paid with an SPL token
+#[test]
+fn test_buy_mixed_token_programs() {
+    buy_with_token_programs(token_2022::ID, token::ID);
+}
+
+// Init and buy an auction whose mints are owned by token_program_sell and
+// token_program_buy. Their ATAs are derived with the program of their mint.
+fn buy_with_token_programs(
+    token_program_sell: Pubkey,
+    token_program_buy: Pubkey,
+) {
+    let anchor_wallet = std::env::var("ANCHOR_WALLET").unwrap();
+    let payer = read_keypair_file(&anchor_wallet).unwrap();
+
+    let test_helper::Test {
+        program,
+        token_program,
+        seller,
+        buyer,
+        counter_pda,
+        ..
+    } = test_helper::set_up(&payer);
+
+    let mint_sell = Keypair::new();
+    let mint_buy = Keypair::new();
+    token_helper::create_mint_with_program(
+        &token_program,
+        &payer,
+        &mint_sell,
+        6,
+        &token_program_sell,
+    );
+    token_helper::create_mint_with_program(
+        &token_program,
+        &payer,
+        &mint_buy,
+        6,
+        &token_program_buy,
+    );
+
+    let create_ata = |mint: &Keypair, owner: &Keypair, program_id: &Pubkey| {
+        token_helper::create_ata_with_program(
+            &token_program,
+            &payer,
+            &mint.pubkey(),
+            &owner.pubkey(),
+            program_id,
+        )
+        .unwrap()
+    };
+    let seller_sell_ata = create_ata(&mint_sell, &seller, &token_program_sell);
+    let buyer_sell_ata = create_ata(&mint_sell, &buyer, &token_program_sell);
+    let seller_buy_ata = create_ata(&mint_buy, &seller, &token_program_buy);
+    let buyer_buy_ata = create_ata(&mint_buy, &buyer, &token_program_buy);
+
+    token_helper::mint_to_with_program(
+        &token_program,
+        &payer,
+        &mint_sell.pubkey(),
+        &seller_sell_ata,
+        100 * (1e6 as u64),
+        &token_program_sell,
+    )
+    .unwrap();
+    token_helper::mint_to_with_program(
+        &token_program,
+        &payer,
+        &mint_buy.pubkey(),
+        &buyer_buy_ata,
+        200 * (1e6 as u64),
+        &token_program_buy,
+    )
+    .unwrap();
+
+    let (auction_pda, _) = Pubkey::find_program_address(
+        &[
+            auction::state::Auction::SEED_PREFIX,
+            seller.pubkey().as_ref(),
+            mint_sell.pubkey().as_ref(),
+            mint_buy.pubkey().as_ref(),
+            &0u64.to_le_bytes(),
+        ],
+        &auction::ID,
+    );
+
+    // Init
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.1 * 1e6) as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let sell_amt = 100 * (1e6 as u64);
+    let auction_sell_ata = get_associated_token_address_with_program_id(
+        &auction_pda,
+        &mint_sell.pubkey(),
+        &token_program_sell,
+    );
+    let (config_pda, treasury) = test_helper::get_config(&program);
+    let treasury_buy_ata = get_associated_token_address_with_program_id(
+        &treasury,
+        &mint_buy.pubkey(),
+        &token_program_buy,
+    );
+
+    program
+        .request()
+        .accounts(auction::accounts::Init {
+            payer: seller.pubkey(),
+            mint_sell: mint_sell.pubkey(),
+            mint_buy: mint_buy.pubkey(),
+            counter: counter_pda,
+            auction: auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell,
+            token_program_buy,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&seller)
+        .args(auction::instruction::Init {
+            id: 0,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            curve: auction::state::PriceCurve::Linear,
+        })
+        .send()
+        .unwrap();
+
+    assert_eq!(
+        token_helper::get_balance(&token_program, &auction_sell_ata).unwrap(),
+        sell_amt,
+        "Auction sell ATA balance"
+    );
+
+    // Buy
+    let wait_time = start_time - now + 2;
+    println!("Waiting {:?} seconds for auction to start", wait_time);
+    std::thread::sleep(std::time::Duration::from_secs(wait_time));
+
+    program
+        .request()
+        .accounts(auction::accounts::Buy {
+            buyer: buyer.pubkey(),
+            seller: seller.pubkey(),
+            mint_sell: mint_sell.pubkey(),
+            mint_buy: mint_buy.pubkey(),
+            auction: auction_pda,
+            auction_sell_ata,
+            buyer_buy_ata: Some(buyer_buy_ata),
+            buyer_sell_ata,
+            seller_buy_ata: Some(seller_buy_ata),
+            seller_sell_ata,
+            token_program_sell,
+            token_program_buy,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+            config: config_pda,
+            treasury,
+            treasury_buy_ata: Some(treasury_buy_ata),
+        })
+        .signer(&buyer)
+        .args(auction::instruction::Buy {
+            id: 0,
+            max_price: start_price,
+            buy_amt: sell_amt,
+        })
+        .send()
+        .unwrap();
+
+    assert!(
+        program
+            .account::<auction::state::Auction>(auction_pda)
+            .is_err(),
+        "Auction not closed"
+    );
+    assert!(
+        token_helper::get_balance(&token_program, &seller_buy_ata).unwrap() > 0,
+        "Seller buy ATA balance"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &buyer_sell_ata).unwrap(),
+        sell_amt,
+        "Buyer sell ATA balance"
+    );
+}
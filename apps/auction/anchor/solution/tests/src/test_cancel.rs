@@ -21,6 +21,7 @@ fn test_cancel() {
         buyer,
         auction_pda,
         auction_bump,
+        counter_pda,
         mint_sell,
         mint_buy,
         seller_sell_ata,
@@ -48,21 +49,25 @@ fn test_cancel() {
             payer: seller.pubkey(),
             mint_sell: mint_sell.pubkey(),
             mint_buy: mint_buy.pubkey(),
+            counter: counter_pda,
             auction: auction_pda,
             auction_sell_ata,
             seller_sell_ata,
-            seller_buy_ata,
-            token_program: token::ID,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
             associated_token_program: spl_associated_token_account::ID,
             system_program: system_program::ID,
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            id: 0,
             start_price,
             end_price,
             start_time,
             end_time,
             sell_amt,
+            curve: auction::state::PriceCurve::Linear,
         })
         .send()
         .unwrap();
@@ -82,7 +87,7 @@ fn test_cancel() {
             system_program: system_program::ID,
         })
         .signer(&seller)
-        .args(auction::instruction::Cancel {})
+        .args(auction::instruction::Cancel { id: 0 })
         .send()
         .unwrap();
 
@@ -1,3 +1,4 @@
+use anchor_client::anchor_lang::solana_program::bpf_loader_upgradeable;
 use anchor_client::solana_sdk::signature::Signer;
 use anchor_client::{
     solana_sdk::{
@@ -17,6 +18,7 @@ pub struct Test<'a> {
     pub buyer: Keypair,
     pub auction_pda: Pubkey,
     pub auction_bump: u8,
+    pub counter_pda: Pubkey,
     pub mint_sell: Keypair,
     pub mint_buy: Keypair,
     pub seller_sell_ata: Pubkey,
@@ -106,13 +108,48 @@ pub fn set_up<'a>(payer: &'a Keypair) -> Test<'a> {
     )
     .unwrap();
 
-    // Calculate Auction PDA
+    // Protocol fee config is shared by all tests, created by the first one
+    let (config_pda, treasury) = get_config(&program);
+    if program
+        .account::<auction::state::Config>(config_pda)
+        .is_err()
+    {
+        // payer deployed the program and is its upgrade authority
+        let (program_data, _) = Pubkey::find_program_address(
+            &[program_id.as_ref()],
+            &bpf_loader_upgradeable::ID,
+        );
+        // Fails if another test created it first
+        let _ = program
+            .request()
+            .accounts(auction::accounts::InitConfig {
+                admin: payer.pubkey(),
+                treasury,
+                config: config_pda,
+                system_program: system_program::ID,
+                program_data,
+            })
+            .args(auction::instruction::InitConfig { fee_bps: 0 })
+            .send();
+    }
+
+    // Calculate Counter PDA
+    let (counter_pda, _) = Pubkey::find_program_address(
+        &[
+            auction::state::Counter::SEED_PREFIX,
+            seller.pubkey().as_ref(),
+        ],
+        &program_id,
+    );
+
+    // Calculate PDA of the seller's first auction
     let (pda, bump) = Pubkey::find_program_address(
         &[
             auction::state::Auction::SEED_PREFIX,
             &seller.pubkey().as_ref(),
             mint_sell.pubkey().as_ref(),
             mint_buy.pubkey().as_ref(),
+            &0u64.to_le_bytes(),
         ],
         &program_id,
     );
@@ -124,6 +161,7 @@ pub fn set_up<'a>(payer: &'a Keypair) -> Test<'a> {
         buyer,
         auction_pda: pda,
         auction_bump: bump,
+        counter_pda,
         mint_sell,
         mint_buy,
         seller_sell_ata,
@@ -132,3 +170,16 @@ pub fn set_up<'a>(payer: &'a Keypair) -> Test<'a> {
         buyer_buy_ata,
     }
 }
+
+// Config PDA and treasury, the payer until the config is created
+pub fn get_config(program: &Program<&Keypair>) -> (Pubkey, Pubkey) {
+    let (config_pda, _) = Pubkey::find_program_address(
+        &[auction::state::Config::SEED_PREFIX],
+        &auction::ID,
+    );
+    let treasury = program
+        .account::<auction::state::Config>(config_pda)
+        .map(|config| config.treasury)
+        .unwrap_or(program.payer());
+    (config_pda, treasury)
+}
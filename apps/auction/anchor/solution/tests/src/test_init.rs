@@ -1,5 +1,8 @@
+use anchor_client::solana_client::rpc_filter::{Memcmp, RpcFilterType};
 use anchor_client::solana_sdk::signature::Signer;
-use anchor_client::solana_sdk::{signature::read_keypair_file, system_program};
+use anchor_client::solana_sdk::{
+    pubkey::Pubkey, signature::read_keypair_file, system_program,
+};
 use anchor_spl::associated_token::{
     get_associated_token_address, spl_associated_token_account,
 };
@@ -21,6 +24,7 @@ fn test_init() {
         buyer,
         auction_pda,
         auction_bump,
+        counter_pda,
         mint_sell,
         mint_buy,
         seller_sell_ata,
@@ -52,21 +56,25 @@ fn test_init() {
             payer: seller.pubkey(),
             mint_sell: mint_sell.pubkey(),
             mint_buy: mint_sell.pubkey(),
+            counter: counter_pda,
             auction: auction_pda,
             auction_sell_ata,
             seller_sell_ata,
-            seller_buy_ata,
-            token_program: token::ID,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
             associated_token_program: spl_associated_token_account::ID,
             system_program: system_program::ID,
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            id: 0,
             start_price,
             end_price,
             start_time,
             end_time,
             sell_amt,
+            curve: auction::state::PriceCurve::Linear,
         })
         .send();
 
@@ -79,21 +87,25 @@ fn test_init() {
             payer: seller.pubkey(),
             mint_sell: mint_sell.pubkey(),
             mint_buy: mint_buy.pubkey(),
+            counter: counter_pda,
             auction: auction_pda,
             auction_sell_ata,
             seller_sell_ata,
-            seller_buy_ata,
-            token_program: token::ID,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
             associated_token_program: spl_associated_token_account::ID,
             system_program: system_program::ID,
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            id: 0,
             start_price,
             end_price: start_price + 1,
             start_time,
             end_time,
             sell_amt,
+            curve: auction::state::PriceCurve::Linear,
         })
         .send();
 
@@ -106,21 +118,25 @@ fn test_init() {
             payer: seller.pubkey(),
             mint_sell: mint_sell.pubkey(),
             mint_buy: mint_buy.pubkey(),
+            counter: counter_pda,
             auction: auction_pda,
             auction_sell_ata,
             seller_sell_ata,
-            seller_buy_ata,
-            token_program: token::ID,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
             associated_token_program: spl_associated_token_account::ID,
             system_program: system_program::ID,
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            id: 0,
             start_price,
             end_price,
             start_time: now - 1,
             end_time,
             sell_amt,
+            curve: auction::state::PriceCurve::Linear,
         })
         .send();
 
@@ -133,21 +149,25 @@ fn test_init() {
             payer: seller.pubkey(),
             mint_sell: mint_sell.pubkey(),
             mint_buy: mint_buy.pubkey(),
+            counter: counter_pda,
             auction: auction_pda,
             auction_sell_ata,
             seller_sell_ata,
-            seller_buy_ata,
-            token_program: token::ID,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
             associated_token_program: spl_associated_token_account::ID,
             system_program: system_program::ID,
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            id: 0,
             start_price,
             end_price,
             start_time,
             end_time: start_time,
             sell_amt,
+            curve: auction::state::PriceCurve::Linear,
         })
         .send();
 
@@ -160,21 +180,25 @@ fn test_init() {
             payer: seller.pubkey(),
             mint_sell: mint_sell.pubkey(),
             mint_buy: mint_buy.pubkey(),
+            counter: counter_pda,
             auction: auction_pda,
             auction_sell_ata,
             seller_sell_ata,
-            seller_buy_ata,
-            token_program: token::ID,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
             associated_token_program: spl_associated_token_account::ID,
             system_program: system_program::ID,
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            id: 0,
             start_price,
             end_price,
             start_time,
             end_time,
             sell_amt: 0,
+            curve: auction::state::PriceCurve::Linear,
         })
         .send();
 
@@ -188,29 +212,35 @@ fn test_init() {
             payer: seller.pubkey(),
             mint_sell: mint_sell.pubkey(),
             mint_buy: mint_buy.pubkey(),
+            counter: counter_pda,
             auction: auction_pda,
             auction_sell_ata,
             seller_sell_ata,
-            seller_buy_ata,
-            token_program: token::ID,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
             associated_token_program: spl_associated_token_account::ID,
             system_program: system_program::ID,
         })
         .signer(&seller)
         .args(auction::instruction::Init {
+            id: 0,
             start_price,
             end_price,
             start_time,
             end_time,
             sell_amt,
+            curve: auction::state::PriceCurve::Linear,
         })
         .send()
         .unwrap();
 
     let auction: auction::state::Auction =
         program.account(auction_pda).unwrap();
+    assert_eq!(auction.seller, seller.pubkey(), "auction.seller");
     assert_eq!(auction.mint_sell, mint_sell.pubkey(), "auction.mint_sell");
     assert_eq!(auction.mint_buy, mint_buy.pubkey(), "auction.mint_buy");
+    assert_eq!(auction.id, 0, "auction.id");
     assert_eq!(auction.start_time, start_time, "auction.start_time");
     assert_eq!(auction.end_time, end_time, "auction.end_time");
     assert_eq!(auction.end_time, end_time, "auction.end_time");
@@ -226,3 +256,104 @@ fn test_init() {
         "Auction sell ATA balance"
     );
 }
+
+#[test]
+fn test_init_multiple() {
+    let anchor_wallet = std::env::var("ANCHOR_WALLET").unwrap();
+    let payer = read_keypair_file(&anchor_wallet).unwrap();
+
+    let test_helper::Test {
+        program,
+        token_program,
+        seller,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        counter_pda,
+        ..
+    } = test_helper::set_up(&payer);
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.1 * 1e6) as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let sell_amt = 10 * (1e6 as u64);
+
+    // Same seller and mints, one auction per id
+    let init = |id: u64| {
+        let (auction_pda, _) = Pubkey::find_program_address(
+            &[
+                auction::state::Auction::SEED_PREFIX,
+                seller.pubkey().as_ref(),
+                mint_sell.pubkey().as_ref(),
+                mint_buy.pubkey().as_ref(),
+                &id.to_le_bytes(),
+            ],
+            &auction::ID,
+        );
+        let auction_sell_ata =
+            get_associated_token_address(&auction_pda, &mint_sell.pubkey());
+
+        program
+            .request()
+            .accounts(auction::accounts::Init {
+                payer: seller.pubkey(),
+                mint_sell: mint_sell.pubkey(),
+                mint_buy: mint_buy.pubkey(),
+                counter: counter_pda,
+                auction: auction_pda,
+                auction_sell_ata,
+                seller_sell_ata,
+                seller_buy_ata: Some(seller_buy_ata),
+                token_program_sell: token::ID,
+                token_program_buy: token::ID,
+                associated_token_program: spl_associated_token_account::ID,
+                system_program: system_program::ID,
+            })
+            .signer(&seller)
+            .args(auction::instruction::Init {
+                id,
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                sell_amt,
+                curve: auction::state::PriceCurve::Linear,
+            })
+            .send()
+    };
+
+    init(0).unwrap();
+    assert!(init(2).is_err(), "Test id = counter.next_id");
+    init(1).unwrap();
+
+    let counter: auction::state::Counter =
+        program.account(counter_pda).unwrap();
+    assert_eq!(counter.next_id, 2, "counter.next_id");
+
+    assert_eq!(
+        token_helper::get_balance(&token_program, &seller_sell_ata).unwrap(),
+        100 * (1e6 as u64) - 2 * sell_amt,
+        "Seller sell ATA balance"
+    );
+
+    // List open auctions of the seller
+    let mut auctions = program
+        .accounts::<auction::state::Auction>(vec![RpcFilterType::Memcmp(
+            Memcmp::new_base58_encoded(
+                auction::state::Auction::SELLER_OFFSET,
+                seller.pubkey().as_ref(),
+            ),
+        )])
+        .unwrap();
+    auctions.sort_by_key(|(_, a)| a.id);
+
+    let ids: Vec<u64> = auctions.iter().map(|(_, a)| a.id).collect();
+    assert_eq!(ids, vec![0, 1], "Seller auctions");
+}
@@ -0,0 +1,154 @@
+use anchor_client::solana_sdk::signature::Signer;
+use anchor_client::solana_sdk::{signature::read_keypair_file, system_program};
+use anchor_spl::associated_token::{
+    get_associated_token_address, spl_associated_token_account,
+};
+use anchor_spl::token::{self};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::test_helper;
+use super::token_helper;
+
+#[test]
+fn test_update() {
+    let anchor_wallet = std::env::var("ANCHOR_WALLET").unwrap();
+    let payer = read_keypair_file(&anchor_wallet).unwrap();
+
+    let test_helper::Test {
+        program,
+        token_program,
+        seller,
+        buyer,
+        auction_pda,
+        auction_bump,
+        counter_pda,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        buyer_sell_ata,
+        seller_buy_ata,
+        buyer_buy_ata,
+    } = test_helper::set_up(&payer);
+
+    // Init
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.1 * 1e6) as u64;
+    // Far enough for the updates to land before start_time
+    let start_time = now + 60;
+    let end_time = start_time + 10;
+    let sell_amt = 100 * (1e6 as u64);
+    let init_amt = sell_amt / 2;
+    let auction_sell_ata =
+        get_associated_token_address(&auction_pda, &mint_sell.pubkey());
+
+    program
+        .request()
+        .accounts(auction::accounts::Init {
+            payer: seller.pubkey(),
+            mint_sell: mint_sell.pubkey(),
+            mint_buy: mint_buy.pubkey(),
+            counter: counter_pda,
+            auction: auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+            seller_buy_ata: Some(seller_buy_ata),
+            token_program_sell: token::ID,
+            token_program_buy: token::ID,
+            associated_token_program: spl_associated_token_account::ID,
+            system_program: system_program::ID,
+        })
+        .signer(&seller)
+        .args(auction::instruction::Init {
+            id: 0,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt: init_amt,
+            curve: auction::state::PriceCurve::Linear,
+        })
+        .send()
+        .unwrap();
+
+    let update = |start_price: u64,
+                  end_price: u64,
+                  start_time: u64,
+                  end_time: u64,
+                  sell_amt: u64| {
+        program
+            .request()
+            .accounts(auction::accounts::Update {
+                payer: seller.pubkey(),
+                mint_sell: mint_sell.pubkey(),
+                mint_buy: mint_buy.pubkey(),
+                auction: auction_pda,
+                auction_sell_ata,
+                seller_sell_ata,
+                token_program: token::ID,
+            })
+            .signer(&seller)
+            .args(auction::instruction::Update {
+                id: 0,
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                sell_amt,
+            })
+            .send()
+    };
+
+    // Same validation as init
+    assert!(
+        update(end_price, start_price, start_time, end_time, sell_amt).is_err(),
+        "start_price < end_price"
+    );
+    assert!(
+        update(start_price, end_price, start_time, start_time, sell_amt)
+            .is_err(),
+        "end_time <= start_time"
+    );
+    assert!(
+        update(start_price, end_price, start_time, end_time, 0).is_err(),
+        "sell_amt = 0"
+    );
+
+    // Top up
+    let start_price = (3.0 * 1e6) as u64;
+    let end_time = end_time + 10;
+    update(start_price, end_price, start_time, end_time, sell_amt).unwrap();
+
+    let auction: auction::state::Auction =
+        program.account(auction_pda).unwrap();
+    assert_eq!(auction.start_price, start_price, "Start price");
+    assert_eq!(auction.end_time, end_time, "End time");
+    assert_eq!(auction.remaining, sell_amt, "Remaining");
+    assert_eq!(
+        token_helper::get_balance(&token_program, &auction_sell_ata).unwrap(),
+        sell_amt,
+        "Auction sell ATA balance"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &seller_sell_ata).unwrap(),
+        0,
+        "Seller sell ATA balance"
+    );
+
+    // Withdraw
+    update(start_price, end_price, start_time, end_time, sell_amt / 4).unwrap();
+
+    assert_eq!(
+        token_helper::get_balance(&token_program, &auction_sell_ata).unwrap(),
+        sell_amt / 4,
+        "Auction sell ATA balance"
+    );
+    assert_eq!(
+        token_helper::get_balance(&token_program, &seller_sell_ata).unwrap(),
+        sell_amt - sell_amt / 4,
+        "Seller sell ATA balance"
+    );
+}
@@ -5,15 +5,31 @@ use anchor_client::{
     },
     Program,
 };
-use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::associated_token::{
+    self, get_associated_token_address_with_program_id,
+};
 use anchor_spl::token::spl_token::solana_program::program_pack::Pack;
 use anchor_spl::token::{self, spl_token};
+use anchor_spl::token_2022::spl_token_2022::{
+    self, extension::StateWithExtensions,
+};
 
 pub fn create_mint(
     program: &Program<&Keypair>,
     payer: &Keypair,
     mint_keypair: &Keypair,
     decimals: u8,
+) {
+    create_mint_with_program(program, payer, mint_keypair, decimals, &token::ID)
+}
+
+// Mint owned by token_program, the Token or Token-2022 program
+pub fn create_mint_with_program(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    mint_keypair: &Keypair,
+    decimals: u8,
+    token_program: &Pubkey,
 ) {
     let rpc = program.rpc();
 
@@ -28,11 +44,11 @@ pub fn create_mint(
         &mint,
         rent,
         spl_token::state::Mint::LEN as u64,
-        &token::ID,
+        token_program,
     );
 
-    let init_mint_ix = spl_token::instruction::initialize_mint(
-        &token::ID,
+    let init_mint_ix = spl_token_2022::instruction::initialize_mint(
+        token_program,
         &mint,
         &payer.pubkey(),
         None,
@@ -56,17 +72,31 @@ pub fn create_ata(
     payer: &Keypair,
     mint: &Pubkey,
     owner: &Pubkey,
+) -> Result<Pubkey, Box<dyn std::error::Error>> {
+    create_ata_with_program(program, payer, mint, owner, &token::ID)
+}
+
+pub fn create_ata_with_program(
+    program: &Program<&Keypair>,
+    payer: &Keypair,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    token_program: &Pubkey,
 ) -> Result<Pubkey, Box<dyn std::error::Error>> {
     let rpc = program.rpc();
 
-    let ata = get_associated_token_address(owner, mint);
+    let ata = get_associated_token_address_with_program_id(
+        owner,
+        mint,
+        token_program,
+    );
 
     let ix =
         associated_token::spl_associated_token_account::instruction::create_associated_token_account(
             &payer.pubkey(),
             owner,
             mint,
-            &token::ID,
+            token_program,
         );
 
     let blockhash = rpc.get_latest_blockhash()?;
@@ -89,11 +119,23 @@ pub fn mint_to(
     mint: &Pubkey,
     dst: &Pubkey,
     amt: u64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    mint_to_with_program(program, auth, mint, dst, amt, &token::ID)
+}
+
+pub fn mint_to_with_program(
+    program: &Program<&Keypair>,
+    // Mint authority
+    auth: &Keypair,
+    mint: &Pubkey,
+    dst: &Pubkey,
+    amt: u64,
+    token_program: &Pubkey,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let rpc = program.rpc();
 
-    let ix = spl_token::instruction::mint_to(
-        &token::ID,
+    let ix = spl_token_2022::instruction::mint_to(
+        token_program,
         mint,
         dst,
         &auth.pubkey(),
@@ -119,6 +161,9 @@ pub fn get_balance(
     token_account: &Pubkey,
 ) -> Result<u64, Box<dyn std::error::Error>> {
     let account_data = program.rpc().get_account_data(token_account)?;
-    let token_account_info = spl_token::state::Account::unpack(&account_data)?;
-    Ok(token_account_info.amount)
+    // Token-2022 accounts may have extensions after the base state
+    let token_account_info = StateWithExtensions::<
+        spl_token_2022::state::Account,
+    >::unpack(&account_data)?;
+    Ok(token_account_info.base.amount)
 }
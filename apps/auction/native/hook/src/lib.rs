@@ -0,0 +1,161 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    entrypoint::ProgramResult,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{Sysvar, rent::Rent},
+};
+
+// Token-2022 transfer hook used by the auction tests. It counts the
+// transfers of a mint in a counter PDA, the only extra account of the hook.
+
+// sha256("spl-transfer-hook-interface:execute")[..8]
+pub const EXECUTE: [u8; 8] = [105, 37, 101, 197, 75, 251, 102, 26];
+// sha256("spl-transfer-hook-interface:initialize-extra-account-metas")[..8]
+pub const INIT_EXTRA_ACCOUNT_METAS: [u8; 8] =
+    [43, 34, 13, 49, 167, 88, 235, 235];
+
+pub const VALIDATION_SEED_PREFIX: &[u8; 19] = b"extra-account-metas";
+pub const COUNTER_SEED_PREFIX: &[u8; 7] = b"counter";
+
+// ExtraAccountMetaList of EXECUTE holding 1 ExtraAccountMeta:
+// discriminator (8) + length (4) + count (4) + meta (1 + 32 + 1 + 1)
+pub const VALIDATION_SPACE: usize = 8 + 4 + 4 + 35;
+// Number of transfers, u64
+pub const COUNTER_SPACE: usize = 8;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (discriminator, _) = instruction_data
+        .split_first_chunk::<8>()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match *discriminator {
+        INIT_EXTRA_ACCOUNT_METAS => init(program_id, accounts),
+        EXECUTE => execute(program_id, accounts),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+pub fn get_validation_pda(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VALIDATION_SEED_PREFIX, mint.as_ref()],
+        program_id,
+    )
+}
+
+pub fn get_counter_pda(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[COUNTER_SEED_PREFIX, mint.as_ref()],
+        program_id,
+    )
+}
+
+// Create the validation PDA of mint listing the counter PDA as a writable
+// extra account, and the counter PDA.
+// The extra accounts are fixed, so unlike the interface they are not read
+// from the instruction data.
+fn init(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_iter = &mut accounts.iter();
+
+    let validation_pda = next_account_info(account_iter)?;
+    let mint = next_account_info(account_iter)?;
+    // Pays for the PDAs
+    let payer = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let counter_pda = next_account_info(account_iter)?;
+
+    // Check payer signed
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let (validation, validation_bump) =
+        get_validation_pda(program_id, mint.key);
+    if *validation_pda.key != validation {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    let (counter, counter_bump) = get_counter_pda(program_id, mint.key);
+    if *counter_pda.key != counter {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let rent = Rent::get()?;
+    for (pda, space, seeds) in [
+        (
+            validation_pda,
+            VALIDATION_SPACE,
+            &[
+                VALIDATION_SEED_PREFIX,
+                mint.key.as_ref(),
+                &[validation_bump],
+            ] as &[&[u8]],
+        ),
+        (
+            counter_pda,
+            COUNTER_SPACE,
+            &[COUNTER_SEED_PREFIX, mint.key.as_ref(), &[counter_bump]],
+        ),
+    ] {
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                pda.key,
+                rent.minimum_balance(space),
+                space as u64,
+                program_id,
+            ),
+            &[payer.clone(), pda.clone(), sys_program.clone()],
+            &[seeds],
+        )?;
+    }
+
+    let mut data = validation_pda.data.borrow_mut();
+    data[..8].copy_from_slice(&EXECUTE);
+    // Length of the list
+    data[8..12].copy_from_slice(&(4u32 + 35).to_le_bytes());
+    // Number of metas
+    data[12..16].copy_from_slice(&1u32.to_le_bytes());
+    // Literal address, not a signer, writable
+    data[16] = 0;
+    data[17..49].copy_from_slice(counter.as_ref());
+    data[49] = 0;
+    data[50] = 1;
+
+    Ok(())
+}
+
+// Called by Token-2022 on every transfer of mint with the accounts
+// source, mint, destination, authority, validation PDA, counter PDA
+fn execute(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_iter = &mut accounts.iter();
+
+    let _source = next_account_info(account_iter)?;
+    let mint = next_account_info(account_iter)?;
+    let _destination = next_account_info(account_iter)?;
+    let _authority = next_account_info(account_iter)?;
+    let validation_pda = next_account_info(account_iter)?;
+    let counter_pda = next_account_info(account_iter)?;
+
+    if *validation_pda.key != get_validation_pda(program_id, mint.key).0
+        || *counter_pda.key != get_counter_pda(program_id, mint.key).0
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if counter_pda.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let mut data = counter_pda.data.borrow_mut();
+    let count = u64::from_le_bytes(data[..8].try_into().unwrap());
+    data[..8].copy_from_slice(&(count + 1).to_le_bytes());
+
+    Ok(())
+}
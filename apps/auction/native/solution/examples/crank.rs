@@ -0,0 +1,288 @@
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_address::Address;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::RpcFilterType,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Signer, read_keypair_file},
+    transaction::{Transaction, TransactionError},
+};
+use spl_token_2022_interface::extension::{StateWithExtensions, transfer_hook};
+use spl_transfer_hook_interface::offchain::add_extra_account_metas_for_execute;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use auction::Cmd;
+use auction::error::AuctionError;
+use auction::state::{Auction, AuctionResult};
+
+/*
+RPC=https://api.devnet.solana.com
+PROGRAM_ID=9Xsm3WVTBY6ALbUhRTDzt5wVZiNN52BU5kXUR3m6ERZ
+KEYPAIR=~/.config/solana/id.json
+
+cargo run --example crank $KEYPAIR $RPC $PROGRAM_ID
+*/
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    // Pays the fees and receives the crank rewards
+    let keypair_path: PathBuf = [&args[1]].iter().collect();
+    let cranker =
+        read_keypair_file(keypair_path).expect("Cannot read keypair file");
+
+    let rpc_url = String::from(&args[2]);
+    let client =
+        RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+
+    let program_id = Pubkey::from_str(&args[3]).expect("Invalid program ID");
+
+    // Open auctions, closed auctions no longer exist
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::DataSize(Auction::SPACE)]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let now = client.get_block_time(client.get_slot().unwrap()).unwrap() as u64;
+
+    let stale: Vec<(Pubkey, Auction)> = client
+        .get_program_accounts_with_config(&program_id, config)
+        .unwrap()
+        .into_iter()
+        .filter_map(|(addr, account)| {
+            Auction::unpack(&account.data).ok().map(|a| (addr, a))
+        })
+        // Batch auctions are closed by Clear and BatchClaim
+        .filter(|(_, a)| a.end_time <= now && a.batch == 0)
+        .collect();
+
+    println!("Stale auctions: {}", stale.len());
+    for (addr, auction) in stale {
+        println!("Expire {:?}", addr);
+
+        let (auction_pda, bump) = Pubkey::find_program_address(
+            &[
+                Auction::SEED_PREFIX,
+                auction.seller.as_ref(),
+                auction.mint_sell.as_ref(),
+                auction.mint_buy.as_ref(),
+                &auction.id.to_le_bytes(),
+            ],
+            &program_id,
+        );
+        assert_eq!(auction_pda, addr);
+
+        // mint_sell is owned by the SPL token or the Token-2022 program
+        let mint_sell = client.get_account(&auction.mint_sell).unwrap();
+        let token_program = mint_sell.owner;
+
+        let mut ix = create_expire_ix(
+            program_id,
+            bump,
+            cranker.pubkey(),
+            &auction,
+            auction_pda,
+            token_program,
+        );
+
+        // Expire sends the unsold sell token to seller, add the accounts of
+        // the transfer hook of mint_sell, if any
+        if let Err(err) = add_hook_accounts(
+            &client,
+            &mut ix,
+            auction.mint_sell,
+            get_ata(&auction_pda, &auction.mint_sell, &token_program),
+            get_ata(&auction.seller, &auction.mint_sell, &token_program),
+            auction_pda,
+            auction.remaining,
+        ) {
+            println!("Failed to resolve transfer hook accounts: {:?}", err);
+            continue;
+        }
+
+        let mut tx =
+            Transaction::new_with_payer(&[ix], Some(&cranker.pubkey()));
+        let blockhash = client.get_latest_blockhash().unwrap();
+        tx.sign(&[&cranker], blockhash);
+
+        // Another crank may have expired it first
+        match client.send_and_confirm_transaction(&tx) {
+            Ok(sig) => println!("Expired: {:?}", sig),
+            Err(err) => match err.get_transaction_error() {
+                Some(TransactionError::InstructionError(_, e)) => {
+                    match AuctionError::from_instruction_error(&e) {
+                        Some(e) => println!("Failed: {}", e),
+                        None => println!("Failed: {:?}", err),
+                    }
+                }
+                _ => println!("Failed: {:?}", err),
+            },
+        }
+    }
+}
+
+fn create_expire_ix(
+    program_id: Pubkey,
+    bump: u8,
+    cranker: Pubkey,
+    auction: &Auction,
+    auction_pda: Pubkey,
+    // Token program of mint_sell
+    token_program: Pubkey,
+) -> Instruction {
+    let (result_pda, result_bump) = Pubkey::find_program_address(
+        &[AuctionResult::SEED_PREFIX, auction_pda.as_ref()],
+        &program_id,
+    );
+    let Auction {
+        id,
+        seller,
+        mint_sell,
+        mint_buy,
+        ..
+    } = *auction;
+    let cmd = Cmd::Expire {
+        id,
+        bump,
+        result_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: cranker,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            // Writable to harvest withheld transfer fees before closing
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&auction_pda, &mint_sell, &token_program),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&seller, &mint_sell, &token_program),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: token_program,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: result_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+// Append the hook program, its validation account and the extra accounts
+// it lists for a transfer of mint from src to dst. Nothing is added if mint
+// has no transfer hook.
+fn add_hook_accounts(
+    client: &RpcClient,
+    ix: &mut Instruction,
+    mint: Pubkey,
+    src: Pubkey,
+    dst: Pubkey,
+    // Transfer authority
+    auth: Pubkey,
+    amount: u64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mint_data = client.get_account_data(&mint)?;
+    let state =
+        StateWithExtensions::<spl_token_2022_interface::state::Mint>::unpack(
+            &mint_data,
+        )?;
+    let Some(hook_program) = transfer_hook::get_program_id(&state) else {
+        return Ok(());
+    };
+    let hook_program = Pubkey::from(hook_program.to_bytes());
+
+    // Transfer the extra accounts are resolved against, only its accounts
+    // are used
+    let mut hook_ix = Instruction::new_with_bytes(
+        ix.program_id,
+        &[],
+        vec![
+            AccountMeta::new(src, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(dst, false),
+            AccountMeta::new_readonly(auth, false),
+        ],
+    );
+    futures::executor::block_on(add_extra_account_metas_for_execute(
+        &mut hook_ix,
+        &hook_program,
+        &src,
+        &mint,
+        &dst,
+        &auth,
+        amount,
+        |addr| {
+            std::future::ready(Ok(client
+                .get_account(&addr)
+                .ok()
+                .map(|account| account.data)))
+        },
+    ))?;
+
+    ix.accounts.extend_from_slice(&hook_ix.accounts[4..]);
+    Ok(())
+}
+
+fn get_ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    let ata_addr = spl_associated_token_account_interface::address::get_associated_token_address_with_program_id(
+        &Address::from(owner.to_bytes()),
+        &Address::from(mint.to_bytes()),
+        &Address::from(token_program.to_bytes()),
+    );
+    Pubkey::from(ata_addr.to_bytes())
+}
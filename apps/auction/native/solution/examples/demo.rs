@@ -14,8 +14,10 @@ use std::str::FromStr;
 use std::thread;
 use std::time::Duration;
 
-use auction::Cmd;
-use auction::state::Auction;
+use auction::state::{
+    Auction, AuctionResult, Config, Counter, PriceCurve, Purchase,
+};
+use auction::{Cmd, InitParams};
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
@@ -55,18 +57,86 @@ fn main() {
     mint_to(&client, &payer, &mint_buy, &buyer_buy_ata, 1e9 as u64);
 
     // Derive PDAs
-    let (auction_pda, bump) = Pubkey::find_program_address(
-        &[
-            Auction::SEED_PREFIX,
-            seller.pubkey().as_ref(),
-            mint_sell.as_ref(),
-            mint_buy.as_ref(),
-        ],
+    let (counter_pda, counter_bump) = Pubkey::find_program_address(
+        &[Counter::SEED_PREFIX, seller.pubkey().as_ref()],
+        &program_id,
+    );
+
+    // Seller's first auction
+    let (auction_pda, bump) = get_auction_pda(
         &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        0,
     );
 
     let auction_sell_ata = get_ata(&auction_pda, &mint_sell);
 
+    // Protocol fee config, created once per deployment
+    let (config_pda, config_bump) =
+        Pubkey::find_program_address(&[Config::SEED_PREFIX], &program_id);
+    let treasury = match client.get_account(&config_pda) {
+        Ok(account) => Config::unpack(&account.data).unwrap().treasury,
+        Err(_) => {
+            println!("Init config");
+
+            let ix = Instruction::new_with_borsh(
+                program_id,
+                &Cmd::InitConfig {
+                    fee_bps: 0,
+                    bump: config_bump,
+                },
+                vec![
+                    AccountMeta {
+                        pubkey: payer.pubkey(),
+                        is_signer: true,
+                        is_writable: true,
+                    },
+                    AccountMeta {
+                        pubkey: payer.pubkey(),
+                        is_signer: false,
+                        is_writable: false,
+                    },
+                    AccountMeta {
+                        pubkey: config_pda,
+                        is_signer: false,
+                        is_writable: true,
+                    },
+                    AccountMeta {
+                        pubkey: solana_sdk::system_program::id(),
+                        is_signer: false,
+                        is_writable: false,
+                    },
+                    // payer must be the upgrade authority of the program
+                    AccountMeta {
+                        pubkey: Pubkey::find_program_address(
+                            &[program_id.as_ref()],
+                            &solana_program::bpf_loader_upgradeable::ID,
+                        )
+                        .0,
+                        is_signer: false,
+                        is_writable: false,
+                    },
+                    // No oracle program, oracle pegged auctions are disabled
+                    AccountMeta {
+                        pubkey: Pubkey::default(),
+                        is_signer: false,
+                        is_writable: false,
+                    },
+                ],
+            );
+
+            let mut tx =
+                Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
+            let blockhash = client.get_latest_blockhash().unwrap();
+            tx.sign(&[&payer], blockhash);
+            client.send_and_confirm_transaction(&tx).unwrap();
+
+            payer.pubkey()
+        }
+    };
+
     // Init
     println!("Init");
 
@@ -79,15 +149,19 @@ fn main() {
 
     let ix = create_init_ix(
         program_id,
+        0,
         start_price,
         end_price,
         start_time,
         end_time,
         sell_amt,
+        PriceCurve::Linear,
         bump,
+        counter_bump,
         seller.pubkey(),
         mint_sell,
         mint_buy,
+        counter_pda,
         auction_pda,
         auction_sell_ata,
         seller_sell_ata,
@@ -109,9 +183,31 @@ fn main() {
     println!("Buy");
     thread::sleep(Duration::from_millis(1500));
 
+    // Only used by auctions with a wallet cap
+    let (purchase_pda, purchase_bump) = Pubkey::find_program_address(
+        &[
+            Purchase::SEED_PREFIX,
+            auction_pda.as_ref(),
+            buyer.pubkey().as_ref(),
+        ],
+        &program_id,
+    );
+    // Outcome of the auction, created when the buy sells it out
+    let (result_pda, result_bump) = Pubkey::find_program_address(
+        &[AuctionResult::SEED_PREFIX, auction_pda.as_ref()],
+        &program_id,
+    );
+
     let cmd = Cmd::Buy {
+        id: 0,
         max_price: start_price - 1,
+        buy_amt: sell_amt,
         bump,
+        purchase_bump,
+        config_bump,
+        result_bump,
+        vesting_bump: 0,
+        proof: vec![],
     };
 
     let ix = Instruction::new_with_borsh(
@@ -168,11 +264,71 @@ fn main() {
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: purchase_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: treasury,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&treasury, &mint_buy),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: false,
+            },
+            // No oracle
             AccountMeta {
                 pubkey: solana_sdk::system_program::id(),
                 is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: result_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
                 is_writable: true,
             },
+            // No vesting
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: false,
+            },
         ],
     );
 
@@ -196,8 +352,17 @@ fn main() {
     assert_eq!(buyer_sell_ata_bal, sell_amt, "Buyer sell ATA balance");
     assert!(seller_buy_ata_bal > 0, "Seller buy ATA balance");
 
-    // Create auction
+    // Create another auction, same seller and mints
     println!("Init");
+    let (auction_pda, bump) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        1,
+    );
+    let auction_sell_ata = get_ata(&auction_pda, &mint_sell);
+
     let now = client.get_block_time(client.get_slot().unwrap()).unwrap() as u64;
     let start_price: u64 = (2.0 * 1e6) as u64;
     let end_price: u64 = (1.5 * 1e6) as u64;
@@ -207,15 +372,19 @@ fn main() {
 
     let ix = create_init_ix(
         program_id,
+        1,
         start_price,
         end_price,
         start_time,
         end_time,
         sell_amt,
+        PriceCurve::Linear,
         bump,
+        counter_bump,
         seller.pubkey(),
         mint_sell,
         mint_buy,
+        counter_pda,
         auction_pda,
         auction_sell_ata,
         seller_sell_ata,
@@ -230,7 +399,15 @@ fn main() {
 
     // Cancel
     println!("Cancel");
-    let cmd = Cmd::Cancel { bump };
+    let (result_pda, result_bump) = Pubkey::find_program_address(
+        &[AuctionResult::SEED_PREFIX, auction_pda.as_ref()],
+        &program_id,
+    );
+    let cmd = Cmd::Cancel {
+        id: 1,
+        bump,
+        result_bump,
+    };
 
     let ix = Instruction::new_with_borsh(
         program_id,
@@ -276,6 +453,11 @@ fn main() {
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: result_pda,
+                is_signer: false,
+                is_writable: true,
+            },
         ],
     );
 
@@ -298,29 +480,56 @@ fn main() {
     );
 }
 
+fn get_auction_pda(
+    program_id: &Pubkey,
+    seller: &Pubkey,
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            Auction::SEED_PREFIX,
+            seller.as_ref(),
+            mint_sell.as_ref(),
+            mint_buy.as_ref(),
+            &id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
 fn create_init_ix(
     program_id: Pubkey,
+    id: u64,
     start_price: u64,
     end_price: u64,
     start_time: u64,
     end_time: u64,
     sell_amt: u64,
+    curve: PriceCurve,
     bump: u8,
+    counter_bump: u8,
     seller: Pubkey,
     mint_sell: Pubkey,
     mint_buy: Pubkey,
+    counter_pda: Pubkey,
     auction_pda: Pubkey,
     auction_sell_ata: Pubkey,
     seller_sell_ata: Pubkey,
 ) -> Instruction {
-    let cmd = Cmd::Init {
+    let cmd = Cmd::Init(InitParams {
+        id,
         start_price,
         end_price,
         start_time,
         end_time,
         sell_amt,
+        curve,
         bump,
-    };
+        counter_bump,
+        ..Default::default()
+    });
 
     Instruction::new_with_borsh(
         program_id,
@@ -341,6 +550,11 @@ fn create_init_ix(
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: counter_pda,
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: auction_pda,
                 is_signer: false,
@@ -361,6 +575,11 @@ fn create_init_ix(
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: Pubkey::from(
                     spl_associated_token_account_interface::program::ID
@@ -379,6 +598,26 @@ fn create_init_ix(
                 is_signer: false,
                 is_writable: true,
             },
+            // Not a batch auction
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: Pubkey::find_program_address(
+                    &[Config::SEED_PREFIX],
+                    &program_id,
+                )
+                .0,
+                is_signer: false,
+                is_writable: false,
+            },
         ],
     )
 }
@@ -0,0 +1,114 @@
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use std::str::FromStr;
+
+use auction::state::AuctionResult;
+
+/*
+RPC=https://api.devnet.solana.com
+PROGRAM_ID=9Xsm3WVTBY6ALbUhRTDzt5wVZiNN52BU5kXUR3m6ERZ
+SELLER=73Rgt8CZCJez89VtJdRd84kfUPVSprS2Sy5V7Skmq1bU
+
+cargo run --example history $RPC $PROGRAM_ID seller $SELLER
+cargo run --example history $RPC $PROGRAM_ID mint $MINT
+*/
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let rpc_url = String::from(&args[1]);
+    let client =
+        RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+
+    let program_id = Pubkey::from_str(&args[2]).expect("Invalid program ID");
+    let key = Pubkey::from_str(&args[4]).expect("Invalid pubkey");
+
+    let mut results = match args[3].as_str() {
+        "seller" => results_by_seller(&client, &program_id, &key),
+        "mint" => results_by_mint(&client, &program_id, &key),
+        _ => panic!("Expected seller or mint"),
+    };
+    results.sort_by_key(|(_, r)| r.closed_at);
+
+    println!("Closed auctions: {}", results.len());
+    for (addr, result) in results {
+        let outcome = match result.outcome {
+            AuctionResult::SOLD => "sold",
+            AuctionResult::CANCELLED => "cancelled",
+            AuctionResult::EXPIRED => "expired",
+            _ => "unknown",
+        };
+        println!("--- Auction {} ---", result.id);
+        println!("Result: {:?}", addr);
+        println!("Seller: {:?}", result.seller);
+        println!("Sell mint: {:?}", result.mint_sell);
+        println!("Buy mint: {:?}", result.mint_buy);
+        println!("Outcome: {}", outcome);
+        println!("Buyer: {:?}", result.buyer);
+        println!("Sold: {}", result.sold);
+        println!("Proceeds: {}", result.proceeds);
+        println!("Price: {}", result.price);
+        println!("Closed at: {}", result.closed_at);
+    }
+}
+
+// Closed auctions of a seller
+fn results_by_seller(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    seller: &Pubkey,
+) -> Vec<(Pubkey, AuctionResult)> {
+    get_results(client, program_id, AuctionResult::SELLER_OFFSET, seller)
+}
+
+// Closed auctions selling or paid in mint
+fn results_by_mint(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    mint: &Pubkey,
+) -> Vec<(Pubkey, AuctionResult)> {
+    let mut results =
+        get_results(client, program_id, AuctionResult::MINT_SELL_OFFSET, mint);
+    results.extend(get_results(
+        client,
+        program_id,
+        AuctionResult::MINT_BUY_OFFSET,
+        mint,
+    ));
+    results
+}
+
+fn get_results(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    offset: usize,
+    key: &Pubkey,
+) -> Vec<(Pubkey, AuctionResult)> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(AuctionResult::SPACE),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                offset,
+                key.as_ref(),
+            )),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    client
+        .get_program_accounts_with_config(program_id, config)
+        .unwrap()
+        .into_iter()
+        .filter_map(|(addr, account)| {
+            AuctionResult::unpack(&account.data).ok().map(|r| (addr, r))
+        })
+        .collect()
+}
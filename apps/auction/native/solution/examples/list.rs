@@ -0,0 +1,154 @@
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use borsh::BorshDeserialize;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{
+        RpcAccountInfoConfig, RpcProgramAccountsConfig,
+        RpcSimulateTransactionConfig,
+    },
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    message::Message,
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+use std::str::FromStr;
+
+use auction::{
+    Cmd,
+    price::Quote,
+    state::{Auction, Counter},
+};
+
+/*
+RPC=https://api.devnet.solana.com
+PROGRAM_ID=9Xsm3WVTBY6ALbUhRTDzt5wVZiNN52BU5kXUR3m6ERZ
+SELLER=73Rgt8CZCJez89VtJdRd84kfUPVSprS2Sy5V7Skmq1bU
+
+cargo run --example list $RPC $PROGRAM_ID $SELLER
+*/
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    let rpc_url = String::from(&args[1]);
+    let client =
+        RpcClient::new_with_commitment(rpc_url, CommitmentConfig::confirmed());
+
+    let program_id = Pubkey::from_str(&args[2]).expect("Invalid program ID");
+    let seller = Pubkey::from_str(&args[3]).expect("Invalid seller");
+
+    // Number of auctions the seller has created
+    let (counter_pda, _) = Pubkey::find_program_address(
+        &[Counter::SEED_PREFIX, seller.as_ref()],
+        &program_id,
+    );
+    let next_id = match client.get_account_data(&counter_pda) {
+        Ok(data) => Counter::unpack(&data).unwrap().next_id,
+        Err(_) => 0,
+    };
+    println!("Auctions created: {}", next_id);
+
+    // Open auctions, see the history example for closed auctions
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::DataSize(Auction::SPACE),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                Auction::SELLER_OFFSET,
+                seller.as_ref(),
+            )),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let mut auctions: Vec<(Pubkey, Auction)> = client
+        .get_program_accounts_with_config(&program_id, config)
+        .unwrap()
+        .into_iter()
+        .filter_map(|(addr, account)| {
+            Auction::unpack(&account.data).ok().map(|a| (addr, a))
+        })
+        .collect();
+    auctions.sort_by_key(|(_, a)| a.id);
+
+    println!("Open auctions: {}", auctions.len());
+    for (addr, auction) in auctions {
+        println!("--- Auction {} ---", auction.id);
+        println!("Address: {:?}", addr);
+        println!("Sell mint: {:?}", auction.mint_sell);
+        println!("Buy mint: {:?}", auction.mint_buy);
+        println!("Start price: {}", auction.start_price);
+        println!("End price: {}", auction.end_price);
+        println!("Start time: {}", auction.start_time);
+        println!("End time: {}", auction.end_time);
+        println!("Remaining: {}", auction.remaining);
+        if auction.nft == 1 {
+            println!("NFT collection: {:?}", auction.collection);
+        }
+        match get_price(&client, &program_id, &auction) {
+            Some(quote) => {
+                println!("Price now: {}", quote.price);
+                println!("Time left: {}", quote.time_left);
+            }
+            None => println!("Price now: unavailable"),
+        }
+    }
+}
+
+// Current price, computed by the program itself by simulating GetPrice
+fn get_price(
+    client: &RpcClient,
+    program_id: &Pubkey,
+    auction: &Auction,
+) -> Option<Quote> {
+    let (auction_pda, bump) = Pubkey::find_program_address(
+        &[
+            Auction::SEED_PREFIX,
+            auction.seller.as_ref(),
+            auction.mint_sell.as_ref(),
+            auction.mint_buy.as_ref(),
+            &auction.id.to_le_bytes(),
+        ],
+        program_id,
+    );
+    // Any account if the auction has no oracle
+    let oracle = if auction.oracle == Pubkey::default() {
+        solana_sdk::system_program::id()
+    } else {
+        auction.oracle
+    };
+
+    let ix = Instruction::new_with_borsh(
+        *program_id,
+        &Cmd::GetPrice {
+            id: auction.id,
+            bump,
+        },
+        vec![
+            AccountMeta::new_readonly(auction.seller, false),
+            AccountMeta::new_readonly(auction.mint_sell, false),
+            AccountMeta::new_readonly(auction.mint_buy, false),
+            AccountMeta::new_readonly(auction_pda, false),
+            AccountMeta::new_readonly(oracle, false),
+        ],
+    );
+    // Nothing is signed, the seller only pays the fee of the simulation
+    let tx =
+        Transaction::new_unsigned(Message::new(&[ix], Some(&auction.seller)));
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        ..RpcSimulateTransactionConfig::default()
+    };
+
+    let res = client.simulate_transaction_with_config(&tx, config).ok()?;
+    let (data, _) = res.value.return_data?.data;
+    Quote::try_from_slice(&BASE64.decode(data).ok()?).ok()
+}
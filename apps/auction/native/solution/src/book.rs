@@ -0,0 +1,96 @@
+use bytemuck::{Pod, Zeroable};
+use solana_program::pubkey::Pubkey;
+
+// Max number of winning bids kept in a Book
+pub const MAX_BIDS: usize = 8;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, Pod, Zeroable, PartialEq)]
+pub struct BookBid {
+    pub bidder: Pubkey,
+    // Max price of 1 whole sell token, price::PRICE_SCALE = 1 whole buy token
+    pub price: u64,
+    // Amount of sell token
+    pub amount: u64,
+}
+
+// Bids that win part of the supply, highest price first.
+// Bids at the same price keep their arrival order.
+// Stored inside zero copy accounts.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Book {
+    pub len: u64,
+    pub bids: [BookBid; MAX_BIDS],
+}
+
+impl Book {
+    // Min amount of a bid. MAX_BIDS bids of at least this amount cover
+    // supply, so a full book never drops a bid that could still win.
+    pub fn min_amount(supply: u64) -> u64 {
+        supply.div_ceil(MAX_BIDS as u64)
+    }
+
+    pub fn bids(&self) -> &[BookBid] {
+        &self.bids[..self.len as usize]
+    }
+
+    // Insert bid and drop bids that can no longer win: bids below the ones
+    // that already cover supply, and the lowest bid if the book is full.
+    // Returns false if bid itself did not make it into the book.
+    pub fn insert(&mut self, bid: BookBid, supply: u64) -> bool {
+        let len = self.len as usize;
+        let i = self.bids[..len]
+            .iter()
+            .position(|b| b.price < bid.price)
+            .unwrap_or(len);
+        if i == MAX_BIDS {
+            return false;
+        }
+
+        // Shift lower bids right, the last one falls off a full book
+        let end = len.min(MAX_BIDS - 1);
+        self.bids.copy_within(i..end, i + 1);
+        self.bids[i] = bid;
+
+        // Keep bids until supply is covered
+        let mut len = end + 1;
+        let mut total: u64 = 0;
+        for (j, b) in self.bids[..len].iter().enumerate() {
+            if total >= supply {
+                len = j;
+                break;
+            }
+            total = total.saturating_add(b.amount);
+        }
+        self.bids[len..].fill(BookBid::default());
+        self.len = len as u64;
+
+        i < len
+    }
+
+    // Amount of sell token each bid wins. The last bid may be filled
+    // partially.
+    pub fn fills(&self, supply: u64) -> impl Iterator<Item = (&BookBid, u64)> {
+        let mut left = supply;
+        self.bids().iter().map(move |b| {
+            let fill = b.amount.min(left);
+            left -= fill;
+            (b, fill)
+        })
+    }
+
+    // Amount of sell token won by bidder, 0 if bidder lost
+    pub fn fill_of(&self, bidder: &Pubkey, supply: u64) -> u64 {
+        self.fills(supply)
+            .find(|(b, _)| b.bidder == *bidder)
+            .map(|(_, fill)| fill)
+            .unwrap_or(0)
+    }
+
+    // Uniform price paid by all winners, the lowest winning bid.
+    // 0 if there are no bids.
+    pub fn clearing_price(&self) -> u64 {
+        self.bids().last().map(|b| b.price).unwrap_or(0)
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt;
+
+use solana_program::{
+    instruction::InstructionError, program_error::ProgramError,
+};
+
+// Custom program errors, returned as ProgramError::Custom(code)
+macro_rules! auction_error {
+    ($($code:literal $name:ident $msg:literal,)*) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(u32)]
+        pub enum AuctionError {
+            $($name = $code,)*
+        }
+
+        impl AuctionError {
+            pub fn msg(&self) -> &'static str {
+                match self {
+                    $(Self::$name => $msg,)*
+                }
+            }
+        }
+
+        impl TryFrom<u32> for AuctionError {
+            type Error = u32;
+
+            fn try_from(code: u32) -> Result<Self, u32> {
+                match code {
+                    $($code => Ok(Self::$name),)*
+                    _ => Err(code),
+                }
+            }
+        }
+    };
+}
+
+// Codes 6000 to 6015 are the same as the Anchor program's error::Error
+auction_error! {
+    6000 InvalidMints "Sell mint = buy mint",
+    6001 InvalidPrices "Invalid prices",
+    6002 InvalidStartTime "Invalid start time",
+    6003 InvalidEndTime "Invalid end time",
+    6004 InvalidSellAmount "Sell amount must be > 0",
+    6005 AuctionNotStarted "Auction not started",
+    6006 AuctionEnded "Auction ended",
+    6007 InvalidCurrentPrice "Invalid current price",
+    6008 MaxPrice "Max price",
+    6009 InvalidBuyAmount "Invalid buy amount",
+    6010 InvalidId "Invalid auction id",
+    6011 InvalidCurve "Invalid price curve",
+    6012 MathOverflow "Math overflow",
+    6013 InvalidFee "Fee above cap",
+    6014 MissingTokenAccount "Missing token account",
+    6015 AuctionStarted "Auction started",
+    6016 InvalidTokenAccount "Token account is not the expected ATA",
+    6017 InvalidAccount "Account does not match the auction",
+    6018 Unauthorized "Signer is not the admin",
+    6019 NotAllowed "Buyer is not in the allowlist",
+    6020 WalletCapExceeded "Wallet cap exceeded",
+    6021 InvalidOracle "Invalid oracle",
+    6022 StaleOracle "Stale oracle price",
+    6023 InvalidVesting "Invalid vesting schedule",
+    6024 InvalidNft "Invalid NFT",
+    6025 InvalidAuctionKind "Not allowed for this kind of auction",
+    6026 AuctionNotEnded "Auction not ended",
+    6027 AlreadySettled "Auction already settled",
+    6028 NotSettled "Auction not settled",
+    6029 BidTooLow "Bid too low",
+    6030 InvalidCommitment "Bid does not match the commitment",
+    6031 NothingToClaim "Nothing to claim",
+    6032 MinPrice "Min price",
+}
+
+impl AuctionError {
+    // Decode the error of a failed instruction, None if it is not ours
+    pub fn from_instruction_error(err: &InstructionError) -> Option<Self> {
+        match err {
+            InstructionError::Custom(code) => Self::try_from(*code).ok(),
+            _ => None,
+        }
+    }
+}
+
+impl From<AuctionError> for ProgramError {
+    fn from(e: AuctionError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl fmt::Display for AuctionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.msg())
+    }
+}
+
+impl std::error::Error for AuctionError {}
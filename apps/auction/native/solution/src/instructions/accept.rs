@@ -0,0 +1,219 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_token_program, close_escrow, close_pda, create_ata_idempotent,
+    get_ata, get_mint_decimals, get_pre_fee_amount, get_reverse_pda, transfer,
+    transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::price::{receive_amount, reverse_price_at};
+use crate::state::ReverseAuction;
+
+// Seller delivers sell token to the buyer of a reverse auction and is paid
+// at the current price out of the escrow
+pub fn accept(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    min_price: u64,
+    // Amount of sell token to deliver
+    sell_amt: u64,
+    // Reverse auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let seller = next_account_info(account_iter)?;
+    // Receives the rent of the reverse auction when it is filled
+    let buyer = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let reverse_pda = next_account_info(account_iter)?;
+    let reverse_buy_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let seller_buy_ata = next_account_info(account_iter)?;
+    let buyer_sell_ata = next_account_info(account_iter)?;
+    let buyer_buy_ata = next_account_info(account_iter)?;
+    let token_program_sell = next_account_info(account_iter)?;
+    let token_program_buy = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check seller signed
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token programs of the mints
+    check_token_program(mint_sell, token_program_sell)?;
+    check_token_program(mint_buy, token_program_buy)?;
+    // Check that reverse_pda matches expected PDA
+    if *reverse_pda.key
+        != get_reverse_pda(
+            program_id,
+            buyer.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that reverse_buy_ata matches calculated account
+    if *reverse_buy_ata.key
+        != get_ata(reverse_pda.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_buy_ata matches calculated account
+    if *seller_buy_ata.key
+        != get_ata(seller.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that buyer_sell_ata matches calculated account
+    if *buyer_sell_ata.key
+        != get_ata(buyer.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that buyer_buy_ata matches calculated account
+    if *buyer_buy_ata.key
+        != get_ata(buyer.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = reverse_pda.data.borrow();
+        *ReverseAuction::load(&data)?
+    }; // Drop borrow here
+
+    // Check auction has started
+    if now < auction.start_time {
+        return Err(AuctionError::AuctionNotStarted.into());
+    }
+    // Check auction has not ended
+    if auction.end_time <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+
+    let price = reverse_price_at(&auction, now)?;
+
+    // Check current price is greater than or equal to min_price
+    if price < min_price {
+        return Err(AuctionError::MinPrice.into());
+    }
+    // Check 0 < sell_amt <= remaining
+    if sell_amt == 0 || sell_amt > auction.remaining {
+        return Err(AuctionError::InvalidSellAmount.into());
+    }
+
+    // Send sell token to buyer
+    create_ata_idempotent(
+        seller,
+        mint_sell,
+        buyer,
+        buyer_sell_ata,
+        token_program_sell,
+        sys_program,
+        ata_program,
+    )?;
+    transfer(
+        token_program_sell,
+        seller_sell_ata,
+        mint_sell,
+        buyer_sell_ata,
+        seller,
+        get_pre_fee_amount(mint_sell, sell_amt)?,
+        hook_accounts,
+    )?;
+
+    let id_bytes = id.to_le_bytes();
+    let seeds = &[
+        ReverseAuction::SEED_PREFIX,
+        buyer.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &id_bytes,
+        &[bump],
+    ];
+
+    // Pay seller out of the escrow
+    let pay_amt = receive_amount(
+        sell_amt,
+        price,
+        get_mint_decimals(mint_sell)?,
+        get_mint_decimals(mint_buy)?,
+    )?;
+    if pay_amt > 0 {
+        create_ata_idempotent(
+            seller,
+            mint_buy,
+            seller,
+            seller_buy_ata,
+            token_program_buy,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program_buy,
+            reverse_buy_ata,
+            mint_buy,
+            seller_buy_ata,
+            reverse_pda,
+            pay_amt,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    let remaining = auction.remaining - sell_amt;
+
+    // Keep the auction open until everything is bought
+    if remaining > 0 {
+        let mut data = reverse_pda.data.borrow_mut();
+        ReverseAuction::load_mut(&mut data)?.remaining = remaining;
+        return Ok(());
+    }
+
+    // Refund what is left of the escrow to buyer
+    create_ata_idempotent(
+        seller,
+        mint_buy,
+        buyer,
+        buyer_buy_ata,
+        token_program_buy,
+        sys_program,
+        ata_program,
+    )?;
+    close_escrow(
+        token_program_buy,
+        reverse_buy_ata,
+        mint_buy,
+        buyer_buy_ata,
+        buyer,
+        reverse_pda,
+        seeds,
+        hook_accounts,
+    )?;
+
+    // Close reverse_pda
+    close_pda(reverse_pda, buyer, sys_program)
+}
@@ -0,0 +1,190 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{Sysvar, clock::Clock, rent::Rent},
+};
+
+use super::lib::{
+    check_token_program, get_ata, get_batch_bid_pda, get_batch_pda,
+    get_mint_decimals, get_pda, get_pre_fee_amount, transfer,
+};
+use crate::book::{Book, BookBid};
+use crate::error::AuctionError;
+use crate::price::pay_amount;
+use crate::state::{Auction, Batch, BatchBid};
+
+// Bidder escrows the payment for amount at price. One bid per bidder.
+pub fn batch_bid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Max price of 1 whole sell token
+    price: u64,
+    // Amount of sell token
+    amount: u64,
+    // Auction PDA bump
+    bump: u8,
+    // Batch PDA bump
+    batch_bump: u8,
+    // BatchBid PDA bump
+    bid_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let bidder = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let auction_buy_ata = next_account_info(account_iter)?;
+    let bidder_buy_ata = next_account_info(account_iter)?;
+    let batch_pda = next_account_info(account_iter)?;
+    let bid_pda = next_account_info(account_iter)?;
+    // Token program of mint_buy
+    let token_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check bidder signed
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token program of mint_buy
+    check_token_program(mint_buy, token_program)?;
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that batch_pda matches expected PDA
+    if *batch_pda.key != get_batch_pda(program_id, auction_pda.key, batch_bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that bid_pda matches expected PDA
+    if *bid_pda.key
+        != get_batch_bid_pda(program_id, auction_pda.key, bidder.key, bid_bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_buy_ata matches calculated account
+    if *auction_buy_ata.key
+        != get_ata(auction_pda.key, mint_buy.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = auction_pda.data.borrow();
+        *Auction::load(&data)?
+    }; // Drop borrow here
+    let batch = {
+        let data = batch_pda.data.borrow();
+        *Batch::load(&data)?
+    }; // Drop borrow here
+
+    // Check auction has started
+    if now < auction.start_time {
+        return Err(AuctionError::AuctionNotStarted.into());
+    }
+    // Check auction has not ended
+    if auction.end_time <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+    // Check auction is not cleared yet
+    if batch.cleared != 0 {
+        return Err(AuctionError::AlreadySettled.into());
+    }
+    // Check price >= end_price
+    if price < auction.end_price {
+        return Err(AuctionError::BidTooLow.into());
+    }
+    // Check amount >= min amount of the book, also > 0
+    if amount == 0 || amount < Book::min_amount(auction.remaining) {
+        return Err(AuctionError::InvalidBuyAmount.into());
+    }
+
+    // Insert bid into the book, bids that can not win are rejected instead
+    // of escrowed
+    {
+        let mut data = batch_pda.data.borrow_mut();
+        let batch = Batch::load_mut(&mut data)?;
+        let inserted = batch.book.insert(
+            BookBid {
+                bidder: *bidder.key,
+                price,
+                amount,
+            },
+            auction.remaining,
+        );
+        if !inserted {
+            return Err(AuctionError::BidTooLow.into());
+        }
+        batch.num_bids += 1;
+    } // Drop borrow here
+
+    // Escrow the full payment, the part above the clearing price is
+    // refunded on claim
+    let pay_amt = pay_amount(
+        amount,
+        price,
+        get_mint_decimals(mint_sell)?,
+        get_mint_decimals(mint_buy)?,
+    )?;
+    transfer(
+        token_program,
+        bidder_buy_ata,
+        mint_buy,
+        auction_buy_ata,
+        bidder,
+        get_pre_fee_amount(mint_buy, pay_amt)?,
+        hook_accounts,
+    )?;
+
+    // Create bid_pda, fails if bidder already placed a bid
+    let space = BatchBid::SPACE;
+    let rent = Rent::get()?.minimum_balance(space as usize);
+
+    invoke_signed(
+        &system_instruction::create_account(
+            bidder.key,
+            bid_pda.key,
+            rent,
+            space,
+            program_id,
+        ),
+        &[bidder.clone(), bid_pda.clone(), sys_program.clone()],
+        &[&[
+            BatchBid::SEED_PREFIX,
+            auction_pda.key.as_ref(),
+            bidder.key.as_ref(),
+            &[bid_bump],
+        ]],
+    )?;
+
+    {
+        let mut data = bid_pda.data.borrow_mut();
+        let bid = BatchBid::init(&mut data)?;
+        bid.bidder = *bidder.key;
+        bid.price = price;
+        bid.amount = amount;
+        bid.escrowed = pay_amt;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_token_program, close_escrow, close_pda, create_ata_idempotent,
+    create_result, get_ata, get_batch_bid_pda, get_batch_pda,
+    get_mint_decimals, get_pda, transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::price::pay_amount;
+use crate::state::{Auction, AuctionResult, Batch, BatchBid};
+
+// Winners receive their sell token and the part of the escrow above the
+// clearing price, losers and the overflow of a partial fill are refunded.
+// The auction is closed and its AuctionResult created after the last claim.
+// Anyone can claim for a bidder, so the seller does not depend on every
+// bidder to get the rent of the auction back.
+pub fn batch_claim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Auction PDA bump
+    bump: u8,
+    // Batch PDA bump
+    batch_bump: u8,
+    // BatchBid PDA bump
+    bid_bump: u8,
+    // AuctionResult PDA bump, only used by the last claim
+    result_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Anyone can claim for a bidder and pays for ATAs of the bidder, if
+    // they do not exist
+    let payer = next_account_info(account_iter)?;
+    // Receives the sell token won, the refund and the rent of the bid
+    let bidder = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let auction_sell_ata = next_account_info(account_iter)?;
+    let auction_buy_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let seller_buy_ata = next_account_info(account_iter)?;
+    let bidder_sell_ata = next_account_info(account_iter)?;
+    let bidder_buy_ata = next_account_info(account_iter)?;
+    let batch_pda = next_account_info(account_iter)?;
+    let bid_pda = next_account_info(account_iter)?;
+    let result_pda = next_account_info(account_iter)?;
+    let token_program_sell = next_account_info(account_iter)?;
+    let token_program_buy = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check payer signed
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token programs of the mints
+    check_token_program(mint_sell, token_program_sell)?;
+    check_token_program(mint_buy, token_program_buy)?;
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that batch_pda matches expected PDA
+    if *batch_pda.key != get_batch_pda(program_id, auction_pda.key, batch_bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that bid_pda matches expected PDA
+    if *bid_pda.key
+        != get_batch_bid_pda(program_id, auction_pda.key, bidder.key, bid_bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_sell_ata matches calculated account
+    if *auction_sell_ata.key
+        != get_ata(auction_pda.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that auction_buy_ata matches calculated account
+    if *auction_buy_ata.key
+        != get_ata(auction_pda.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_buy_ata matches calculated account
+    if *seller_buy_ata.key
+        != get_ata(seller.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that bidder_sell_ata matches calculated account
+    if *bidder_sell_ata.key
+        != get_ata(bidder.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that bidder_buy_ata matches calculated account
+    if *bidder_buy_ata.key
+        != get_ata(bidder.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let batch = {
+        let data = batch_pda.data.borrow();
+        *Batch::load(&data)?
+    }; // Drop borrow here
+    let bid = {
+        let data = bid_pda.data.borrow();
+        *BatchBid::load(&data)?
+    }; // Drop borrow here
+
+    // Check auction is cleared
+    if batch.cleared == 0 {
+        return Err(AuctionError::NotSettled.into());
+    }
+
+    let fill = batch.book.fill_of(bidder.key, batch.supply);
+    let pay_amt = if fill > 0 {
+        pay_amount(
+            fill,
+            batch.clearing_price,
+            get_mint_decimals(mint_sell)?,
+            get_mint_decimals(mint_buy)?,
+        )?
+    } else {
+        0
+    };
+    let refund = bid
+        .escrowed
+        .checked_sub(pay_amt)
+        .ok_or(AuctionError::MathOverflow)?;
+
+    let id_bytes = id.to_le_bytes();
+    let seeds = &[
+        Auction::SEED_PREFIX,
+        seller.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &id_bytes,
+        &[bump],
+    ];
+
+    // Send sell token won
+    if fill > 0 {
+        create_ata_idempotent(
+            payer,
+            mint_sell,
+            bidder,
+            bidder_sell_ata,
+            token_program_sell,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program_sell,
+            auction_sell_ata,
+            mint_sell,
+            bidder_sell_ata,
+            auction_pda,
+            fill,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Refund escrow above the clearing price and for the amount not filled
+    if refund > 0 {
+        create_ata_idempotent(
+            payer,
+            mint_buy,
+            bidder,
+            bidder_buy_ata,
+            token_program_buy,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program_buy,
+            auction_buy_ata,
+            mint_buy,
+            bidder_buy_ata,
+            auction_pda,
+            refund,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Close bid_pda
+    close_pda(bid_pda, bidder, sys_program)?;
+
+    let num_claimed = {
+        let mut data = batch_pda.data.borrow_mut();
+        let batch = Batch::load_mut(&mut data)?;
+        batch.num_claimed += 1;
+        batch.num_claimed
+    };
+
+    // Close the auction after the last claim
+    if num_claimed < batch.num_bids {
+        return Ok(());
+    }
+
+    close_escrow(
+        token_program_sell,
+        auction_sell_ata,
+        mint_sell,
+        seller_sell_ata,
+        seller,
+        auction_pda,
+        seeds,
+        hook_accounts,
+    )?;
+    close_escrow(
+        token_program_buy,
+        auction_buy_ata,
+        mint_buy,
+        seller_buy_ata,
+        seller,
+        auction_pda,
+        seeds,
+        hook_accounts,
+    )?;
+    close_pda(batch_pda, seller, sys_program)?;
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = auction_pda.data.borrow();
+        *Auction::load(&data)?
+    }; // Drop borrow here
+
+    // Record the outcome, sold out if the bids covered the supply
+    let outcome = if auction.sold == batch.supply {
+        AuctionResult::SOLD
+    } else {
+        AuctionResult::EXPIRED
+    };
+    create_result(
+        program_id,
+        auction_pda,
+        result_pda,
+        sys_program,
+        result_bump,
+        AuctionResult {
+            seller: auction.seller,
+            mint_sell: auction.mint_sell,
+            mint_buy: auction.mint_buy,
+            buyer: Pubkey::default(),
+            id,
+            sold: auction.sold,
+            proceeds: auction.proceeds,
+            price: batch.clearing_price,
+            closed_at: now,
+            outcome,
+        },
+    )?;
+
+    // Close auction_pda
+    close_pda(auction_pda, seller, sys_program)
+}
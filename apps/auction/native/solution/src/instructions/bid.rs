@@ -0,0 +1,174 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_token_program, create_ata_idempotent, get_ata, get_english_pda,
+    get_pre_fee_amount, transfer, transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::state::EnglishAuction;
+
+pub fn bid(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Amount of buy token offered for the whole lot
+    amount: u64,
+    // English auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let bidder = next_account_info(account_iter)?;
+    // Highest bidder so far, refunded when outbid
+    let prev_bidder = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let english_pda = next_account_info(account_iter)?;
+    let english_buy_ata = next_account_info(account_iter)?;
+    let bidder_buy_ata = next_account_info(account_iter)?;
+    let prev_bidder_buy_ata = next_account_info(account_iter)?;
+    // Token program of mint_buy
+    let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check bidder signed
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token program of mint_buy
+    check_token_program(mint_buy, token_program)?;
+    // Check that english_pda matches expected PDA
+    if *english_pda.key
+        != get_english_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that english_buy_ata matches calculated account
+    if *english_buy_ata.key
+        != get_ata(english_pda.key, mint_buy.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that bidder_buy_ata matches calculated account
+    if *bidder_buy_ata.key
+        != get_ata(bidder.key, mint_buy.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = english_pda.data.borrow();
+        *EnglishAuction::load(&data)?
+    }; // Drop borrow here
+
+    // Check auction has started
+    if now < auction.start_time {
+        return Err(AuctionError::AuctionNotStarted.into());
+    }
+    // Check auction has not ended
+    if auction.end_time <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+
+    // Check amount >= reserve for the first bid and
+    // amount >= bid + min_increment after that
+    let min_bid = if auction.bid == 0 {
+        auction.reserve
+    } else {
+        auction
+            .bid
+            .checked_add(auction.min_increment)
+            .ok_or(AuctionError::MathOverflow)?
+    };
+    if amount < min_bid {
+        return Err(AuctionError::BidTooLow.into());
+    }
+
+    // Escrow bid
+    transfer(
+        token_program,
+        bidder_buy_ata,
+        mint_buy,
+        english_buy_ata,
+        bidder,
+        get_pre_fee_amount(mint_buy, amount)?,
+        hook_accounts,
+    )?;
+
+    // Refund the previous highest bid
+    if auction.bid > 0 {
+        // Check prev_bidder is the highest bidder
+        if *prev_bidder.key != auction.bidder {
+            return Err(AuctionError::InvalidAccount.into());
+        }
+        // Check that prev_bidder_buy_ata matches calculated account
+        let ata = get_ata(prev_bidder.key, mint_buy.key, token_program.key);
+        if *prev_bidder_buy_ata.key != ata {
+            return Err(AuctionError::InvalidTokenAccount.into());
+        }
+
+        // Closing the ATA must not block outbids
+        create_ata_idempotent(
+            bidder,
+            mint_buy,
+            prev_bidder,
+            prev_bidder_buy_ata,
+            token_program,
+            sys_program,
+            ata_program,
+        )?;
+
+        let id_bytes = id.to_le_bytes();
+        let seeds = &[
+            EnglishAuction::SEED_PREFIX,
+            seller.key.as_ref(),
+            mint_sell.key.as_ref(),
+            mint_buy.key.as_ref(),
+            &id_bytes,
+            &[bump],
+        ];
+
+        transfer_from_pda(
+            token_program,
+            english_buy_ata,
+            mint_buy,
+            prev_bidder_buy_ata,
+            english_pda,
+            auction.bid,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Store highest bid and extend auctions that are about to end
+    let mut data = english_pda.data.borrow_mut();
+    let auction = EnglishAuction::load_mut(&mut data)?;
+    auction.bidder = *bidder.key;
+    auction.bid = amount;
+    if auction.end_time - now < auction.extension {
+        auction.end_time = now
+            .checked_add(auction.extension)
+            .ok_or(AuctionError::MathOverflow)?;
+    }
+
+    Ok(())
+}
@@ -1,22 +1,43 @@
-use borsh::BorshDeserialize;
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
+    program::invoke_signed,
     program_error::ProgramError,
     pubkey::Pubkey,
-    sysvar::{Sysvar, clock::Clock},
+    system_instruction,
+    sysvar::{Sysvar, clock::Clock, rent::Rent},
 };
 
 use super::lib::{
-    close_ata, get_ata, get_pda, get_token_balance, transfer, transfer_from_pda,
+    check_token_program, close_escrow, close_pda, create_ata_idempotent,
+    create_result, current_price, get_ata, get_config_pda, get_mint_decimals,
+    get_pda, get_pre_fee_amount, get_purchase_pda, get_token_balance,
+    get_vesting_pda, is_native_mint, transfer, transfer_from_pda,
+    transfer_lamports,
 };
-use crate::state::Auction;
+use crate::error::AuctionError;
+use crate::merkle;
+use crate::price::pay_amount;
+use crate::state::{Auction, AuctionResult, Config, Purchase, Vesting};
 
 pub fn buy(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    id: u64,
     max_price: u64,
+    // Amount of sell token to buy
+    buy_amt: u64,
     // Auction PDA bump
     bump: u8,
+    // Purchase PDA bump
+    purchase_bump: u8,
+    // Config PDA bump
+    config_bump: u8,
+    // AuctionResult PDA bump
+    result_bump: u8,
+    // Vesting PDA bump
+    vesting_bump: u8,
+    // Merkle proof of the buyer
+    proof: &[[u8; 32]],
 ) -> Result<(), ProgramError> {
     let account_iter = &mut accounts.iter();
 
@@ -27,36 +48,99 @@ pub fn buy(
     let auction_pda = next_account_info(account_iter)?;
     let auction_sell_ata = next_account_info(account_iter)?;
     let buyer_sell_ata = next_account_info(account_iter)?;
+    // mint_buy ATAs are not used if the auction is paid in SOL
     let buyer_buy_ata = next_account_info(account_iter)?;
     let seller_buy_ata = next_account_info(account_iter)?;
-    let token_program = next_account_info(account_iter)?;
+    let token_program_sell = next_account_info(account_iter)?;
+    let token_program_buy = next_account_info(account_iter)?;
     let sys_program = next_account_info(account_iter)?;
+    // Only used if the auction has a wallet_cap
+    let purchase_pda = next_account_info(account_iter)?;
+    let config_pda = next_account_info(account_iter)?;
+    let treasury = next_account_info(account_iter)?;
+    let treasury_buy_ata = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    // Only used if the auction is pegged to an oracle
+    let oracle_account = next_account_info(account_iter)?;
+    // Only used by the buy that sells out the auction
+    let result_pda = next_account_info(account_iter)?;
+    // Receives what is left in auction_sell_ata when the auction sells out
+    let seller_sell_ata = next_account_info(account_iter)?;
+    // Only used if the auction has vesting
+    let vesting_pda = next_account_info(account_iter)?;
+    let vesting_sell_ata = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
 
     // Check buyer signed
     if !buyer.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
+    // Check the token programs of the mints
+    check_token_program(mint_sell, token_program_sell)?;
+    check_token_program(mint_buy, token_program_buy)?;
     // Check that auction_pda matches expected PDA
     if *auction_pda.key
-        != get_pda(program_id, seller.key, mint_sell.key, mint_buy.key, bump)?
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
     {
         return Err(ProgramError::InvalidSeeds);
     }
     // Check that auction_sell_ata matches calculated account
-    if *auction_sell_ata.key != get_ata(auction_pda.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+    if *auction_sell_ata.key
+        != get_ata(auction_pda.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
     // Check that buyer_sell_ata matches calculated account
-    if *buyer_sell_ata.key != get_ata(buyer.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+    if *buyer_sell_ata.key
+        != get_ata(buyer.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
-    // Check that buyer_buy_ata matches calculated account
-    if *buyer_buy_ata.key != get_ata(buyer.key, mint_buy.key) {
-        return Err(ProgramError::InvalidArgument);
+
+    // Native mint_buy is paid in lamports through the system program
+    let pay_sol = is_native_mint(mint_buy.key);
+
+    if !pay_sol {
+        // Check that buyer_buy_ata matches calculated account
+        if *buyer_buy_ata.key
+            != get_ata(buyer.key, mint_buy.key, token_program_buy.key)
+        {
+            return Err(AuctionError::InvalidTokenAccount.into());
+        }
+        // Check that seller_buy_ata matches calculated account
+        if *seller_buy_ata.key
+            != get_ata(seller.key, mint_buy.key, token_program_buy.key)
+        {
+            return Err(AuctionError::InvalidTokenAccount.into());
+        }
+        // Check that treasury_buy_ata matches calculated account
+        if *treasury_buy_ata.key
+            != get_ata(treasury.key, mint_buy.key, token_program_buy.key)
+        {
+            return Err(AuctionError::InvalidTokenAccount.into());
+        }
     }
-    // Check that seller_buy_ata matches calculated account
-    if *seller_buy_ata.key != get_ata(seller.key, mint_buy.key) {
-        return Err(ProgramError::InvalidArgument);
+    // Check that config_pda matches expected PDA
+    if *config_pda.key != get_config_pda(program_id, config_bump)? {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let config = {
+        let data = config_pda.data.borrow();
+        *Config::load(&data)?
+    }; // Drop borrow here
+
+    // Check that treasury is the one in config
+    if *treasury.key != config.treasury {
+        return Err(AuctionError::InvalidAccount.into());
     }
 
     let clock = Clock::get()?;
@@ -64,70 +148,325 @@ pub fn buy(
 
     let auction = {
         let data = auction_pda.data.borrow();
-        Auction::try_from_slice(&data)?
+        *Auction::load(&data)?
     }; // Drop borrow here
 
+    // Check auction is not a batch auction, those take bids with BatchBid
+    if auction.batch != 0 {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
     // Check auction has started
-    assert!(auction.start_time <= now, "auction not started");
+    if now < auction.start_time {
+        return Err(AuctionError::AuctionNotStarted.into());
+    }
     // Check auction has not ended
-    assert!(now < auction.end_time, "auction ended");
-
-    // Calculate price
-    let price_decrease = (auction.start_price - auction.end_price)
-        * (now - auction.start_time)
-        / (auction.end_time - auction.start_time);
+    if auction.end_time <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
 
-    let price = auction.start_price - price_decrease;
+    // Calculate price, GetPrice quotes the same price
+    let price = current_price(&auction, now, oracle_account)?;
 
     // Check current price is greater than or equal to end_price
-    assert!(price >= auction.end_price, "price < min");
+    if price < auction.end_price {
+        return Err(AuctionError::InvalidCurrentPrice.into());
+    }
 
     // Check current price is less than or equal to max_price
-    assert!(price <= max_price, "price > max");
+    if price > max_price {
+        return Err(AuctionError::MaxPrice.into());
+    }
+
+    // Check 0 < buy_amt <= remaining
+    if buy_amt == 0 || buy_amt > auction.remaining {
+        return Err(AuctionError::InvalidBuyAmount.into());
+    }
+
+    // Check buyer is allowed to buy
+    if auction.merkle_root != [0; 32]
+        && !merkle::verify(&auction.merkle_root, merkle::leaf(buyer.key), proof)
+    {
+        return Err(AuctionError::NotAllowed.into());
+    }
+
+    // Check buyer stays within wallet_cap
+    if auction.wallet_cap > 0 {
+        // Check that purchase_pda matches expected PDA
+        if *purchase_pda.key
+            != get_purchase_pda(
+                program_id,
+                auction_pda.key,
+                buyer.key,
+                purchase_bump,
+            )?
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        // Create purchase_pda on the buyer's first buy
+        if purchase_pda.lamports() == 0 {
+            let space = Purchase::SPACE;
+            let rent = Rent::get()?.minimum_balance(space as usize);
+
+            invoke_signed(
+                &system_instruction::create_account(
+                    buyer.key,
+                    purchase_pda.key,
+                    rent,
+                    space,
+                    program_id,
+                ),
+                &[buyer.clone(), purchase_pda.clone(), sys_program.clone()],
+                &[&[
+                    Purchase::SEED_PREFIX,
+                    auction_pda.key.as_ref(),
+                    buyer.key.as_ref(),
+                    &[purchase_bump],
+                ]],
+            )?;
+
+            let mut data = purchase_pda.data.borrow_mut();
+            Purchase::init(&mut data)?;
+        }
+
+        let mut data = purchase_pda.data.borrow_mut();
+        let purchase = Purchase::load_mut(&mut data)?;
+        purchase.bought = purchase
+            .bought
+            .checked_add(buy_amt)
+            .ok_or(AuctionError::MathOverflow)?;
+        if purchase.bought > auction.wallet_cap {
+            return Err(AuctionError::WalletCapExceeded.into());
+        }
+    }
 
     // Calculate amount of buy token to send to seller
-    let sell_amt = get_token_balance(auction_sell_ata)?;
-    let buy_amt = sell_amt * price / (1e6 as u64);
+    // Round up so that small buys can not be paid with 0
+    let decimals_buy = if pay_sol {
+        spl_token_interface::native_mint::DECIMALS
+    } else {
+        get_mint_decimals(mint_buy)?
+    };
+    let pay_amt = pay_amount(
+        buy_amt,
+        price,
+        get_mint_decimals(mint_sell)?,
+        decimals_buy,
+    )?;
 
-    // Send buy token to seller
-    transfer(token_program, buyer_buy_ata, seller_buy_ata, buyer, buy_amt)?;
+    let fee = config.fee(pay_amt);
+    if pay_sol {
+        // Send protocol fee to treasury
+        if fee > 0 {
+            transfer_lamports(buyer, treasury, sys_program, fee)?;
+        }
+        // Send the rest of the lamports to seller
+        transfer_lamports(buyer, seller, sys_program, pay_amt - fee)?;
+    } else {
+        // Send protocol fee to treasury
+        if fee > 0 {
+            create_ata_idempotent(
+                buyer,
+                mint_buy,
+                treasury,
+                treasury_buy_ata,
+                token_program_buy,
+                sys_program,
+                ata_program,
+            )?;
+            transfer(
+                token_program_buy,
+                buyer_buy_ata,
+                mint_buy,
+                treasury_buy_ata,
+                buyer,
+                get_pre_fee_amount(mint_buy, fee)?,
+                hook_accounts,
+            )?;
+        }
+        // Send the rest of the buy token to seller
+        transfer(
+            token_program_buy,
+            buyer_buy_ata,
+            mint_buy,
+            seller_buy_ata,
+            buyer,
+            get_pre_fee_amount(mint_buy, pay_amt - fee)?,
+            hook_accounts,
+        )?;
+    }
 
     // Send sell token to buyer
+    let id_bytes = id.to_le_bytes();
     let seeds = &[
         Auction::SEED_PREFIX,
         seller.key.as_ref(),
         mint_sell.key.as_ref(),
         mint_buy.key.as_ref(),
+        &id_bytes,
         &[bump],
     ];
 
-    transfer_from_pda(
-        token_program,
-        auction_sell_ata,
-        buyer_sell_ata,
-        auction_pda,
-        sell_amt,
-        seeds,
-    )?;
+    if auction.vest_duration > 0 {
+        // Check that vesting_pda matches expected PDA
+        if *vesting_pda.key
+            != get_vesting_pda(
+                program_id,
+                auction_pda.key,
+                buyer.key,
+                vesting_bump,
+            )?
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        // Check that vesting_sell_ata matches calculated account
+        if *vesting_sell_ata.key
+            != get_ata(vesting_pda.key, mint_sell.key, token_program_sell.key)
+        {
+            return Err(AuctionError::InvalidTokenAccount.into());
+        }
 
-    // Close auction_sell_ata
-    close_ata(token_program, auction_sell_ata, seller, auction_pda, seeds)?;
+        // Create vesting_pda on the buyer's first buy
+        if vesting_pda.lamports() == 0 {
+            let space = Vesting::SPACE;
+            let rent = Rent::get()?.minimum_balance(space as usize);
 
-    // Close auction_pda
-    // Get PDA balance and transfer lamports directly
-    let pda_lamports = auction_pda.lamports();
+            invoke_signed(
+                &system_instruction::create_account(
+                    buyer.key,
+                    vesting_pda.key,
+                    rent,
+                    space,
+                    program_id,
+                ),
+                &[buyer.clone(), vesting_pda.clone(), sys_program.clone()],
+                &[&[
+                    Vesting::SEED_PREFIX,
+                    auction_pda.key.as_ref(),
+                    buyer.key.as_ref(),
+                    &[vesting_bump],
+                ]],
+            )?;
+
+            let mut data = vesting_pda.data.borrow_mut();
+            let vesting = Vesting::init(&mut data)?;
+            vesting.auction = *auction_pda.key;
+            vesting.buyer = *buyer.key;
+            vesting.mint_sell = *mint_sell.key;
+            vesting.start = auction.end_time;
+            vesting.cliff = auction.vest_cliff;
+            vesting.duration = auction.vest_duration;
+        }
+
+        create_ata_idempotent(
+            buyer,
+            mint_sell,
+            vesting_pda,
+            vesting_sell_ata,
+            token_program_sell,
+            sys_program,
+            ata_program,
+        )?;
+        // Lock sell token in vesting_sell_ata
+        let before = get_token_balance(vesting_sell_ata)?;
+        transfer_from_pda(
+            token_program_sell,
+            auction_sell_ata,
+            mint_sell,
+            vesting_sell_ata,
+            auction_pda,
+            buy_amt,
+            seeds,
+            hook_accounts,
+        )?;
 
-    **auction_pda.try_borrow_mut_lamports()? = 0;
-    **seller.try_borrow_mut_lamports()? = seller
-        .lamports()
-        .checked_add(pda_lamports)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
+        // Vest what was received, less than buy_amt if mint_sell has a
+        // transfer fee
+        let received = get_token_balance(vesting_sell_ata)? - before;
+        let mut data = vesting_pda.data.borrow_mut();
+        Vesting::load_mut(&mut data)?.total += received;
+    } else {
+        transfer_from_pda(
+            token_program_sell,
+            auction_sell_ata,
+            mint_sell,
+            buyer_sell_ata,
+            auction_pda,
+            buy_amt,
+            seeds,
+            hook_accounts,
+        )?;
+    }
 
-    // Clear out data
-    auction_pda.resize(0)?;
+    let remaining = auction.remaining - buy_amt;
+    let sold = auction.sold + buy_amt;
+    let proceeds = auction
+        .proceeds
+        .checked_add(pay_amt)
+        .ok_or(AuctionError::MathOverflow)?;
 
-    // Assign the account to the System Program
-    auction_pda.assign(sys_program.key);
+    // Keep the auction open until everything is sold
+    if remaining > 0 {
+        let mut data = auction_pda.data.borrow_mut();
+        let auction = Auction::load_mut(&mut data)?;
+        auction.remaining = remaining;
+        auction.sold = sold;
+        auction.proceeds = proceeds;
+        return Ok(());
+    }
 
-    Ok(())
+    // Record the sale
+    create_result(
+        program_id,
+        auction_pda,
+        result_pda,
+        sys_program,
+        result_bump,
+        AuctionResult {
+            seller: auction.seller,
+            mint_sell: auction.mint_sell,
+            mint_buy: auction.mint_buy,
+            buyer: *buyer.key,
+            id,
+            sold,
+            proceeds,
+            price,
+            closed_at: now,
+            outcome: AuctionResult::SOLD,
+        },
+    )?;
+
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    // Anyone can send sell token to auction_sell_ata, send what is left to
+    // seller and close auction_sell_ata
+    if get_token_balance(auction_sell_ata)? > 0 {
+        create_ata_idempotent(
+            buyer,
+            mint_sell,
+            seller,
+            seller_sell_ata,
+            token_program_sell,
+            sys_program,
+            ata_program,
+        )?;
+    }
+    close_escrow(
+        token_program_sell,
+        auction_sell_ata,
+        mint_sell,
+        seller_sell_ata,
+        seller,
+        auction_pda,
+        seeds,
+        hook_accounts,
+    )?;
+
+    // Close auction_pda
+    close_pda(auction_pda, seller, sys_program)
 }
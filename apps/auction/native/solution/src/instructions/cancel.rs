@@ -2,18 +2,24 @@ use solana_program::{
     account_info::{AccountInfo, next_account_info},
     program_error::ProgramError,
     pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
 };
 
 use super::lib::{
-    close_ata, get_ata, get_pda, get_token_balance, transfer_from_pda,
+    check_token_program, close_ata, close_pda, create_result, get_ata, get_pda,
+    get_token_balance, transfer_from_pda,
 };
-use crate::state::Auction;
+use crate::error::AuctionError;
+use crate::state::{Auction, AuctionResult};
 
 pub fn cancel(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
+    id: u64,
     // Auction PDA bump
     bump: u8,
+    // AuctionResult PDA bump
+    result_bump: u8,
 ) -> Result<(), ProgramError> {
     let account_iter = &mut accounts.iter();
 
@@ -23,67 +29,114 @@ pub fn cancel(
     let auction_pda = next_account_info(account_iter)?;
     let auction_sell_ata = next_account_info(account_iter)?;
     let seller_sell_ata = next_account_info(account_iter)?;
+    // Token program of mint_sell
     let token_program = next_account_info(account_iter)?;
     let sys_program = next_account_info(account_iter)?;
+    let result_pda = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
 
     // Check seller signed
     if !seller.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
+    // Check the token program of mint_sell
+    check_token_program(mint_sell, token_program)?;
     // Check that auction_pda matches expected PDA
     if *auction_pda.key
-        != get_pda(program_id, seller.key, mint_sell.key, mint_buy.key, bump)?
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
     {
         return Err(ProgramError::InvalidSeeds);
     }
     // Check that auction_sell_ata matches calculated account
-    if *auction_sell_ata.key != get_ata(auction_pda.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+    if *auction_sell_ata.key
+        != get_ata(auction_pda.key, mint_sell.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
     // Check that buyer_sell_ata matches calculated account
-    if *seller_sell_ata.key != get_ata(seller.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let auction = {
+        let data = auction_pda.data.borrow();
+        *Auction::load(&data)?
+    }; // Drop borrow here
+
+    // Check auction is not a batch auction, bids are escrowed until Clear
+    if auction.batch != 0 {
+        return Err(AuctionError::InvalidAuctionKind.into());
     }
 
     // Get sell amount locked in auction_sell_ata
     let sell_amt = get_token_balance(auction_sell_ata)?;
 
     // Send sell token to seller
+    let id_bytes = id.to_le_bytes();
     let seeds = &[
         Auction::SEED_PREFIX,
         seller.key.as_ref(),
         mint_sell.key.as_ref(),
         mint_buy.key.as_ref(),
+        &id_bytes,
         &[bump],
     ];
 
     transfer_from_pda(
         token_program,
         auction_sell_ata,
+        mint_sell,
         seller_sell_ata,
         auction_pda,
         sell_amt,
         seeds,
+        hook_accounts,
     )?;
 
     // Close auction_sell_ata
-    close_ata(token_program, auction_sell_ata, seller, auction_pda, seeds)?;
-
-    // Close auction_pda
-    // Get PDA balance and transfer lamports directly
-    let pda_lamports = auction_pda.lamports();
-
-    **auction_pda.try_borrow_mut_lamports()? = 0;
-    **seller.try_borrow_mut_lamports()? = seller
-        .lamports()
-        .checked_add(pda_lamports)
-        .ok_or(ProgramError::ArithmeticOverflow)?;
+    close_ata(
+        token_program,
+        auction_sell_ata,
+        mint_sell,
+        seller,
+        auction_pda,
+        seeds,
+    )?;
 
-    // Clear out data
-    auction_pda.resize(0)?;
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
 
-    // Assign the account to the System Program
-    auction_pda.assign(sys_program.key);
+    // Record the cancellation
+    create_result(
+        program_id,
+        auction_pda,
+        result_pda,
+        sys_program,
+        result_bump,
+        AuctionResult {
+            seller: auction.seller,
+            mint_sell: auction.mint_sell,
+            mint_buy: auction.mint_buy,
+            buyer: Pubkey::default(),
+            id,
+            sold: auction.sold,
+            proceeds: auction.proceeds,
+            price: 0,
+            closed_at: now,
+            outcome: AuctionResult::CANCELLED,
+        },
+    )?;
 
-    Ok(())
+    // Close auction_pda
+    close_pda(auction_pda, seller, sys_program)
 }
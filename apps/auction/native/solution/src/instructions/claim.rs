@@ -0,0 +1,133 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_token_program, close_escrow, close_pda, create_ata_idempotent,
+    get_ata, get_vesting_pda, transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::state::Vesting;
+
+// Buyer receives the part of the vested sell token not claimed yet.
+// The Vesting PDA and its ATA are closed after the last claim.
+pub fn claim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Vesting PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let buyer = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let vesting_pda = next_account_info(account_iter)?;
+    let vesting_sell_ata = next_account_info(account_iter)?;
+    let buyer_sell_ata = next_account_info(account_iter)?;
+    // Token program of mint_sell
+    let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check buyer signed
+    if !buyer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let vesting = {
+        let data = vesting_pda.data.borrow();
+        *Vesting::load(&data)?
+    }; // Drop borrow here
+
+    // Check the token program of mint_sell
+    check_token_program(mint_sell, token_program)?;
+    // Check that vesting_pda matches expected PDA
+    if *vesting_pda.key
+        != get_vesting_pda(program_id, &vesting.auction, buyer.key, bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check mint_sell
+    if *mint_sell.key != vesting.mint_sell {
+        return Err(AuctionError::InvalidAccount.into());
+    }
+    // Check that vesting_sell_ata matches calculated account
+    if *vesting_sell_ata.key
+        != get_ata(vesting_pda.key, mint_sell.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that buyer_sell_ata matches calculated account
+    if *buyer_sell_ata.key
+        != get_ata(buyer.key, mint_sell.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    // Check something vested since the last claim
+    let amt = vesting.vested(now) - vesting.claimed;
+    if amt == 0 {
+        return Err(AuctionError::NothingToClaim.into());
+    }
+
+    let seeds = &[
+        Vesting::SEED_PREFIX,
+        vesting.auction.as_ref(),
+        buyer.key.as_ref(),
+        &[bump],
+    ];
+
+    // Send vested sell token to buyer
+    create_ata_idempotent(
+        buyer,
+        mint_sell,
+        buyer,
+        buyer_sell_ata,
+        token_program,
+        sys_program,
+        ata_program,
+    )?;
+    transfer_from_pda(
+        token_program,
+        vesting_sell_ata,
+        mint_sell,
+        buyer_sell_ata,
+        vesting_pda,
+        amt,
+        seeds,
+        hook_accounts,
+    )?;
+
+    let claimed = vesting.claimed + amt;
+
+    // Keep the vesting open until everything is claimed
+    if claimed < vesting.total {
+        let mut data = vesting_pda.data.borrow_mut();
+        Vesting::load_mut(&mut data)?.claimed = claimed;
+        return Ok(());
+    }
+
+    // Anyone can send sell token to vesting_sell_ata, send what is left to
+    // buyer and close vesting_sell_ata
+    close_escrow(
+        token_program,
+        vesting_sell_ata,
+        mint_sell,
+        buyer_sell_ata,
+        buyer,
+        vesting_pda,
+        seeds,
+        hook_accounts,
+    )?;
+
+    // Close vesting_pda
+    close_pda(vesting_pda, buyer, sys_program)
+}
@@ -0,0 +1,282 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_token_program, close_escrow, close_pda, create_ata_idempotent,
+    create_result, get_ata, get_batch_pda, get_mint_decimals, get_pda,
+    transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::price::{pay_amount, price_at};
+use crate::state::{Auction, AuctionResult, Batch};
+
+// Anyone can clear a batch auction once the bids in the book cover the
+// supply at the current price, or after end_time.
+// Winners pay the lowest winning price, capped at start_price. If demand
+// falls short at end_time, every bid wins in full at end_price.
+// Seller receives the proceeds and the unsold sell token. Bidders collect
+// with BatchClaim.
+pub fn clear(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Auction PDA bump
+    bump: u8,
+    // Batch PDA bump
+    batch_bump: u8,
+    // AuctionResult PDA bump, only used if there are no bids
+    result_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Pays for ATAs of the seller, if they do not exist
+    let payer = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let auction_sell_ata = next_account_info(account_iter)?;
+    let auction_buy_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let seller_buy_ata = next_account_info(account_iter)?;
+    let batch_pda = next_account_info(account_iter)?;
+    let result_pda = next_account_info(account_iter)?;
+    let token_program_sell = next_account_info(account_iter)?;
+    let token_program_buy = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check payer signed
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token programs of the mints
+    check_token_program(mint_sell, token_program_sell)?;
+    check_token_program(mint_buy, token_program_buy)?;
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that batch_pda matches expected PDA
+    if *batch_pda.key != get_batch_pda(program_id, auction_pda.key, batch_bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_sell_ata matches calculated account
+    if *auction_sell_ata.key
+        != get_ata(auction_pda.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that auction_buy_ata matches calculated account
+    if *auction_buy_ata.key
+        != get_ata(auction_pda.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_buy_ata matches calculated account
+    if *seller_buy_ata.key
+        != get_ata(seller.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = auction_pda.data.borrow();
+        *Auction::load(&data)?
+    }; // Drop borrow here
+    let batch = {
+        let data = batch_pda.data.borrow();
+        *Batch::load(&data)?
+    }; // Drop borrow here
+
+    // Check auction is not cleared yet
+    if batch.cleared != 0 {
+        return Err(AuctionError::AlreadySettled.into());
+    }
+
+    let supply = auction.remaining;
+    let demand = batch
+        .book
+        .bids()
+        .iter()
+        .fold(0u64, |total, b| total.saturating_add(b.amount));
+
+    let clearing_price = if demand >= supply {
+        // Lowest winning bid, demand meets supply once the price drops to it
+        let marginal = batch.book.clearing_price();
+        if now < auction.end_time && price_at(&auction, now)? > marginal {
+            return Err(AuctionError::AuctionNotEnded.into());
+        }
+        marginal.min(auction.start_price)
+    } else {
+        // Check auction has ended
+        if now < auction.end_time {
+            return Err(AuctionError::AuctionNotEnded.into());
+        }
+        auction.end_price
+    };
+
+    let decimals_sell = get_mint_decimals(mint_sell)?;
+    let decimals_buy = get_mint_decimals(mint_buy)?;
+
+    let mut sold: u64 = 0;
+    let mut proceeds: u64 = 0;
+    for (_, fill) in batch.book.fills(supply) {
+        sold += fill;
+        proceeds = proceeds
+            .checked_add(pay_amount(
+                fill,
+                clearing_price,
+                decimals_sell,
+                decimals_buy,
+            )?)
+            .ok_or(AuctionError::MathOverflow)?;
+    }
+
+    let id_bytes = id.to_le_bytes();
+    let seeds = &[
+        Auction::SEED_PREFIX,
+        seller.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &id_bytes,
+        &[bump],
+    ];
+
+    // Send proceeds to seller
+    if proceeds > 0 {
+        create_ata_idempotent(
+            payer,
+            mint_buy,
+            seller,
+            seller_buy_ata,
+            token_program_buy,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program_buy,
+            auction_buy_ata,
+            mint_buy,
+            seller_buy_ata,
+            auction_pda,
+            proceeds,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Send unsold sell token back to seller
+    let unsold = supply - sold;
+    if unsold > 0 {
+        create_ata_idempotent(
+            payer,
+            mint_sell,
+            seller,
+            seller_sell_ata,
+            token_program_sell,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program_sell,
+            auction_sell_ata,
+            mint_sell,
+            seller_sell_ata,
+            auction_pda,
+            unsold,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Nothing left to claim
+    if batch.num_bids == 0 {
+        close_escrow(
+            token_program_sell,
+            auction_sell_ata,
+            mint_sell,
+            seller_sell_ata,
+            seller,
+            auction_pda,
+            seeds,
+            hook_accounts,
+        )?;
+        close_escrow(
+            token_program_buy,
+            auction_buy_ata,
+            mint_buy,
+            seller_buy_ata,
+            seller,
+            auction_pda,
+            seeds,
+            hook_accounts,
+        )?;
+        close_pda(batch_pda, seller, sys_program)?;
+
+        create_result(
+            program_id,
+            auction_pda,
+            result_pda,
+            sys_program,
+            result_bump,
+            AuctionResult {
+                seller: auction.seller,
+                mint_sell: auction.mint_sell,
+                mint_buy: auction.mint_buy,
+                buyer: Pubkey::default(),
+                id,
+                sold: auction.sold,
+                proceeds: auction.proceeds,
+                price: 0,
+                closed_at: now,
+                outcome: AuctionResult::EXPIRED,
+            },
+        )?;
+
+        return close_pda(auction_pda, seller, sys_program);
+    }
+
+    {
+        let mut data = batch_pda.data.borrow_mut();
+        let batch = Batch::load_mut(&mut data)?;
+        batch.cleared = 1;
+        batch.clearing_price = clearing_price;
+        batch.supply = supply;
+    }
+
+    let mut data = auction_pda.data.borrow_mut();
+    let auction = Auction::load_mut(&mut data)?;
+    auction.sold += sold;
+    auction.proceeds = auction
+        .proceeds
+        .checked_add(proceeds)
+        .ok_or(AuctionError::MathOverflow)?;
+
+    Ok(())
+}
@@ -0,0 +1,140 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{Sysvar, clock::Clock, rent::Rent},
+};
+
+use super::lib::{
+    check_token_program, get_ata, get_commitment_pda, get_pre_fee_amount,
+    get_sealed_pda, transfer,
+};
+use crate::error::AuctionError;
+use crate::state::{Commitment, SealedAuction};
+
+pub fn commit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Commitment::hash(price, amount, salt)
+    hash: [u8; 32],
+    // Sealed auction PDA bump
+    bump: u8,
+    // Commitment PDA bump
+    commitment_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let bidder = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let sealed_pda = next_account_info(account_iter)?;
+    let sealed_buy_ata = next_account_info(account_iter)?;
+    let bidder_buy_ata = next_account_info(account_iter)?;
+    let commitment_pda = next_account_info(account_iter)?;
+    // Token program of mint_buy
+    let token_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check bidder signed
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token program of mint_buy
+    check_token_program(mint_buy, token_program)?;
+    // Check that sealed_pda matches expected PDA
+    if *sealed_pda.key
+        != get_sealed_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that commitment_pda matches expected PDA
+    if *commitment_pda.key
+        != get_commitment_pda(
+            program_id,
+            sealed_pda.key,
+            bidder.key,
+            commitment_bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that sealed_buy_ata matches calculated account
+    if *sealed_buy_ata.key
+        != get_ata(sealed_pda.key, mint_buy.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = sealed_pda.data.borrow();
+        *SealedAuction::load(&data)?
+    }; // Drop borrow here
+
+    // Check commit phase has not ended
+    if auction.commit_end <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+
+    // Escrow deposit
+    transfer(
+        token_program,
+        bidder_buy_ata,
+        mint_buy,
+        sealed_buy_ata,
+        bidder,
+        get_pre_fee_amount(mint_buy, auction.deposit)?,
+        hook_accounts,
+    )?;
+
+    let space = Commitment::SPACE;
+    let rent = Rent::get()?.minimum_balance(space as usize);
+
+    // Create commitment_pda, fails if bidder already committed
+    invoke_signed(
+        &system_instruction::create_account(
+            bidder.key,
+            commitment_pda.key,
+            rent,
+            space,
+            program_id,
+        ),
+        &[bidder.clone(), commitment_pda.clone(), sys_program.clone()],
+        &[&[
+            Commitment::SEED_PREFIX,
+            sealed_pda.key.as_ref(),
+            bidder.key.as_ref(),
+            &[commitment_bump],
+        ]],
+    )?;
+
+    // Store Commitment state
+    {
+        let mut data = commitment_pda.data.borrow_mut();
+        let commitment = Commitment::init(&mut data)?;
+        commitment.bidder = *bidder.key;
+        commitment.hash = hash;
+        commitment.reveal_end = auction.reveal_end;
+        commitment.escrowed = auction.deposit;
+    }
+
+    let mut data = sealed_pda.data.borrow_mut();
+    SealedAuction::load_mut(&mut data)?.num_commits += 1;
+
+    Ok(())
+}
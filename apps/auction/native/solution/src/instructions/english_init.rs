@@ -0,0 +1,200 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{Sysvar, clock::Clock, rent::Rent},
+};
+
+use super::lib::{
+    check_token_program, create_ata, get_ata, get_counter_pda, get_english_pda,
+    get_pre_fee_amount, take_id, transfer,
+};
+use crate::error::AuctionError;
+use crate::state::EnglishAuction;
+
+pub fn english_init(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Must equal counter.next_id
+    id: u64,
+    reserve: u64,
+    min_increment: u64,
+    start_time: u64,
+    end_time: u64,
+    extension: u64,
+    sell_amt: u64,
+    // English auction PDA bump
+    bump: u8,
+    // Counter PDA bump
+    counter_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let counter_pda = next_account_info(account_iter)?;
+    let english_pda = next_account_info(account_iter)?;
+    let english_sell_ata = next_account_info(account_iter)?;
+    let english_buy_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let token_program_sell = next_account_info(account_iter)?;
+    let token_program_buy = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let rent_sysvar = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check seller signed
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token programs of the mints
+    check_token_program(mint_sell, token_program_sell)?;
+    check_token_program(mint_buy, token_program_buy)?;
+    // Check that counter_pda matches expected PDA
+    if *counter_pda.key
+        != get_counter_pda(program_id, seller.key, counter_bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that english_pda matches expected PDA
+    if *english_pda.key
+        != get_english_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check english_sell_ata
+    if *english_sell_ata.key
+        != get_ata(english_pda.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check english_buy_ata
+    if *english_buy_ata.key
+        != get_ata(english_pda.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check seller_sell_ata
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check sell token != buy token
+    if *mint_sell.key == *mint_buy.key {
+        return Err(AuctionError::InvalidMints.into());
+    }
+    // Check reserve > 0 and min_increment > 0
+    if reserve == 0 || min_increment == 0 {
+        return Err(AuctionError::InvalidPrices.into());
+    }
+    // Check now <= start_time < end_time
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+    if start_time < now {
+        return Err(AuctionError::InvalidStartTime.into());
+    }
+    if end_time <= start_time {
+        return Err(AuctionError::InvalidEndTime.into());
+    }
+    // Check sell_amt > 0
+    if sell_amt == 0 {
+        return Err(AuctionError::InvalidSellAmount.into());
+    }
+
+    // Create counter_pda if needed and check id is the next id
+    take_id(
+        program_id,
+        seller,
+        counter_pda,
+        sys_program,
+        id,
+        counter_bump,
+    )?;
+
+    let space = EnglishAuction::SPACE;
+    let rent = Rent::get()?.minimum_balance(space as usize);
+
+    // Create PDA account
+    invoke_signed(
+        &system_instruction::create_account(
+            seller.key,
+            english_pda.key,
+            rent,
+            space,
+            program_id,
+        ),
+        &[seller.clone(), english_pda.clone(), sys_program.clone()],
+        &[&[
+            EnglishAuction::SEED_PREFIX,
+            seller.key.as_ref(),
+            mint_sell.key.as_ref(),
+            mint_buy.key.as_ref(),
+            &id.to_le_bytes(),
+            &[bump],
+        ]],
+    )?;
+
+    // Create english_sell_ata to hold the lot
+    create_ata(
+        seller,
+        mint_sell,
+        english_pda,
+        english_sell_ata,
+        token_program_sell,
+        sys_program,
+        ata_program,
+        rent_sysvar,
+    )?;
+
+    // Create english_buy_ata to escrow the highest bid
+    create_ata(
+        seller,
+        mint_buy,
+        english_pda,
+        english_buy_ata,
+        token_program_buy,
+        sys_program,
+        ata_program,
+        rent_sysvar,
+    )?;
+
+    // Send sell token to english_sell_ata
+    transfer(
+        token_program_sell,
+        seller_sell_ata,
+        mint_sell,
+        english_sell_ata,
+        seller,
+        get_pre_fee_amount(mint_sell, sell_amt)?,
+        hook_accounts,
+    )?;
+
+    // Store EnglishAuction state
+    let mut data = english_pda.data.borrow_mut();
+    let auction = EnglishAuction::init(&mut data)?;
+    auction.seller = *seller.key;
+    auction.mint_sell = *mint_sell.key;
+    auction.mint_buy = *mint_buy.key;
+    auction.id = id;
+    auction.sell_amt = sell_amt;
+    auction.reserve = reserve;
+    auction.min_increment = min_increment;
+    auction.start_time = start_time;
+    auction.end_time = end_time;
+    auction.extension = extension;
+
+    Ok(())
+}
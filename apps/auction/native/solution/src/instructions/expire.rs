@@ -0,0 +1,170 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_token_program, close_ata, close_pda, create_ata_idempotent,
+    create_result, get_ata, get_pda, get_token_balance, transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::state::{Auction, AuctionResult};
+
+// Anyone can close an auction after end_time. Unsold sell token and the
+// rent go back to the seller, minus a crank reward for the caller.
+pub fn expire(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Auction PDA bump
+    bump: u8,
+    // AuctionResult PDA bump
+    result_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Receives the crank reward, pays for seller_sell_ata if it was closed
+    let cranker = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let auction_sell_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    // Token program of mint_sell
+    let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let result_pda = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check cranker signed
+    if !cranker.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token program of mint_sell
+    check_token_program(mint_sell, token_program)?;
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_sell_ata matches calculated account
+    if *auction_sell_ata.key
+        != get_ata(auction_pda.key, mint_sell.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = auction_pda.data.borrow();
+        *Auction::load(&data)?
+    }; // Drop borrow here
+
+    // Check auction has ended
+    if now < auction.end_time {
+        return Err(AuctionError::AuctionNotEnded.into());
+    }
+    // Check auction is not a batch auction, those are closed by Clear
+    if auction.batch != 0 {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+
+    // Get sell amount locked in auction_sell_ata
+    let sell_amt = get_token_balance(auction_sell_ata)?;
+
+    let id_bytes = id.to_le_bytes();
+    let seeds = &[
+        Auction::SEED_PREFIX,
+        seller.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &id_bytes,
+        &[bump],
+    ];
+
+    // Send unsold sell token to seller
+    if sell_amt > 0 {
+        create_ata_idempotent(
+            cranker,
+            mint_sell,
+            seller,
+            seller_sell_ata,
+            token_program,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program,
+            auction_sell_ata,
+            mint_sell,
+            seller_sell_ata,
+            auction_pda,
+            sell_amt,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Close auction_sell_ata
+    close_ata(
+        token_program,
+        auction_sell_ata,
+        mint_sell,
+        seller,
+        auction_pda,
+        seeds,
+    )?;
+
+    // Pay crank reward out of the auction_pda rent
+    let reward = auction_pda.lamports() * Auction::CRANK_REWARD_BPS / 10_000;
+    **auction_pda.try_borrow_mut_lamports()? -= reward;
+    **cranker.try_borrow_mut_lamports()? = cranker
+        .lamports()
+        .checked_add(reward)
+        .ok_or(AuctionError::MathOverflow)?;
+
+    // Record the expiry, its rent is also taken from auction_pda
+    create_result(
+        program_id,
+        auction_pda,
+        result_pda,
+        sys_program,
+        result_bump,
+        AuctionResult {
+            seller: auction.seller,
+            mint_sell: auction.mint_sell,
+            mint_buy: auction.mint_buy,
+            buyer: Pubkey::default(),
+            id,
+            sold: auction.sold,
+            proceeds: auction.proceeds,
+            price: 0,
+            closed_at: now,
+            outcome: AuctionResult::EXPIRED,
+        },
+    )?;
+
+    // Close auction_pda, the rest of the rent goes to seller
+    close_pda(auction_pda, seller, sys_program)
+}
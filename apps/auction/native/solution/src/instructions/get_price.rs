@@ -0,0 +1,63 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program::set_return_data,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{current_price, get_pda};
+use crate::price::Quote;
+use crate::state::Auction;
+
+// Read only. Returns the Quote of the auction at the current time as return
+// data, clients simulate it instead of computing the price themselves.
+pub fn get_price(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    // Only used if the auction has an oracle
+    let oracle_account = next_account_info(account_iter)?;
+
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = auction_pda.data.borrow();
+        *Auction::load(&data)?
+    }; // Drop borrow here
+
+    let quote = Quote {
+        price: current_price(&auction, now, oracle_account)?,
+        remaining: auction.remaining,
+        time_left: auction.end_time.saturating_sub(now),
+        nft: auction.nft == 1,
+        collection: auction.collection,
+    };
+    set_return_data(&borsh::to_vec(&quote)?);
+
+    Ok(())
+}
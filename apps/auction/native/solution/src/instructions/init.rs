@@ -1,7 +1,5 @@
-use borsh::BorshSerialize;
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
-    msg,
     program::invoke_signed,
     program_error::ProgramError,
     pubkey::Pubkey,
@@ -9,73 +7,179 @@ use solana_program::{
     sysvar::{Sysvar, clock::Clock, rent::Rent},
 };
 
-use super::lib::{create_ata, get_ata, get_pda, transfer};
-use crate::state::Auction;
+use super::lib::{
+    check_nft, check_params, check_token_program, create_ata, get_ata,
+    get_batch_pda, get_counter_pda, get_pda, get_pre_fee_amount, take_id,
+    transfer,
+};
+use crate::InitParams;
+use crate::error::AuctionError;
+use crate::state::{Auction, Batch, Config, PriceCurve};
 
 pub fn init(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    start_price: u64,
-    end_price: u64,
-    start_time: u64,
-    end_time: u64,
-    sell_amt: u64,
-    // Auction PDA bump
-    bump: u8,
+    params: InitParams,
 ) -> Result<(), ProgramError> {
+    let InitParams {
+        id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        curve,
+        merkle_root,
+        wallet_cap,
+        oracle,
+        oracle_decimals,
+        oracle_bps,
+        vest_cliff,
+        vest_duration,
+        batch,
+        nft,
+        metadata,
+        collection,
+        bump,
+        counter_bump,
+        batch_bump,
+    } = params;
+
     let account_iter = &mut accounts.iter();
 
     let seller = next_account_info(account_iter)?;
     let mint_sell = next_account_info(account_iter)?;
     let mint_buy = next_account_info(account_iter)?;
+    let counter_pda = next_account_info(account_iter)?;
     let auction_pda = next_account_info(account_iter)?;
     let auction_sell_ata = next_account_info(account_iter)?;
     let seller_sell_ata = next_account_info(account_iter)?;
-    let token_program = next_account_info(account_iter)?;
+    let token_program_sell = next_account_info(account_iter)?;
+    let token_program_buy = next_account_info(account_iter)?;
     let ata_program = next_account_info(account_iter)?;
     let sys_program = next_account_info(account_iter)?;
     let rent_sysvar = next_account_info(account_iter)?;
+    let batch_pda = next_account_info(account_iter)?;
+    let auction_buy_ata = next_account_info(account_iter)?;
+    // Holds the oracle program, only read if the auction has an oracle
+    let config_pda = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
 
     // Check seller signed
     if !seller.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
+    // Check the token programs of the mints
+    check_token_program(mint_sell, token_program_sell)?;
+    check_token_program(mint_buy, token_program_buy)?;
+    // Check that counter_pda matches expected PDA
+    if *counter_pda.key
+        != get_counter_pda(program_id, seller.key, counter_bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
     // Check that auction_pda matches expected PDA
     if *auction_pda.key
-        != get_pda(program_id, seller.key, mint_sell.key, mint_buy.key, bump)?
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
     {
         return Err(ProgramError::InvalidSeeds);
     }
     // Check auction_sell_ata
-    if *auction_sell_ata.key != get_ata(auction_pda.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+    if *auction_sell_ata.key
+        != get_ata(auction_pda.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
     // Check seller_sell_ata
-    if *seller_sell_ata.key != get_ata(seller.key, mint_sell.key) {
-        return Err(ProgramError::InvalidArgument);
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
     }
     // Check sell token != buy token
     if *mint_sell.key == *mint_buy.key {
-        return Err(ProgramError::InvalidArgument);
-    }
-    // Check start_price >= end_price
-    if start_price < end_price {
-        return Err(ProgramError::InvalidArgument);
+        return Err(AuctionError::InvalidMints.into());
     }
-    // Check now <= start_time < end_time
     let clock = Clock::get()?;
     let now: u64 = clock.unix_timestamp.try_into().unwrap();
-    if (start_time < now) || (end_time <= start_time) {
-        return Err(ProgramError::InvalidArgument);
+    // Check prices, times and sell_amt
+    check_params(now, start_price, end_price, start_time, end_time, sell_amt)?;
+    // Check oracle_decimals and oracle_bps > 0 if the floor is pegged to an
+    // oracle, and that the admin set the oracle program
+    let oracle_program = if oracle != Pubkey::default() {
+        if oracle_decimals > Auction::MAX_ORACLE_DECIMALS || oracle_bps == 0 {
+            return Err(AuctionError::InvalidOracle.into());
+        }
+        // Config is created by InitConfig at its PDA only
+        if config_pda.owner != program_id {
+            return Err(ProgramError::IllegalOwner);
+        }
+        let data = config_pda.data.borrow();
+        let oracle_program = Config::load(&data)?.oracle_program;
+        if oracle_program == Pubkey::default() {
+            return Err(AuctionError::InvalidOracle.into());
+        }
+        oracle_program
+    } else {
+        Pubkey::default()
+    };
+    // Check vest_cliff <= vest_duration
+    if vest_cliff > vest_duration {
+        return Err(AuctionError::InvalidVesting.into());
+    }
+    // Check batch auctions have no allowlist, cap, oracle floor or vesting
+    if batch
+        && (merkle_root != [0; 32]
+            || wallet_cap > 0
+            || oracle != Pubkey::default()
+            || vest_duration > 0)
+    {
+        return Err(AuctionError::InvalidAuctionKind.into());
+    }
+    // Check metadata and collection are only set for NFTs
+    if !nft
+        && (metadata != Pubkey::default() || collection != Pubkey::default())
+    {
+        return Err(AuctionError::InvalidNft.into());
+    }
+    // Check the NFT and that the whole supply is sold
+    if nft {
+        check_nft(mint_sell, &metadata, &collection)?;
+        if sell_amt != 1 {
+            return Err(AuctionError::InvalidSellAmount.into());
+        }
     }
-    // Check sell_amt > 0
-    if sell_amt == 0 {
-        return Err(ProgramError::InvalidArgument);
+    // Check half_life > 0 and step > 0
+    match curve {
+        PriceCurve::Linear => {}
+        PriceCurve::Exponential { half_life: param }
+        | PriceCurve::Stepped { step: param } => {
+            if param == 0 {
+                return Err(AuctionError::InvalidCurve.into());
+            }
+        }
     }
 
-    // 32 + 32 + 8 + 8 + 8 + 8 = 96
-    let space = 96;
-    let rent = Rent::get()?.minimum_balance(space);
+    // Create counter_pda if needed and check id is the next id
+    take_id(
+        program_id,
+        seller,
+        counter_pda,
+        sys_program,
+        id,
+        counter_bump,
+    )?;
+
+    let space = Auction::SPACE;
+    let rent = Rent::get()?.minimum_balance(space as usize);
 
     // Create PDA account
     invoke_signed(
@@ -83,7 +187,7 @@ pub fn init(
             seller.key,
             auction_pda.key,
             rent,
-            space as u64,
+            space,
             program_id,
         ),
         &[seller.clone(), auction_pda.clone(), sys_program.clone()],
@@ -92,6 +196,7 @@ pub fn init(
             seller.key.as_ref(),
             mint_sell.key.as_ref(),
             mint_buy.key.as_ref(),
+            &id.to_le_bytes(),
             &[bump],
         ]],
     )?;
@@ -102,7 +207,7 @@ pub fn init(
         mint_sell,
         auction_pda,
         auction_sell_ata,
-        token_program,
+        token_program_sell,
         sys_program,
         ata_program,
         rent_sysvar,
@@ -110,24 +215,86 @@ pub fn init(
 
     // Send sell token to auction_sell_ata
     transfer(
-        token_program,
+        token_program_sell,
         seller_sell_ata,
+        mint_sell,
         auction_sell_ata,
         seller,
-        sell_amt,
+        get_pre_fee_amount(mint_sell, sell_amt)?,
+        hook_accounts,
     )?;
 
+    // Create batch_pda holding the bids and auction_buy_ata escrowing them
+    if batch {
+        // Check that batch_pda matches expected PDA
+        if *batch_pda.key
+            != get_batch_pda(program_id, auction_pda.key, batch_bump)?
+        {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        // Check auction_buy_ata
+        if *auction_buy_ata.key
+            != get_ata(auction_pda.key, mint_buy.key, token_program_buy.key)
+        {
+            return Err(AuctionError::InvalidTokenAccount.into());
+        }
+
+        let space = Batch::SPACE;
+        let rent = Rent::get()?.minimum_balance(space as usize);
+
+        invoke_signed(
+            &system_instruction::create_account(
+                seller.key,
+                batch_pda.key,
+                rent,
+                space,
+                program_id,
+            ),
+            &[seller.clone(), batch_pda.clone(), sys_program.clone()],
+            &[&[Batch::SEED_PREFIX, auction_pda.key.as_ref(), &[batch_bump]]],
+        )?;
+
+        {
+            let mut data = batch_pda.data.borrow_mut();
+            Batch::init(&mut data)?.auction = *auction_pda.key;
+        }
+
+        create_ata(
+            seller,
+            mint_buy,
+            auction_pda,
+            auction_buy_ata,
+            token_program_buy,
+            sys_program,
+            ata_program,
+            rent_sysvar,
+        )?;
+    }
+
     // Store Auction state
     let mut data = auction_pda.data.borrow_mut();
-    let auction = Auction {
-        mint_sell: *mint_sell.key,
-        mint_buy: *mint_buy.key,
-        start_price,
-        end_price,
-        start_time,
-        end_time,
-    };
-    auction.serialize(&mut &mut data[..])?;
+    let auction = Auction::init(&mut data)?;
+    auction.seller = *seller.key;
+    auction.mint_sell = *mint_sell.key;
+    auction.mint_buy = *mint_buy.key;
+    auction.id = id;
+    auction.start_price = start_price;
+    auction.end_price = end_price;
+    auction.start_time = start_time;
+    auction.end_time = end_time;
+    auction.remaining = sell_amt;
+    (auction.curve, auction.curve_param) = curve.pack();
+    auction.merkle_root = merkle_root;
+    auction.wallet_cap = wallet_cap;
+    auction.oracle = oracle;
+    auction.oracle_program = oracle_program;
+    auction.oracle_decimals = oracle_decimals as u64;
+    auction.oracle_bps = oracle_bps;
+    auction.vest_cliff = vest_cliff;
+    auction.vest_duration = vest_duration;
+    auction.batch = batch as u64;
+    auction.nft = nft as u64;
+    auction.collection = collection;
 
     Ok(())
 }
@@ -0,0 +1,74 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{Sysvar, rent::Rent},
+};
+
+use super::lib::{check_upgrade_authority, get_config_pda};
+use crate::error::AuctionError;
+use crate::state::Config;
+
+// Called once after deployment by the upgrade authority of the program, the
+// signer becomes the admin
+pub fn init_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_bps: u64,
+    // Config PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_iter)?;
+    let treasury = next_account_info(account_iter)?;
+    let config_pda = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // ProgramData of this program, holds its upgrade authority
+    let program_data = next_account_info(account_iter)?;
+    // Program that must own the oracle accounts of oracle pegged auctions
+    let oracle_program = next_account_info(account_iter)?;
+
+    // Check admin signed
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check admin is the upgrade authority, so that nobody else can create
+    // the config first
+    check_upgrade_authority(program_id, program_data, admin.key)?;
+    // Check that config_pda matches expected PDA
+    if *config_pda.key != get_config_pda(program_id, bump)? {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check fee_bps <= MAX_FEE_BPS
+    if fee_bps > Config::MAX_FEE_BPS {
+        return Err(AuctionError::InvalidFee.into());
+    }
+
+    let space = Config::SPACE;
+    let rent = Rent::get()?.minimum_balance(space as usize);
+
+    // Create config_pda, fails if it already exists
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            config_pda.key,
+            rent,
+            space,
+            program_id,
+        ),
+        &[admin.clone(), config_pda.clone(), sys_program.clone()],
+        &[&[Config::SEED_PREFIX, &[bump]]],
+    )?;
+
+    let mut data = config_pda.data.borrow_mut();
+    let config = Config::init(&mut data)?;
+    config.admin = *admin.key;
+    config.treasury = *treasury.key;
+    config.fee_bps = fee_bps;
+    config.oracle_program = *oracle_program.key;
+
+    Ok(())
+}
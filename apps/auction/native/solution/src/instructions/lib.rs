@@ -1,3 +1,4 @@
+use borsh::BorshDeserialize;
 use solana_address::Address;
 use solana_program::entrypoint::ProgramResult;
 use solana_program::pubkey::{Pubkey, PubkeyError};
@@ -5,18 +6,32 @@ use solana_program::{account_info::AccountInfo, program_error::ProgramError};
 use solana_program::{
     instruction::{AccountMeta, Instruction},
     program::{invoke, invoke_signed},
+    system_instruction,
+    sysvar::{Sysvar, clock::Clock, rent::Rent},
 };
-use solana_program_pack::Pack;
 use spl_associated_token_account_interface as spl_ata;
+use spl_token_2022_interface::extension::{
+    BaseStateWithExtensions, StateWithExtensions,
+    metadata_pointer::MetadataPointer,
+    transfer_fee::{self, TransferFeeAmount, TransferFeeConfig},
+    transfer_hook,
+};
+use spl_token_group_interface::state::TokenGroupMember;
 use spl_token_interface;
 
-use crate::state::Auction;
+use crate::error::AuctionError;
+use crate::price::{oracle_floor, price_at};
+use crate::state::{
+    Auction, AuctionResult, Batch, BatchBid, Commitment, Config, Counter,
+    EnglishAuction, Purchase, ReverseAuction, SealedAuction, Vesting,
+};
 
 pub fn get_pda(
     program_id: &Pubkey,
     seller: &Pubkey,
     mint_sell: &Pubkey,
     mint_buy: &Pubkey,
+    id: u64,
     bump: u8,
 ) -> Result<Pubkey, PubkeyError> {
     Pubkey::create_program_address(
@@ -25,12 +40,368 @@ pub fn get_pda(
             seller.as_ref(),
             mint_sell.as_ref(),
             mint_buy.as_ref(),
+            &id.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn get_purchase_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+    buyer: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            Purchase::SEED_PREFIX,
+            auction_pda.as_ref(),
+            buyer.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn get_vesting_pda(
+    program_id: &Pubkey,
+    auction: &Pubkey,
+    buyer: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            Vesting::SEED_PREFIX,
+            auction.as_ref(),
+            buyer.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn get_batch_pda(
+    program_id: &Pubkey,
+    auction: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[Batch::SEED_PREFIX, auction.as_ref(), &[bump]],
+        program_id,
+    )
+}
+
+pub fn get_batch_bid_pda(
+    program_id: &Pubkey,
+    auction: &Pubkey,
+    bidder: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            BatchBid::SEED_PREFIX,
+            auction.as_ref(),
+            bidder.as_ref(),
             &[bump],
         ],
         program_id,
     )
 }
 
+pub fn get_english_pda(
+    program_id: &Pubkey,
+    seller: &Pubkey,
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    id: u64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            EnglishAuction::SEED_PREFIX,
+            seller.as_ref(),
+            mint_sell.as_ref(),
+            mint_buy.as_ref(),
+            &id.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn get_sealed_pda(
+    program_id: &Pubkey,
+    seller: &Pubkey,
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    id: u64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            SealedAuction::SEED_PREFIX,
+            seller.as_ref(),
+            mint_sell.as_ref(),
+            mint_buy.as_ref(),
+            &id.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn get_commitment_pda(
+    program_id: &Pubkey,
+    sealed_pda: &Pubkey,
+    bidder: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            Commitment::SEED_PREFIX,
+            sealed_pda.as_ref(),
+            bidder.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn get_reverse_pda(
+    program_id: &Pubkey,
+    buyer: &Pubkey,
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    id: u64,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            ReverseAuction::SEED_PREFIX,
+            buyer.as_ref(),
+            mint_sell.as_ref(),
+            mint_buy.as_ref(),
+            &id.to_le_bytes(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+pub fn get_counter_pda(
+    program_id: &Pubkey,
+    seller: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[Counter::SEED_PREFIX, seller.as_ref(), &[bump]],
+        program_id,
+    )
+}
+
+pub fn get_config_pda(
+    program_id: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(&[Config::SEED_PREFIX, &[bump]], program_id)
+}
+
+// Check authority is the upgrade authority of program_id, read from the
+// ProgramData account of the program. Its data starts with the u32 tag 3, the
+// u64 deployment slot and the Option<Pubkey> upgrade authority.
+pub fn check_upgrade_authority<'a>(
+    program_id: &Pubkey,
+    program_data: &AccountInfo<'a>,
+    authority: &Pubkey,
+) -> ProgramResult {
+    let loader = solana_program::bpf_loader_upgradeable::ID;
+    let (expected, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &loader);
+    if *program_data.key != expected {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    if *program_data.owner != loader {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = program_data.data.borrow();
+    if data.len() < 45 || data[..4] != 3u32.to_le_bytes() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if data[12] != 1 || data[13..45] != authority.to_bytes() {
+        return Err(AuctionError::Unauthorized.into());
+    }
+    Ok(())
+}
+
+pub fn get_result_pda(
+    program_id: &Pubkey,
+    auction: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[AuctionResult::SEED_PREFIX, auction.as_ref(), &[bump]],
+        program_id,
+    )
+}
+
+// Create the AuctionResult of an auction that is about to be closed.
+// Rent is moved out of auction_pda, so nobody pays for the record.
+pub fn create_result<'a>(
+    program_id: &Pubkey,
+    auction_pda: &AccountInfo<'a>,
+    result_pda: &AccountInfo<'a>,
+    sys_program: &AccountInfo<'a>,
+    bump: u8,
+    result: AuctionResult,
+) -> ProgramResult {
+    // Check that result_pda matches expected PDA
+    if *result_pda.key != get_result_pda(program_id, auction_pda.key, bump)? {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let space = AuctionResult::SPACE;
+    let rent = Rent::get()?
+        .minimum_balance(space as usize)
+        .saturating_sub(result_pda.lamports());
+    **auction_pda.try_borrow_mut_lamports()? = auction_pda
+        .lamports()
+        .checked_sub(rent)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    **result_pda.try_borrow_mut_lamports()? += rent;
+
+    let seeds: &[&[u8]] = &[
+        AuctionResult::SEED_PREFIX,
+        auction_pda.key.as_ref(),
+        &[bump],
+    ];
+    invoke_signed(
+        &system_instruction::allocate(result_pda.key, space),
+        &[result_pda.clone(), sys_program.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(result_pda.key, program_id),
+        &[result_pda.clone(), sys_program.clone()],
+        &[seeds],
+    )?;
+
+    let mut data = result_pda.data.borrow_mut();
+    *AuctionResult::init(&mut data)? = result;
+
+    Ok(())
+}
+
+// Price of 1 whole sell token at time now, used by Buy and GetPrice.
+// The price does not drop below the oracle floor of an oracle pegged
+// auction. oracle_account is only used if the auction has an oracle.
+pub fn current_price<'a>(
+    auction: &Auction,
+    now: u64,
+    oracle_account: &AccountInfo<'a>,
+) -> Result<u64, ProgramError> {
+    let price = price_at(auction, now)?;
+
+    if auction.oracle == Pubkey::default() {
+        return Ok(price);
+    }
+
+    // Check that oracle_account is the auction's oracle
+    if *oracle_account.key != auction.oracle {
+        return Err(AuctionError::InvalidOracle.into());
+    }
+    // Check oracle_account is owned by the auction's oracle program
+    if *oracle_account.owner != auction.oracle_program {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let oracle = {
+        let data = oracle_account.data.borrow();
+        oracle::state::Oracle::try_from_slice(&data)?
+    }; // Drop borrow here
+
+    // Check oracle price is not stale
+    if now > oracle.updated_at.saturating_add(Auction::MAX_ORACLE_AGE) {
+        return Err(AuctionError::StaleOracle.into());
+    }
+
+    Ok(price.max(oracle_floor(
+        oracle.price,
+        auction.oracle_decimals,
+        auction.oracle_bps,
+    )?))
+}
+
+// Checks shared by init and update of a Dutch auction. ReverseInit passes
+// its prices swapped, the price of a reverse auction rises.
+pub fn check_params(
+    now: u64,
+    start_price: u64,
+    end_price: u64,
+    start_time: u64,
+    end_time: u64,
+    sell_amt: u64,
+) -> Result<(), ProgramError> {
+    // Check start_price >= end_price
+    if start_price < end_price {
+        return Err(AuctionError::InvalidPrices.into());
+    }
+    // Check now <= start_time
+    if start_time < now {
+        return Err(AuctionError::InvalidStartTime.into());
+    }
+    // Check start_time < end_time
+    if end_time <= start_time {
+        return Err(AuctionError::InvalidEndTime.into());
+    }
+    // Check sell_amt > 0
+    if sell_amt == 0 {
+        return Err(AuctionError::InvalidSellAmount.into());
+    }
+    Ok(())
+}
+
+// Create counter_pda on the seller's first auction, check that id is the
+// next id and hand out the one after
+pub fn take_id<'a>(
+    program_id: &Pubkey,
+    seller: &AccountInfo<'a>,
+    counter_pda: &AccountInfo<'a>,
+    sys_program: &AccountInfo<'a>,
+    id: u64,
+    counter_bump: u8,
+) -> ProgramResult {
+    if counter_pda.lamports() == 0 {
+        let space = Counter::SPACE;
+        let rent = Rent::get()?.minimum_balance(space as usize);
+
+        invoke_signed(
+            &system_instruction::create_account(
+                seller.key,
+                counter_pda.key,
+                rent,
+                space,
+                program_id,
+            ),
+            &[seller.clone(), counter_pda.clone(), sys_program.clone()],
+            &[&[Counter::SEED_PREFIX, seller.key.as_ref(), &[counter_bump]]],
+        )?;
+
+        let mut data = counter_pda.data.borrow_mut();
+        Counter::init(&mut data)?;
+    }
+
+    let mut data = counter_pda.data.borrow_mut();
+    let counter = Counter::load_mut(&mut data)?;
+    if id != counter.next_id {
+        return Err(AuctionError::InvalidId.into());
+    }
+    counter.next_id += 1;
+
+    Ok(())
+}
+
 pub fn create_ata<'a>(
     payer: &AccountInfo<'a>,
     mint: &AccountInfo<'a>,
@@ -80,15 +451,69 @@ pub fn create_ata<'a>(
     Ok(())
 }
 
+// Create the ATA if it does not exist, for token receivers that may have
+// closed their ATA
+pub fn create_ata_idempotent<'a>(
+    payer: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    owner: &AccountInfo<'a>,
+    ata: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    sys_program: &AccountInfo<'a>,
+    ata_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let spl_ix =
+        spl_ata::instruction::create_associated_token_account_idempotent(
+            &Address::from(payer.key.to_bytes()),
+            &Address::from(owner.key.to_bytes()),
+            &Address::from(mint.key.to_bytes()),
+            &Address::from(token_program.key.to_bytes()),
+        );
+
+    let ix = Instruction {
+        program_id: Pubkey::from(spl_ix.program_id.to_bytes()),
+        accounts: spl_ix
+            .accounts
+            .iter()
+            .map(|acc| AccountMeta {
+                pubkey: Pubkey::from(acc.pubkey.to_bytes()),
+                is_signer: acc.is_signer,
+                is_writable: acc.is_writable,
+            })
+            .collect(),
+        data: spl_ix.data,
+    };
+
+    invoke(
+        &ix,
+        &[
+            payer.clone(),
+            ata.clone(),
+            owner.clone(),
+            mint.clone(),
+            sys_program.clone(),
+            token_program.clone(),
+            ata_program.clone(),
+        ],
+    )
+}
+
+// Token-2022 accounts with withheld transfer fees can not be closed, the
+// fees are harvested to the mint first. mint must be writable then.
 pub fn close_ata<'a>(
     token_program: &AccountInfo<'a>,
     ata: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
     dst: &AccountInfo<'a>,
     // ATA owner
     owner: &AccountInfo<'a>,
     signer_seeds: &[&[u8]],
 ) -> ProgramResult {
-    let spl_ix = spl_token_interface::instruction::close_account(
+    if get_withheld_amount(ata)? > 0 {
+        harvest_withheld(token_program, mint, ata)?;
+    }
+
+    let spl_ix = spl_token_2022_interface::instruction::close_account(
         &Address::from(token_program.key.to_bytes()),
         &Address::from(ata.key.to_bytes()),
         &Address::from(dst.key.to_bytes()),
@@ -124,30 +549,62 @@ pub fn close_ata<'a>(
     )
 }
 
-pub fn get_ata(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
-    let addr = spl_ata::address::get_associated_token_address(
-        &Address::from(wallet.to_bytes()),
-        &Address::from(mint.to_bytes()),
-    );
-    Pubkey::from(addr.to_bytes())
+// Send what is left in a PDA owned ATA to dst and close it
+pub fn close_escrow<'a>(
+    token_program: &AccountInfo<'a>,
+    ata: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    dst: &AccountInfo<'a>,
+    // Receiver of the ATA rent
+    rent_dst: &AccountInfo<'a>,
+    // ATA owner
+    owner: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+    hook_accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let amt = get_token_balance(ata)?;
+    if amt > 0 {
+        transfer_from_pda(
+            token_program,
+            ata,
+            mint,
+            dst,
+            owner,
+            amt,
+            signer_seeds,
+            hook_accounts,
+        )?;
+    }
+    close_ata(token_program, ata, mint, rent_dst, owner, signer_seeds)
 }
 
-pub fn transfer<'a>(
+// Transfer fees withheld in a Token-2022 account
+fn get_withheld_amount<'a>(
+    token_account: &AccountInfo<'a>,
+) -> Result<u64, ProgramError> {
+    let data = token_account.data.borrow();
+    let state = StateWithExtensions::<
+        spl_token_2022_interface::state::Account,
+    >::unpack(&data)
+    .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    Ok(state
+        .get_extension::<TransferFeeAmount>()
+        .map(|ext| u64::from(ext.withheld_amount))
+        .unwrap_or(0))
+}
+
+// Move the withheld transfer fees of a Token-2022 account to its mint,
+// anyone can harvest
+fn harvest_withheld<'a>(
     token_program: &AccountInfo<'a>,
-    src: &AccountInfo<'a>,
-    dst: &AccountInfo<'a>,
-    // Transfer authority
-    auth: &AccountInfo<'a>,
-    amount: u64,
+    mint: &AccountInfo<'a>,
+    token_account: &AccountInfo<'a>,
 ) -> ProgramResult {
-    let spl_ix = spl_token_interface::instruction::transfer(
+    let spl_ix = transfer_fee::instruction::harvest_withheld_tokens_to_mint(
         &Address::from(token_program.key.to_bytes()),
-        &Address::from(src.key.to_bytes()),
-        &Address::from(dst.key.to_bytes()),
-        &Address::from(auth.key.to_bytes()),
-        // Signer pubkeys
-        &[],
-        amount,
+        &Address::from(mint.key.to_bytes()),
+        &[&Address::from(token_account.key.to_bytes())],
     )
     .map_err(|_| ProgramError::InvalidInstructionData)?;
 
@@ -167,35 +624,95 @@ pub fn transfer<'a>(
 
     invoke(
         &ix,
-        &[
-            src.clone(),
-            dst.clone(),
-            auth.clone(),
-            token_program.clone(),
-        ],
+        &[mint.clone(), token_account.clone(), token_program.clone()],
     )
 }
 
-pub fn transfer_from_pda<'a>(
+// Send all lamports of a program owned account to dst and hand the account
+// back to the system program
+pub fn close_pda<'a>(
+    pda: &AccountInfo<'a>,
+    dst: &AccountInfo<'a>,
+    sys_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    // Get PDA balance and transfer lamports directly
+    let pda_lamports = pda.lamports();
+
+    **pda.try_borrow_mut_lamports()? = 0;
+    **dst.try_borrow_mut_lamports()? = dst
+        .lamports()
+        .checked_add(pda_lamports)
+        .ok_or(AuctionError::MathOverflow)?;
+
+    // Clear out data
+    pda.resize(0)?;
+
+    // Assign the account to the System Program
+    pda.assign(sys_program.key);
+
+    Ok(())
+}
+
+// Check token_program is the Token or Token-2022 program and owns mint
+pub fn check_token_program<'a>(
+    mint: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+) -> ProgramResult {
+    let token_program_ids = [
+        Pubkey::from(spl_token_interface::ID.to_bytes()),
+        Pubkey::from(spl_token_2022_interface::ID.to_bytes()),
+    ];
+    if !token_program_ids.contains(token_program.key)
+        || mint.owner != token_program.key
+    {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    Ok(())
+}
+
+pub fn get_ata(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    // Token program or Token-2022 program of the mint
+    token_program: &Pubkey,
+) -> Pubkey {
+    let addr = spl_ata::address::get_associated_token_address_with_program_id(
+        &Address::from(wallet.to_bytes()),
+        &Address::from(mint.to_bytes()),
+        &Address::from(token_program.to_bytes()),
+    );
+    Pubkey::from(addr.to_bytes())
+}
+
+// Build a transfer_checked of amount from src to dst.
+// If mint has a Token-2022 transfer hook, the hook program, its validation
+// account and the extra accounts resolved from it are appended. Clients pass
+// them as the remaining accounts of the instruction. Token-2022 looks them up
+// by key, so accounts of the hooks of both auction mints can be passed.
+fn transfer_checked_ix<'a>(
     token_program: &AccountInfo<'a>,
     src: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
     dst: &AccountInfo<'a>,
     // Transfer authority
     auth: &AccountInfo<'a>,
     amount: u64,
-    signer_seeds: &[&[u8]],
-) -> ProgramResult {
-    let spl_ix = spl_token_interface::instruction::transfer(
+    hook_accounts: &[AccountInfo<'a>],
+) -> Result<(Instruction, Vec<AccountInfo<'a>>), ProgramError> {
+    let spl_ix = spl_token_2022_interface::instruction::transfer_checked(
         &Address::from(token_program.key.to_bytes()),
         &Address::from(src.key.to_bytes()),
+        &Address::from(mint.key.to_bytes()),
         &Address::from(dst.key.to_bytes()),
         &Address::from(auth.key.to_bytes()),
+        // Signer pubkeys
         &[],
         amount,
+        get_mint_decimals(mint)?,
     )
     .map_err(|_| ProgramError::InvalidInstructionData)?;
 
-    let ix = Instruction {
+    let mut ix = Instruction {
         program_id: Pubkey::from(spl_ix.program_id.to_bytes()),
         accounts: spl_ix
             .accounts
@@ -208,26 +725,212 @@ pub fn transfer_from_pda<'a>(
             .collect(),
         data: spl_ix.data,
     };
+    let mut infos = vec![
+        src.clone(),
+        mint.clone(),
+        dst.clone(),
+        auth.clone(),
+        token_program.clone(),
+    ];
 
-    invoke_signed(
-        &ix,
-        &[
-            src.clone(),
-            dst.clone(),
-            auth.clone(),
-            token_program.clone(),
-        ],
-        &[signer_seeds],
-    )
+    if let Some(hook_program) = get_transfer_hook_program(mint)? {
+        // Check the hook program and its validation account are passed
+        let (validation, _) = Pubkey::find_program_address(
+            &[b"extra-account-metas", mint.key.as_ref()],
+            &hook_program,
+        );
+        for key in [hook_program, validation] {
+            if !hook_accounts.iter().any(|acc| *acc.key == key) {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            }
+        }
+        for acc in hook_accounts {
+            ix.accounts.push(AccountMeta {
+                pubkey: *acc.key,
+                is_signer: false,
+                is_writable: acc.is_writable,
+            });
+            infos.push(acc.clone());
+        }
+    }
+
+    Ok((ix, infos))
+}
+
+pub fn transfer<'a>(
+    token_program: &AccountInfo<'a>,
+    src: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    dst: &AccountInfo<'a>,
+    // Transfer authority
+    auth: &AccountInfo<'a>,
+    amount: u64,
+    hook_accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let (ix, infos) = transfer_checked_ix(
+        token_program,
+        src,
+        mint,
+        dst,
+        auth,
+        amount,
+        hook_accounts,
+    )?;
+
+    invoke(&ix, &infos)
+}
+
+pub fn transfer_from_pda<'a>(
+    token_program: &AccountInfo<'a>,
+    src: &AccountInfo<'a>,
+    mint: &AccountInfo<'a>,
+    dst: &AccountInfo<'a>,
+    // Transfer authority
+    auth: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: &[&[u8]],
+    hook_accounts: &[AccountInfo<'a>],
+) -> ProgramResult {
+    let (ix, infos) = transfer_checked_ix(
+        token_program,
+        src,
+        mint,
+        dst,
+        auth,
+        amount,
+        hook_accounts,
+    )?;
+
+    invoke_signed(&ix, &infos, &[signer_seeds])
+}
+
+// Program of the Token-2022 transfer hook of mint, if any
+pub fn get_transfer_hook_program<'a>(
+    mint: &AccountInfo<'a>,
+) -> Result<Option<Pubkey>, ProgramError> {
+    let data = mint.data.borrow();
+    let state =
+        StateWithExtensions::<spl_token_2022_interface::state::Mint>::unpack(
+            &data,
+        )
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    Ok(transfer_hook::get_program_id(&state)
+        .map(|program_id| Pubkey::from(program_id.to_bytes())))
+}
+
+// Amount to send so that dst receives amount after the Token-2022 transfer
+// fee of mint is withheld
+pub fn get_pre_fee_amount<'a>(
+    mint: &AccountInfo<'a>,
+    amount: u64,
+) -> Result<u64, ProgramError> {
+    let data = mint.data.borrow();
+    let state =
+        StateWithExtensions::<spl_token_2022_interface::state::Mint>::unpack(
+            &data,
+        )
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    let Ok(config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(amount);
+    };
+    let epoch = Clock::get()?.epoch;
+    let fee = config
+        .calculate_inverse_epoch_fee(epoch, amount)
+        .ok_or(AuctionError::MathOverflow)?;
+
+    amount
+        .checked_add(fee)
+        .ok_or(AuctionError::MathOverflow.into())
 }
 
 pub fn get_token_balance<'a>(
     token_account: &AccountInfo<'a>,
 ) -> Result<u64, ProgramError> {
-    let token_account_data = spl_token_interface::state::Account::unpack(
-        &token_account.data.borrow(),
-    )
+    // Token-2022 accounts may have extensions after the base state
+    let data = token_account.data.borrow();
+    let state = StateWithExtensions::<
+        spl_token_2022_interface::state::Account,
+    >::unpack(&data)
     .map_err(|_| ProgramError::InvalidAccountData)?;
 
-    Ok(token_account_data.amount)
+    Ok(state.base.amount)
+}
+
+// Auctions with the native mint as mint_buy are paid in lamports
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == Pubkey::from(spl_token_interface::native_mint::ID.to_bytes())
+}
+
+pub fn transfer_lamports<'a>(
+    src: &AccountInfo<'a>,
+    dst: &AccountInfo<'a>,
+    sys_program: &AccountInfo<'a>,
+    amt: u64,
+) -> ProgramResult {
+    invoke(
+        &system_instruction::transfer(src.key, dst.key, amt),
+        &[src.clone(), dst.clone(), sys_program.clone()],
+    )
+}
+
+pub fn get_mint_decimals<'a>(
+    mint: &AccountInfo<'a>,
+) -> Result<u8, ProgramError> {
+    // Token-2022 mints may have extensions after the base state
+    let data = mint.data.borrow();
+    let state =
+        StateWithExtensions::<spl_token_2022_interface::state::Mint>::unpack(
+            &data,
+        )
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    Ok(state.base.decimals)
+}
+
+// Check mint is an NFT, 0 decimals and supply 1.
+// A metadata that is not default must match the metadata address of the
+// Token-2022 MetadataPointer extension, a collection that is not default
+// the group of the TokenGroupMember extension.
+pub fn check_nft<'a>(
+    mint: &AccountInfo<'a>,
+    metadata: &Pubkey,
+    collection: &Pubkey,
+) -> ProgramResult {
+    let data = mint.data.borrow();
+    let state =
+        StateWithExtensions::<spl_token_2022_interface::state::Mint>::unpack(
+            &data,
+        )
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+
+    // No more can be minted
+    if state.base.decimals != 0
+        || state.base.supply != 1
+        || state.base.mint_authority.is_some()
+    {
+        return Err(AuctionError::InvalidNft.into());
+    }
+
+    if *metadata != Pubkey::default() {
+        let pointer = state
+            .get_extension::<MetadataPointer>()
+            .map_err(|_| AuctionError::InvalidNft)?;
+        let addr: Option<Address> = pointer.metadata_address.into();
+        if addr.map(|a| a.to_bytes()) != Some(metadata.to_bytes()) {
+            return Err(AuctionError::InvalidNft.into());
+        }
+    }
+
+    if *collection != Pubkey::default() {
+        let member = state
+            .get_extension::<TokenGroupMember>()
+            .map_err(|_| AuctionError::InvalidNft)?;
+        if member.group.to_bytes() != collection.to_bytes() {
+            return Err(AuctionError::InvalidNft.into());
+        }
+    }
+
+    Ok(())
 }
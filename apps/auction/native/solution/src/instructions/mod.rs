@@ -1,8 +1,48 @@
+pub mod accept;
+pub mod batch_bid;
+pub mod batch_claim;
+pub mod bid;
 pub mod buy;
 pub mod cancel;
+pub mod claim;
+pub mod clear;
+pub mod commit;
+pub mod english_init;
+pub mod expire;
+pub mod get_price;
 pub mod init;
+pub mod init_config;
 pub mod lib;
+pub mod reveal;
+pub mod reverse_cancel;
+pub mod reverse_init;
+pub mod sealed_claim;
+pub mod sealed_init;
+pub mod sealed_settle;
+pub mod set_fee;
+pub mod settle;
+pub mod update;
 
+pub use accept::*;
+pub use batch_bid::*;
+pub use batch_claim::*;
+pub use bid::*;
 pub use buy::*;
 pub use cancel::*;
+pub use claim::*;
+pub use clear::*;
+pub use commit::*;
+pub use english_init::*;
+pub use expire::*;
+pub use get_price::*;
 pub use init::*;
+pub use init_config::*;
+pub use reveal::*;
+pub use reverse_cancel::*;
+pub use reverse_init::*;
+pub use sealed_claim::*;
+pub use sealed_init::*;
+pub use sealed_settle::*;
+pub use set_fee::*;
+pub use settle::*;
+pub use update::*;
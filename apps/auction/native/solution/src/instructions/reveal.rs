@@ -0,0 +1,173 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_token_program, get_ata, get_commitment_pda, get_mint_decimals,
+    get_pre_fee_amount, get_sealed_pda, transfer,
+};
+use crate::book::{Book, BookBid};
+use crate::error::AuctionError;
+use crate::price::pay_amount;
+use crate::state::{Commitment, SealedAuction};
+
+pub fn reveal(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Max price of 1 whole sell token
+    price: u64,
+    // Amount of sell token
+    amount: u64,
+    salt: [u8; 32],
+    // Sealed auction PDA bump
+    bump: u8,
+    // Commitment PDA bump
+    commitment_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let bidder = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let sealed_pda = next_account_info(account_iter)?;
+    let sealed_buy_ata = next_account_info(account_iter)?;
+    let bidder_buy_ata = next_account_info(account_iter)?;
+    let commitment_pda = next_account_info(account_iter)?;
+    // Token program of mint_buy
+    let token_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check bidder signed
+    if !bidder.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token program of mint_buy
+    check_token_program(mint_buy, token_program)?;
+    // Check that sealed_pda matches expected PDA
+    if *sealed_pda.key
+        != get_sealed_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that commitment_pda matches expected PDA
+    if *commitment_pda.key
+        != get_commitment_pda(
+            program_id,
+            sealed_pda.key,
+            bidder.key,
+            commitment_bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that sealed_buy_ata matches calculated account
+    if *sealed_buy_ata.key
+        != get_ata(sealed_pda.key, mint_buy.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = sealed_pda.data.borrow();
+        *SealedAuction::load(&data)?
+    }; // Drop borrow here
+    let commitment = {
+        let data = commitment_pda.data.borrow();
+        *Commitment::load(&data)?
+    }; // Drop borrow here
+
+    // Check reveal phase has started
+    if now < auction.commit_end {
+        return Err(AuctionError::AuctionNotStarted.into());
+    }
+    // Check reveal phase has not ended
+    if auction.reveal_end <= now {
+        return Err(AuctionError::AuctionEnded.into());
+    }
+    // Check bid was not revealed yet
+    if commitment.revealed != 0 {
+        return Err(AuctionError::InvalidCommitment.into());
+    }
+    // Check bid matches the commitment
+    if Commitment::hash(price, amount, &salt) != commitment.hash {
+        return Err(AuctionError::InvalidCommitment.into());
+    }
+    // Check price >= reserve
+    if price < auction.reserve {
+        return Err(AuctionError::BidTooLow.into());
+    }
+    // Check amount > 0
+    if amount == 0 {
+        return Err(AuctionError::InvalidBuyAmount.into());
+    }
+
+    // Insert bid into the book. Bids below the min amount of the book or
+    // that can not win stay out of it, only their deposit is kept and it is
+    // refunded on claim.
+    let inserted = {
+        let mut data = sealed_pda.data.borrow_mut();
+        let auction = SealedAuction::load_mut(&mut data)?;
+        auction.num_revealed += 1;
+        let sell_amt = auction.sell_amt;
+        amount >= Book::min_amount(sell_amt)
+            && auction.book.insert(
+                BookBid {
+                    bidder: *bidder.key,
+                    price,
+                    amount,
+                },
+                sell_amt,
+            )
+    }; // Drop borrow here
+
+    // Escrow the full payment of a bid in the book, the deposit counts
+    // towards it
+    let pay_amt = if inserted {
+        pay_amount(
+            amount,
+            price,
+            get_mint_decimals(mint_sell)?,
+            get_mint_decimals(mint_buy)?,
+        )?
+    } else {
+        0
+    };
+    if pay_amt > commitment.escrowed {
+        transfer(
+            token_program,
+            bidder_buy_ata,
+            mint_buy,
+            sealed_buy_ata,
+            bidder,
+            get_pre_fee_amount(mint_buy, pay_amt - commitment.escrowed)?,
+            hook_accounts,
+        )?;
+    }
+
+    {
+        let mut data = commitment_pda.data.borrow_mut();
+        let commitment = Commitment::load_mut(&mut data)?;
+        commitment.escrowed = commitment.escrowed.max(pay_amt);
+        commitment.price = price;
+        commitment.amount = amount;
+        commitment.revealed = 1;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::lib::{
+    check_token_program, close_escrow, close_pda, get_ata, get_reverse_pda,
+};
+use crate::error::AuctionError;
+use crate::state::ReverseAuction;
+
+// Buyer closes a reverse auction at any time, what is left of the escrow is
+// refunded
+pub fn reverse_cancel(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Reverse auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let buyer = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let reverse_pda = next_account_info(account_iter)?;
+    let reverse_buy_ata = next_account_info(account_iter)?;
+    let buyer_buy_ata = next_account_info(account_iter)?;
+    // Token program of mint_buy
+    let token_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check buyer signed
+    if !buyer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token program of mint_buy
+    check_token_program(mint_buy, token_program)?;
+    // Check that reverse_pda matches expected PDA
+    if *reverse_pda.key
+        != get_reverse_pda(
+            program_id,
+            buyer.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that reverse_buy_ata matches calculated account
+    if *reverse_buy_ata.key
+        != get_ata(reverse_pda.key, mint_buy.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that buyer_buy_ata matches calculated account
+    if *buyer_buy_ata.key != get_ata(buyer.key, mint_buy.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    // Check reverse_pda holds a ReverseAuction
+    {
+        let data = reverse_pda.data.borrow();
+        ReverseAuction::load(&data)?;
+    } // Drop borrow here
+
+    let id_bytes = id.to_le_bytes();
+    let seeds = &[
+        ReverseAuction::SEED_PREFIX,
+        buyer.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &id_bytes,
+        &[bump],
+    ];
+
+    // Refund buy token to buyer and close reverse_buy_ata
+    close_escrow(
+        token_program,
+        reverse_buy_ata,
+        mint_buy,
+        buyer_buy_ata,
+        buyer,
+        reverse_pda,
+        seeds,
+        hook_accounts,
+    )?;
+
+    // Close reverse_pda
+    close_pda(reverse_pda, buyer, sys_program)
+}
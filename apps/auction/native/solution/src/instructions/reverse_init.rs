@@ -0,0 +1,180 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{Sysvar, clock::Clock, rent::Rent},
+};
+
+use super::lib::{
+    check_params, check_token_program, create_ata, get_ata, get_counter_pda,
+    get_mint_decimals, get_pre_fee_amount, get_reverse_pda, take_id, transfer,
+};
+use crate::error::AuctionError;
+use crate::price::receive_amount;
+use crate::state::ReverseAuction;
+
+pub fn reverse_init(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Must equal counter.next_id
+    id: u64,
+    start_price: u64,
+    end_price: u64,
+    start_time: u64,
+    end_time: u64,
+    // Amount of sell token wanted
+    buy_amt: u64,
+    // Reverse auction PDA bump
+    bump: u8,
+    // Counter PDA bump
+    counter_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let buyer = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let counter_pda = next_account_info(account_iter)?;
+    let reverse_pda = next_account_info(account_iter)?;
+    let reverse_buy_ata = next_account_info(account_iter)?;
+    let buyer_buy_ata = next_account_info(account_iter)?;
+    // Token program of mint_buy
+    let token_program = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let rent_sysvar = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check buyer signed
+    if !buyer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token program of mint_buy
+    check_token_program(mint_buy, token_program)?;
+    // Check that counter_pda matches expected PDA
+    if *counter_pda.key != get_counter_pda(program_id, buyer.key, counter_bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that reverse_pda matches expected PDA
+    if *reverse_pda.key
+        != get_reverse_pda(
+            program_id,
+            buyer.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check reverse_buy_ata
+    if *reverse_buy_ata.key
+        != get_ata(reverse_pda.key, mint_buy.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check buyer_buy_ata
+    if *buyer_buy_ata.key != get_ata(buyer.key, mint_buy.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check sell token != buy token
+    if *mint_sell.key == *mint_buy.key {
+        return Err(AuctionError::InvalidMints.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    // Check start_price <= end_price, times and buy_amt
+    check_params(now, end_price, start_price, start_time, end_time, buy_amt)?;
+
+    // Payment for buy_amt at end_price, covers every fill at a lower price
+    let escrow_amt = receive_amount(
+        buy_amt,
+        end_price,
+        get_mint_decimals(mint_sell)?,
+        get_mint_decimals(mint_buy)?,
+    )?;
+    // Check escrow_amt > 0
+    if escrow_amt == 0 {
+        return Err(AuctionError::InvalidPrices.into());
+    }
+
+    // Create counter_pda if needed and check id is the next id
+    take_id(
+        program_id,
+        buyer,
+        counter_pda,
+        sys_program,
+        id,
+        counter_bump,
+    )?;
+
+    let space = ReverseAuction::SPACE;
+    let rent = Rent::get()?.minimum_balance(space as usize);
+
+    // Create PDA account
+    invoke_signed(
+        &system_instruction::create_account(
+            buyer.key,
+            reverse_pda.key,
+            rent,
+            space,
+            program_id,
+        ),
+        &[buyer.clone(), reverse_pda.clone(), sys_program.clone()],
+        &[&[
+            ReverseAuction::SEED_PREFIX,
+            buyer.key.as_ref(),
+            mint_sell.key.as_ref(),
+            mint_buy.key.as_ref(),
+            &id.to_le_bytes(),
+            &[bump],
+        ]],
+    )?;
+
+    // Create reverse_buy_ata to escrow the payment
+    create_ata(
+        buyer,
+        mint_buy,
+        reverse_pda,
+        reverse_buy_ata,
+        token_program,
+        sys_program,
+        ata_program,
+        rent_sysvar,
+    )?;
+
+    // Send buy token to reverse_buy_ata
+    transfer(
+        token_program,
+        buyer_buy_ata,
+        mint_buy,
+        reverse_buy_ata,
+        buyer,
+        get_pre_fee_amount(mint_buy, escrow_amt)?,
+        hook_accounts,
+    )?;
+
+    // Store ReverseAuction state
+    let mut data = reverse_pda.data.borrow_mut();
+    let auction = ReverseAuction::init(&mut data)?;
+    auction.buyer = *buyer.key;
+    auction.mint_sell = *mint_sell.key;
+    auction.mint_buy = *mint_buy.key;
+    auction.id = id;
+    auction.start_price = start_price;
+    auction.end_price = end_price;
+    auction.start_time = start_time;
+    auction.end_time = end_time;
+    auction.buy_amt = buy_amt;
+    auction.remaining = buy_amt;
+
+    Ok(())
+}
@@ -0,0 +1,258 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_token_program, close_escrow, close_pda, create_ata_idempotent,
+    get_ata, get_commitment_pda, get_mint_decimals, get_sealed_pda,
+    transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::price::pay_amount;
+use crate::state::{Commitment, SealedAuction};
+
+// Winners receive their sell token and the part of the escrow above the
+// clearing price, losers are refunded. Bidders that did not reveal only get
+// the rent of their commitment back. Anyone can claim for a bidder, so the
+// seller does not depend on every bidder to get the rent of the auction
+// back.
+pub fn sealed_claim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Sealed auction PDA bump
+    bump: u8,
+    // Commitment PDA bump
+    commitment_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Anyone can claim for a bidder and pays for ATAs of the bidder, if
+    // they do not exist
+    let payer = next_account_info(account_iter)?;
+    // Receives the sell token won, the refund and the rent of the bid
+    let bidder = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let sealed_pda = next_account_info(account_iter)?;
+    let sealed_sell_ata = next_account_info(account_iter)?;
+    let sealed_buy_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let seller_buy_ata = next_account_info(account_iter)?;
+    let bidder_sell_ata = next_account_info(account_iter)?;
+    let bidder_buy_ata = next_account_info(account_iter)?;
+    let commitment_pda = next_account_info(account_iter)?;
+    let token_program_sell = next_account_info(account_iter)?;
+    let token_program_buy = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check payer signed
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token programs of the mints
+    check_token_program(mint_sell, token_program_sell)?;
+    check_token_program(mint_buy, token_program_buy)?;
+    // Check that sealed_pda matches expected PDA
+    if *sealed_pda.key
+        != get_sealed_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that commitment_pda matches expected PDA
+    if *commitment_pda.key
+        != get_commitment_pda(
+            program_id,
+            sealed_pda.key,
+            bidder.key,
+            commitment_bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let commitment = {
+        let data = commitment_pda.data.borrow();
+        *Commitment::load(&data)?
+    }; // Drop borrow here
+
+    // Deposit was forfeited, close commitment after the reveal phase
+    if commitment.revealed == 0 {
+        let clock = Clock::get()?;
+        let now: u64 = clock.unix_timestamp.try_into().unwrap();
+        if now < commitment.reveal_end {
+            return Err(AuctionError::AuctionNotEnded.into());
+        }
+        return close_pda(commitment_pda, bidder, sys_program);
+    }
+
+    // Check that sealed_sell_ata matches calculated account
+    if *sealed_sell_ata.key
+        != get_ata(sealed_pda.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that sealed_buy_ata matches calculated account
+    if *sealed_buy_ata.key
+        != get_ata(sealed_pda.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_buy_ata matches calculated account
+    if *seller_buy_ata.key
+        != get_ata(seller.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that bidder_sell_ata matches calculated account
+    if *bidder_sell_ata.key
+        != get_ata(bidder.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that bidder_buy_ata matches calculated account
+    if *bidder_buy_ata.key
+        != get_ata(bidder.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let auction = {
+        let data = sealed_pda.data.borrow();
+        *SealedAuction::load(&data)?
+    }; // Drop borrow here
+
+    // Check auction is settled
+    if auction.settled == 0 {
+        return Err(AuctionError::NotSettled.into());
+    }
+
+    let fill = auction.book.fill_of(bidder.key, auction.sell_amt);
+    let pay_amt = if fill > 0 {
+        pay_amount(
+            fill,
+            auction.clearing_price,
+            get_mint_decimals(mint_sell)?,
+            get_mint_decimals(mint_buy)?,
+        )?
+    } else {
+        0
+    };
+    let refund = commitment
+        .escrowed
+        .checked_sub(pay_amt)
+        .ok_or(AuctionError::MathOverflow)?;
+
+    let id_bytes = id.to_le_bytes();
+    let seeds = &[
+        SealedAuction::SEED_PREFIX,
+        seller.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &id_bytes,
+        &[bump],
+    ];
+
+    // Send sell token won
+    if fill > 0 {
+        create_ata_idempotent(
+            payer,
+            mint_sell,
+            bidder,
+            bidder_sell_ata,
+            token_program_sell,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program_sell,
+            sealed_sell_ata,
+            mint_sell,
+            bidder_sell_ata,
+            sealed_pda,
+            fill,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Refund escrow above the clearing price
+    if refund > 0 {
+        create_ata_idempotent(
+            payer,
+            mint_buy,
+            bidder,
+            bidder_buy_ata,
+            token_program_buy,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program_buy,
+            sealed_buy_ata,
+            mint_buy,
+            bidder_buy_ata,
+            sealed_pda,
+            refund,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Close commitment_pda
+    close_pda(commitment_pda, bidder, sys_program)?;
+
+    let num_claimed = {
+        let mut data = sealed_pda.data.borrow_mut();
+        let auction = SealedAuction::load_mut(&mut data)?;
+        auction.num_claimed += 1;
+        auction.num_claimed
+    };
+
+    // Close the auction after the last claim
+    if num_claimed == auction.num_revealed {
+        close_escrow(
+            token_program_sell,
+            sealed_sell_ata,
+            mint_sell,
+            seller_sell_ata,
+            seller,
+            sealed_pda,
+            seeds,
+            hook_accounts,
+        )?;
+        close_escrow(
+            token_program_buy,
+            sealed_buy_ata,
+            mint_buy,
+            seller_buy_ata,
+            seller,
+            sealed_pda,
+            seeds,
+            hook_accounts,
+        )?;
+        close_pda(sealed_pda, seller, sys_program)?;
+    }
+
+    Ok(())
+}
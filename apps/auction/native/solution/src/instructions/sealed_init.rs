@@ -0,0 +1,195 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+    sysvar::{Sysvar, clock::Clock, rent::Rent},
+};
+
+use super::lib::{
+    check_token_program, create_ata, get_ata, get_counter_pda,
+    get_pre_fee_amount, get_sealed_pda, take_id, transfer,
+};
+use crate::error::AuctionError;
+use crate::state::SealedAuction;
+
+pub fn sealed_init(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    // Must equal counter.next_id
+    id: u64,
+    reserve: u64,
+    deposit: u64,
+    commit_end: u64,
+    reveal_end: u64,
+    sell_amt: u64,
+    // Sealed auction PDA bump
+    bump: u8,
+    // Counter PDA bump
+    counter_bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let counter_pda = next_account_info(account_iter)?;
+    let sealed_pda = next_account_info(account_iter)?;
+    let sealed_sell_ata = next_account_info(account_iter)?;
+    let sealed_buy_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let token_program_sell = next_account_info(account_iter)?;
+    let token_program_buy = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    let rent_sysvar = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check seller signed
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token programs of the mints
+    check_token_program(mint_sell, token_program_sell)?;
+    check_token_program(mint_buy, token_program_buy)?;
+    // Check that counter_pda matches expected PDA
+    if *counter_pda.key
+        != get_counter_pda(program_id, seller.key, counter_bump)?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that sealed_pda matches expected PDA
+    if *sealed_pda.key
+        != get_sealed_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check sealed_sell_ata
+    if *sealed_sell_ata.key
+        != get_ata(sealed_pda.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check sealed_buy_ata
+    if *sealed_buy_ata.key
+        != get_ata(sealed_pda.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check seller_sell_ata
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check sell token != buy token
+    if *mint_sell.key == *mint_buy.key {
+        return Err(AuctionError::InvalidMints.into());
+    }
+    // Check reserve > 0 and deposit > 0
+    if reserve == 0 || deposit == 0 {
+        return Err(AuctionError::InvalidPrices.into());
+    }
+    // Check now < commit_end < reveal_end
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+    if (commit_end <= now) || (reveal_end <= commit_end) {
+        return Err(AuctionError::InvalidEndTime.into());
+    }
+    // Check sell_amt > 0
+    if sell_amt == 0 {
+        return Err(AuctionError::InvalidSellAmount.into());
+    }
+
+    // Create counter_pda if needed and check id is the next id
+    take_id(
+        program_id,
+        seller,
+        counter_pda,
+        sys_program,
+        id,
+        counter_bump,
+    )?;
+
+    let space = SealedAuction::SPACE;
+    let rent = Rent::get()?.minimum_balance(space as usize);
+
+    // Create PDA account
+    invoke_signed(
+        &system_instruction::create_account(
+            seller.key,
+            sealed_pda.key,
+            rent,
+            space,
+            program_id,
+        ),
+        &[seller.clone(), sealed_pda.clone(), sys_program.clone()],
+        &[&[
+            SealedAuction::SEED_PREFIX,
+            seller.key.as_ref(),
+            mint_sell.key.as_ref(),
+            mint_buy.key.as_ref(),
+            &id.to_le_bytes(),
+            &[bump],
+        ]],
+    )?;
+
+    // Create sealed_sell_ata to hold the lot
+    create_ata(
+        seller,
+        mint_sell,
+        sealed_pda,
+        sealed_sell_ata,
+        token_program_sell,
+        sys_program,
+        ata_program,
+        rent_sysvar,
+    )?;
+
+    // Create sealed_buy_ata to escrow deposits and bids
+    create_ata(
+        seller,
+        mint_buy,
+        sealed_pda,
+        sealed_buy_ata,
+        token_program_buy,
+        sys_program,
+        ata_program,
+        rent_sysvar,
+    )?;
+
+    // Send sell token to sealed_sell_ata
+    transfer(
+        token_program_sell,
+        seller_sell_ata,
+        mint_sell,
+        sealed_sell_ata,
+        seller,
+        get_pre_fee_amount(mint_sell, sell_amt)?,
+        hook_accounts,
+    )?;
+
+    // Store SealedAuction state
+    let mut data = sealed_pda.data.borrow_mut();
+    let auction = SealedAuction::init(&mut data)?;
+    auction.seller = *seller.key;
+    auction.mint_sell = *mint_sell.key;
+    auction.mint_buy = *mint_buy.key;
+    auction.id = id;
+    auction.sell_amt = sell_amt;
+    auction.reserve = reserve;
+    auction.deposit = deposit;
+    auction.commit_end = commit_end;
+    auction.reveal_end = reveal_end;
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_token_program, close_escrow, close_pda, create_ata_idempotent,
+    get_ata, get_mint_decimals, get_sealed_pda, transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::price::pay_amount;
+use crate::state::SealedAuction;
+
+// Anyone can settle a sealed bid auction after the reveal phase.
+// Seller receives the payments of the winners, the deposits of bidders that
+// did not reveal and the unsold sell token. Bidders collect with
+// SealedClaim.
+pub fn sealed_settle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // Sealed auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Pays for ATAs of the seller, if they do not exist
+    let payer = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let sealed_pda = next_account_info(account_iter)?;
+    let sealed_sell_ata = next_account_info(account_iter)?;
+    let sealed_buy_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let seller_buy_ata = next_account_info(account_iter)?;
+    let token_program_sell = next_account_info(account_iter)?;
+    let token_program_buy = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check payer signed
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token programs of the mints
+    check_token_program(mint_sell, token_program_sell)?;
+    check_token_program(mint_buy, token_program_buy)?;
+    // Check that sealed_pda matches expected PDA
+    if *sealed_pda.key
+        != get_sealed_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that sealed_sell_ata matches calculated account
+    if *sealed_sell_ata.key
+        != get_ata(sealed_pda.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that sealed_buy_ata matches calculated account
+    if *sealed_buy_ata.key
+        != get_ata(sealed_pda.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_buy_ata matches calculated account
+    if *seller_buy_ata.key
+        != get_ata(seller.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = sealed_pda.data.borrow();
+        *SealedAuction::load(&data)?
+    }; // Drop borrow here
+
+    // Check reveal phase has ended
+    if now < auction.reveal_end {
+        return Err(AuctionError::AuctionNotEnded.into());
+    }
+    // Check auction is not settled yet
+    if auction.settled != 0 {
+        return Err(AuctionError::AlreadySettled.into());
+    }
+
+    // Winners pay the lowest winning price
+    let clearing_price = auction.book.clearing_price();
+    let decimals_sell = get_mint_decimals(mint_sell)?;
+    let decimals_buy = get_mint_decimals(mint_buy)?;
+
+    let mut sold: u64 = 0;
+    let mut proceeds: u64 = 0;
+    for (_, fill) in auction.book.fills(auction.sell_amt) {
+        sold += fill;
+        proceeds = proceeds
+            .checked_add(pay_amount(
+                fill,
+                clearing_price,
+                decimals_sell,
+                decimals_buy,
+            )?)
+            .ok_or(AuctionError::MathOverflow)?;
+    }
+
+    // Deposits of bidders that did not reveal are forfeited
+    let forfeited = auction
+        .deposit
+        .checked_mul(auction.num_commits - auction.num_revealed)
+        .ok_or(AuctionError::MathOverflow)?;
+    let proceeds = proceeds
+        .checked_add(forfeited)
+        .ok_or(AuctionError::MathOverflow)?;
+
+    let id_bytes = id.to_le_bytes();
+    let seeds = &[
+        SealedAuction::SEED_PREFIX,
+        seller.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &id_bytes,
+        &[bump],
+    ];
+
+    // Send proceeds to seller
+    if proceeds > 0 {
+        create_ata_idempotent(
+            payer,
+            mint_buy,
+            seller,
+            seller_buy_ata,
+            token_program_buy,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program_buy,
+            sealed_buy_ata,
+            mint_buy,
+            seller_buy_ata,
+            sealed_pda,
+            proceeds,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Send unsold sell token back to seller
+    let unsold = auction.sell_amt - sold;
+    if unsold > 0 {
+        create_ata_idempotent(
+            payer,
+            mint_sell,
+            seller,
+            seller_sell_ata,
+            token_program_sell,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program_sell,
+            sealed_sell_ata,
+            mint_sell,
+            seller_sell_ata,
+            sealed_pda,
+            unsold,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Nothing left to claim
+    if auction.num_revealed == 0 {
+        close_escrow(
+            token_program_sell,
+            sealed_sell_ata,
+            mint_sell,
+            seller_sell_ata,
+            seller,
+            sealed_pda,
+            seeds,
+            hook_accounts,
+        )?;
+        close_escrow(
+            token_program_buy,
+            sealed_buy_ata,
+            mint_buy,
+            seller_buy_ata,
+            seller,
+            sealed_pda,
+            seeds,
+            hook_accounts,
+        )?;
+        return close_pda(sealed_pda, seller, sys_program);
+    }
+
+    let mut data = sealed_pda.data.borrow_mut();
+    let auction = SealedAuction::load_mut(&mut data)?;
+    auction.settled = 1;
+    auction.clearing_price = clearing_price;
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::lib::get_config_pda;
+use crate::error::AuctionError;
+use crate::state::Config;
+
+pub fn set_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    fee_bps: u64,
+    // Config PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_iter)?;
+    let config_pda = next_account_info(account_iter)?;
+
+    // Check admin signed
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check that config_pda matches expected PDA
+    if *config_pda.key != get_config_pda(program_id, bump)? {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check fee_bps <= MAX_FEE_BPS
+    if fee_bps > Config::MAX_FEE_BPS {
+        return Err(AuctionError::InvalidFee.into());
+    }
+
+    let mut data = config_pda.data.borrow_mut();
+    let config = Config::load_mut(&mut data)?;
+
+    // Check signer is the admin
+    if config.admin != *admin.key {
+        return Err(AuctionError::Unauthorized.into());
+    }
+
+    config.fee_bps = fee_bps;
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_token_program, close_ata, close_pda, create_ata_idempotent, get_ata,
+    get_english_pda, get_token_balance, transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::state::EnglishAuction;
+
+// Anyone can settle an English auction after it ended
+pub fn settle(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    // English auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    // Pays for ATAs of the receivers, if they do not exist
+    let payer = next_account_info(account_iter)?;
+    let seller = next_account_info(account_iter)?;
+    // Highest bidder, ignored if there are no bids
+    let bidder = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let english_pda = next_account_info(account_iter)?;
+    let english_sell_ata = next_account_info(account_iter)?;
+    let english_buy_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    let seller_buy_ata = next_account_info(account_iter)?;
+    let bidder_sell_ata = next_account_info(account_iter)?;
+    let token_program_sell = next_account_info(account_iter)?;
+    let token_program_buy = next_account_info(account_iter)?;
+    let ata_program = next_account_info(account_iter)?;
+    let sys_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check payer signed
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token programs of the mints
+    check_token_program(mint_sell, token_program_sell)?;
+    check_token_program(mint_buy, token_program_buy)?;
+    // Check that english_pda matches expected PDA
+    if *english_pda.key
+        != get_english_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that english_sell_ata matches calculated account
+    if *english_sell_ata.key
+        != get_ata(english_pda.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that english_buy_ata matches calculated account
+    if *english_buy_ata.key
+        != get_ata(english_pda.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program_sell.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_buy_ata matches calculated account
+    if *seller_buy_ata.key
+        != get_ata(seller.key, mint_buy.key, token_program_buy.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = english_pda.data.borrow();
+        *EnglishAuction::load(&data)?
+    }; // Drop borrow here
+
+    // Check auction has ended
+    if now < auction.end_time {
+        return Err(AuctionError::AuctionNotEnded.into());
+    }
+
+    let id_bytes = id.to_le_bytes();
+    let seeds = &[
+        EnglishAuction::SEED_PREFIX,
+        seller.key.as_ref(),
+        mint_sell.key.as_ref(),
+        mint_buy.key.as_ref(),
+        &id_bytes,
+        &[bump],
+    ];
+
+    // Send the highest bid to seller
+    // Whole balance, so that tokens sent to the escrow can not block closing
+    let bid_amt = get_token_balance(english_buy_ata)?;
+    if bid_amt > 0 {
+        create_ata_idempotent(
+            payer,
+            mint_buy,
+            seller,
+            seller_buy_ata,
+            token_program_buy,
+            sys_program,
+            ata_program,
+        )?;
+        transfer_from_pda(
+            token_program_buy,
+            english_buy_ata,
+            mint_buy,
+            seller_buy_ata,
+            english_pda,
+            bid_amt,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    // Lot goes to the highest bidder, or back to seller if nobody bid
+    let (owner, owner_sell_ata) = if auction.bid > 0 {
+        // Check bidder is the highest bidder
+        if *bidder.key != auction.bidder {
+            return Err(AuctionError::InvalidAccount.into());
+        }
+        // Check that bidder_sell_ata matches calculated account
+        if *bidder_sell_ata.key
+            != get_ata(bidder.key, mint_sell.key, token_program_sell.key)
+        {
+            return Err(AuctionError::InvalidTokenAccount.into());
+        }
+        (bidder, bidder_sell_ata)
+    } else {
+        (seller, seller_sell_ata)
+    };
+
+    // Send lot
+    create_ata_idempotent(
+        payer,
+        mint_sell,
+        owner,
+        owner_sell_ata,
+        token_program_sell,
+        sys_program,
+        ata_program,
+    )?;
+    transfer_from_pda(
+        token_program_sell,
+        english_sell_ata,
+        mint_sell,
+        owner_sell_ata,
+        english_pda,
+        get_token_balance(english_sell_ata)?,
+        seeds,
+        hook_accounts,
+    )?;
+
+    // Close english_sell_ata and english_buy_ata
+    close_ata(
+        token_program_sell,
+        english_sell_ata,
+        mint_sell,
+        seller,
+        english_pda,
+        seeds,
+    )?;
+    close_ata(
+        token_program_buy,
+        english_buy_ata,
+        mint_buy,
+        seller,
+        english_pda,
+        seeds,
+    )?;
+
+    // Close english_pda
+    close_pda(english_pda, seller, sys_program)
+}
@@ -0,0 +1,133 @@
+use solana_program::{
+    account_info::{AccountInfo, next_account_info},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
+};
+
+use super::lib::{
+    check_params, check_token_program, get_ata, get_pda, get_pre_fee_amount,
+    transfer, transfer_from_pda,
+};
+use crate::error::AuctionError;
+use crate::state::Auction;
+
+// Fix the parameters of an auction that has not started yet, instead of
+// cancelling and creating it again
+pub fn update(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    id: u64,
+    start_price: u64,
+    end_price: u64,
+    start_time: u64,
+    end_time: u64,
+    // New amount of sell token in the auction
+    sell_amt: u64,
+    // Auction PDA bump
+    bump: u8,
+) -> Result<(), ProgramError> {
+    let account_iter = &mut accounts.iter();
+
+    let seller = next_account_info(account_iter)?;
+    let mint_sell = next_account_info(account_iter)?;
+    let mint_buy = next_account_info(account_iter)?;
+    let auction_pda = next_account_info(account_iter)?;
+    let auction_sell_ata = next_account_info(account_iter)?;
+    let seller_sell_ata = next_account_info(account_iter)?;
+    // Token program of mint_sell
+    let token_program = next_account_info(account_iter)?;
+    // Accounts of the Token-2022 transfer hooks of the mints, if any
+    let hook_accounts = account_iter.as_slice();
+
+    // Check seller signed
+    if !seller.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    // Check the token program of mint_sell
+    check_token_program(mint_sell, token_program)?;
+    // Check that auction_pda matches expected PDA
+    if *auction_pda.key
+        != get_pda(
+            program_id,
+            seller.key,
+            mint_sell.key,
+            mint_buy.key,
+            id,
+            bump,
+        )?
+    {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    // Check that auction_sell_ata matches calculated account
+    if *auction_sell_ata.key
+        != get_ata(auction_pda.key, mint_sell.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+    // Check that seller_sell_ata matches calculated account
+    if *seller_sell_ata.key
+        != get_ata(seller.key, mint_sell.key, token_program.key)
+    {
+        return Err(AuctionError::InvalidTokenAccount.into());
+    }
+
+    let clock = Clock::get()?;
+    let now: u64 = clock.unix_timestamp.try_into().unwrap();
+
+    let auction = {
+        let data = auction_pda.data.borrow();
+        *Auction::load(&data)?
+    }; // Drop borrow here
+
+    // Check auction has not started, so nothing was sold yet
+    if auction.start_time <= now {
+        return Err(AuctionError::AuctionStarted.into());
+    }
+    // Check prices, times and sell_amt
+    check_params(now, start_price, end_price, start_time, end_time, sell_amt)?;
+
+    if sell_amt > auction.remaining {
+        // Top up
+        transfer(
+            token_program,
+            seller_sell_ata,
+            mint_sell,
+            auction_sell_ata,
+            seller,
+            get_pre_fee_amount(mint_sell, sell_amt - auction.remaining)?,
+            hook_accounts,
+        )?;
+    } else if sell_amt < auction.remaining {
+        // Withdraw
+        let id_bytes = id.to_le_bytes();
+        let seeds = &[
+            Auction::SEED_PREFIX,
+            seller.key.as_ref(),
+            mint_sell.key.as_ref(),
+            mint_buy.key.as_ref(),
+            &id_bytes,
+            &[bump],
+        ];
+        transfer_from_pda(
+            token_program,
+            auction_sell_ata,
+            mint_sell,
+            seller_sell_ata,
+            auction_pda,
+            auction.remaining - sell_amt,
+            seeds,
+            hook_accounts,
+        )?;
+    }
+
+    let mut data = auction_pda.data.borrow_mut();
+    let auction = Auction::load_mut(&mut data)?;
+    auction.start_price = start_price;
+    auction.end_price = end_price;
+    auction.start_time = start_time;
+    auction.end_time = end_time;
+    auction.remaining = sell_amt;
+
+    Ok(())
+}
@@ -4,12 +4,194 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
+pub mod book;
+pub mod error;
 pub mod instructions;
+pub mod merkle;
+pub mod price;
 pub mod state;
 
+use state::PriceCurve;
+
+// Options of a Dutch auction, the fields left to Default are turned off
+#[derive(BorshDeserialize, BorshSerialize, Clone, Debug, Default)]
+pub struct InitParams {
+    // Must equal the seller's Counter.next_id
+    pub id: u64,
+    pub start_price: u64,
+    pub end_price: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub sell_amt: u64,
+    pub curve: PriceCurve,
+    // Root of the merkle tree of allowed buyers, all zeros if anyone
+    // can buy
+    pub merkle_root: [u8; 32],
+    // Max amount of sell token per buyer, 0 if there is no cap
+    pub wallet_cap: u64,
+    // Oracle account pegging the floor price, default if there is none.
+    // Must be owned by the oracle program of the Config.
+    pub oracle: Pubkey,
+    // Decimals of the oracle price, 10^oracle_decimals = 1 whole buy
+    // token per whole sell token
+    pub oracle_decimals: u8,
+    // Floor in bps of the oracle price
+    pub oracle_bps: u64,
+    // Seconds after end_time before buyers can claim anything
+    pub vest_cliff: u64,
+    // Buyers claim the sell token linearly over vest_duration seconds
+    // from end_time, 0 if they receive it on buy
+    pub vest_duration: u64,
+    // Buyers place bids with BatchBid instead of buying, winners pay
+    // the uniform price found by Clear
+    pub batch: bool,
+    // mint_sell is an NFT with 0 decimals and supply 1, sell_amt must
+    // be 1
+    pub nft: bool,
+    // Checked against the Token-2022 MetadataPointer of the NFT,
+    // default if not checked
+    pub metadata: Pubkey,
+    // Checked against the group of the Token-2022 TokenGroupMember of
+    // the NFT, default if not checked
+    pub collection: Pubkey,
+    // Auction PDA bump
+    pub bump: u8,
+    // Counter PDA bump
+    pub counter_bump: u8,
+    // Batch PDA bump, only used if batch is true
+    pub batch_bump: u8,
+}
+
 #[derive(BorshDeserialize, BorshSerialize)]
 pub enum Cmd {
-    Init {
+    Init(InitParams),
+    Buy {
+        id: u64,
+        max_price: u64,
+        // Amount of sell token to buy
+        buy_amt: u64,
+        // Auction PDA bump
+        bump: u8,
+        // Purchase PDA bump, only used if the auction has a wallet_cap
+        purchase_bump: u8,
+        // Config PDA bump
+        config_bump: u8,
+        // AuctionResult PDA bump, only used if the buy sells out the auction
+        result_bump: u8,
+        // Vesting PDA bump, only used if the auction has vesting
+        vesting_bump: u8,
+        // Merkle proof of the buyer, empty if anyone can buy
+        proof: Vec<[u8; 32]>,
+    },
+    Cancel {
+        id: u64,
+        // Auction PDA bump
+        bump: u8,
+        // AuctionResult PDA bump
+        result_bump: u8,
+    },
+    // English auction, bids are amounts of buy token for the whole lot
+    EnglishInit {
+        // Must equal the seller's Counter.next_id
+        id: u64,
+        // Minimum first bid
+        reserve: u64,
+        // Minimum raise over the highest bid
+        min_increment: u64,
+        start_time: u64,
+        end_time: u64,
+        // Bids in the last extension seconds push end_time to
+        // now + extension
+        extension: u64,
+        sell_amt: u64,
+        // English auction PDA bump
+        bump: u8,
+        // Counter PDA bump
+        counter_bump: u8,
+    },
+    Bid {
+        id: u64,
+        amount: u64,
+        // English auction PDA bump
+        bump: u8,
+    },
+    Settle {
+        id: u64,
+        // English auction PDA bump
+        bump: u8,
+    },
+    // Sealed bid auction, winners pay the uniform clearing price
+    SealedInit {
+        // Must equal the seller's Counter.next_id
+        id: u64,
+        // Minimum price of 1 whole sell token
+        reserve: u64,
+        // Buy token escrowed with each commitment
+        deposit: u64,
+        commit_end: u64,
+        reveal_end: u64,
+        sell_amt: u64,
+        // Sealed auction PDA bump
+        bump: u8,
+        // Counter PDA bump
+        counter_bump: u8,
+    },
+    Commit {
+        id: u64,
+        // Commitment::hash(price, amount, salt)
+        hash: [u8; 32],
+        // Sealed auction PDA bump
+        bump: u8,
+        // Commitment PDA bump
+        commitment_bump: u8,
+    },
+    Reveal {
+        id: u64,
+        price: u64,
+        amount: u64,
+        salt: [u8; 32],
+        // Sealed auction PDA bump
+        bump: u8,
+        // Commitment PDA bump
+        commitment_bump: u8,
+    },
+    SealedSettle {
+        id: u64,
+        // Sealed auction PDA bump
+        bump: u8,
+    },
+    SealedClaim {
+        id: u64,
+        // Sealed auction PDA bump
+        bump: u8,
+        // Commitment PDA bump
+        commitment_bump: u8,
+    },
+    // Create the Config, the signer becomes the admin and picks the program
+    // that owns the oracles of oracle pegged auctions
+    InitConfig {
+        fee_bps: u64,
+        // Config PDA bump
+        bump: u8,
+    },
+    // Admin only
+    SetFee {
+        fee_bps: u64,
+        // Config PDA bump
+        bump: u8,
+    },
+    // Anyone can close an auction after end_time
+    Expire {
+        id: u64,
+        // Auction PDA bump
+        bump: u8,
+        // AuctionResult PDA bump
+        result_bump: u8,
+    },
+    // Seller only, before start_time. sell_amt replaces the escrowed
+    // amount, the difference is topped up or withdrawn.
+    Update {
+        id: u64,
         start_price: u64,
         end_price: u64,
         start_time: u64,
@@ -18,17 +200,85 @@ pub enum Cmd {
         // Auction PDA bump
         bump: u8,
     },
-    Buy {
-        max_price: u64,
+    // Buyer claims the vested part of the sell token bought
+    Claim {
+        // Vesting PDA bump
+        bump: u8,
+    },
+    // Bid in a batch auction, the payment at price is escrowed
+    BatchBid {
+        id: u64,
+        // Max price of 1 whole sell token
+        price: u64,
+        // Amount of sell token
+        amount: u64,
         // Auction PDA bump
         bump: u8,
+        // Batch PDA bump
+        batch_bump: u8,
+        // BatchBid PDA bump
+        bid_bump: u8,
     },
-    Cancel {
+    // Anyone can set the clearing price of a batch auction once demand
+    // meets supply or after end_time
+    Clear {
+        id: u64,
+        // Auction PDA bump
+        bump: u8,
+        // Batch PDA bump
+        batch_bump: u8,
+        // AuctionResult PDA bump, only used if there are no bids
+        result_bump: u8,
+    },
+    BatchClaim {
+        id: u64,
         // Auction PDA bump
         bump: u8,
+        // Batch PDA bump
+        batch_bump: u8,
+        // BatchBid PDA bump
+        bid_bump: u8,
+        // AuctionResult PDA bump, only used by the last claim
+        result_bump: u8,
+    },
+    // Read only, returns a price::Quote as return data
+    GetPrice {
+        id: u64,
+        bump: u8,
+    },
+    // Reverse Dutch auction, the buyer escrows the payment at end_price and
+    // the price rises until sellers deliver buy_amt sell token
+    ReverseInit {
+        // Must equal the buyer's Counter.next_id
+        id: u64,
+        start_price: u64,
+        end_price: u64,
+        start_time: u64,
+        end_time: u64,
+        // Amount of sell token wanted
+        buy_amt: u64,
+        // Reverse auction PDA bump
+        bump: u8,
+        // Counter PDA bump
+        counter_bump: u8,
+    },
+    // Seller delivers sell_amt sell token at the current price
+    Accept {
+        id: u64,
+        min_price: u64,
+        sell_amt: u64,
+        // Reverse auction PDA bump
+        bump: u8,
+    },
+    // Buyer only, refunds the escrow
+    ReverseCancel {
+        id: u64,
+        // Reverse auction PDA bump
+        bump: u8,
     },
 }
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
@@ -39,7 +289,160 @@ pub fn process_instruction(
     let ix = Cmd::try_from_slice(instruction_data)?;
 
     match ix {
-        Cmd::Init {
+        Cmd::Init(params) => {
+            instructions::init(program_id, accounts, params)?;
+        }
+        Cmd::Buy {
+            id,
+            max_price,
+            buy_amt,
+            bump,
+            purchase_bump,
+            config_bump,
+            result_bump,
+            vesting_bump,
+            proof,
+        } => {
+            instructions::buy(
+                program_id,
+                accounts,
+                id,
+                max_price,
+                buy_amt,
+                bump,
+                purchase_bump,
+                config_bump,
+                result_bump,
+                vesting_bump,
+                &proof,
+            )?;
+        }
+        Cmd::Cancel {
+            id,
+            bump,
+            result_bump,
+        } => {
+            instructions::cancel(program_id, accounts, id, bump, result_bump)?;
+        }
+        Cmd::EnglishInit {
+            id,
+            reserve,
+            min_increment,
+            start_time,
+            end_time,
+            extension,
+            sell_amt,
+            bump,
+            counter_bump,
+        } => {
+            instructions::english_init(
+                program_id,
+                accounts,
+                id,
+                reserve,
+                min_increment,
+                start_time,
+                end_time,
+                extension,
+                sell_amt,
+                bump,
+                counter_bump,
+            )?;
+        }
+        Cmd::Bid { id, amount, bump } => {
+            instructions::bid(program_id, accounts, id, amount, bump)?;
+        }
+        Cmd::Settle { id, bump } => {
+            instructions::settle(program_id, accounts, id, bump)?;
+        }
+        Cmd::SealedInit {
+            id,
+            reserve,
+            deposit,
+            commit_end,
+            reveal_end,
+            sell_amt,
+            bump,
+            counter_bump,
+        } => {
+            instructions::sealed_init(
+                program_id,
+                accounts,
+                id,
+                reserve,
+                deposit,
+                commit_end,
+                reveal_end,
+                sell_amt,
+                bump,
+                counter_bump,
+            )?;
+        }
+        Cmd::Commit {
+            id,
+            hash,
+            bump,
+            commitment_bump,
+        } => {
+            instructions::commit(
+                program_id,
+                accounts,
+                id,
+                hash,
+                bump,
+                commitment_bump,
+            )?;
+        }
+        Cmd::Reveal {
+            id,
+            price,
+            amount,
+            salt,
+            bump,
+            commitment_bump,
+        } => {
+            instructions::reveal(
+                program_id,
+                accounts,
+                id,
+                price,
+                amount,
+                salt,
+                bump,
+                commitment_bump,
+            )?;
+        }
+        Cmd::SealedSettle { id, bump } => {
+            instructions::sealed_settle(program_id, accounts, id, bump)?;
+        }
+        Cmd::SealedClaim {
+            id,
+            bump,
+            commitment_bump,
+        } => {
+            instructions::sealed_claim(
+                program_id,
+                accounts,
+                id,
+                bump,
+                commitment_bump,
+            )?;
+        }
+        Cmd::InitConfig { fee_bps, bump } => {
+            instructions::init_config(program_id, accounts, fee_bps, bump)?;
+        }
+        Cmd::SetFee { fee_bps, bump } => {
+            instructions::set_fee(program_id, accounts, fee_bps, bump)?;
+        }
+        Cmd::Expire {
+            id,
+            bump,
+            result_bump,
+        } => {
+            instructions::expire(program_id, accounts, id, bump, result_bump)?;
+        }
+        Cmd::Update {
+            id,
             start_price,
             end_price,
             start_time,
@@ -47,9 +450,10 @@ pub fn process_instruction(
             sell_amt,
             bump,
         } => {
-            instructions::init(
+            instructions::update(
                 program_id,
                 accounts,
+                id,
                 start_price,
                 end_price,
                 start_time,
@@ -58,11 +462,92 @@ pub fn process_instruction(
                 bump,
             )?;
         }
-        Cmd::Buy { max_price, bump } => {
-            instructions::buy(program_id, accounts, max_price, bump)?;
+        Cmd::Claim { bump } => {
+            instructions::claim(program_id, accounts, bump)?;
+        }
+        Cmd::BatchBid {
+            id,
+            price,
+            amount,
+            bump,
+            batch_bump,
+            bid_bump,
+        } => {
+            instructions::batch_bid(
+                program_id, accounts, id, price, amount, bump, batch_bump,
+                bid_bump,
+            )?;
+        }
+        Cmd::Clear {
+            id,
+            bump,
+            batch_bump,
+            result_bump,
+        } => {
+            instructions::clear(
+                program_id,
+                accounts,
+                id,
+                bump,
+                batch_bump,
+                result_bump,
+            )?;
+        }
+        Cmd::BatchClaim {
+            id,
+            bump,
+            batch_bump,
+            bid_bump,
+            result_bump,
+        } => {
+            instructions::batch_claim(
+                program_id,
+                accounts,
+                id,
+                bump,
+                batch_bump,
+                bid_bump,
+                result_bump,
+            )?;
+        }
+        Cmd::GetPrice { id, bump } => {
+            instructions::get_price(program_id, accounts, id, bump)?;
+        }
+        Cmd::ReverseInit {
+            id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            buy_amt,
+            bump,
+            counter_bump,
+        } => {
+            instructions::reverse_init(
+                program_id,
+                accounts,
+                id,
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                buy_amt,
+                bump,
+                counter_bump,
+            )?;
+        }
+        Cmd::Accept {
+            id,
+            min_price,
+            sell_amt,
+            bump,
+        } => {
+            instructions::accept(
+                program_id, accounts, id, min_price, sell_amt, bump,
+            )?;
         }
-        Cmd::Cancel { bump } => {
-            instructions::cancel(program_id, accounts, bump)?;
+        Cmd::ReverseCancel { id, bump } => {
+            instructions::reverse_cancel(program_id, accounts, id, bump)?;
         }
     }
 
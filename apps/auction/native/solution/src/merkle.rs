@@ -0,0 +1,22 @@
+use solana_program::{hash::hashv, pubkey::Pubkey};
+
+// Leaves and nodes are hashed with different prefixes so that a node can not
+// be passed off as a leaf
+pub fn leaf(wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[&[0], wallet.as_ref()]).to_bytes()
+}
+
+// Pairs are sorted so that proofs do not need left / right flags
+pub fn node(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (l, r) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[1], l, r]).to_bytes()
+}
+
+// Check that leaf is in the tree with this root.
+// proof is the sibling of each node from the leaf up to the root.
+pub fn verify(root: &[u8; 32], leaf: [u8; 32], proof: &[[u8; 32]]) -> bool {
+    proof
+        .iter()
+        .fold(leaf, |hash, sibling| node(&hash, sibling))
+        == *root
+}
@@ -0,0 +1,183 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+use crate::error::AuctionError;
+use crate::state::{Auction, PriceCurve, ReverseAuction};
+
+// Prices are fixed point, PRICE_SCALE = 1 whole buy token
+pub const PRICE_SCALE: u64 = 1_000_000;
+
+// Fixed point 1.0 for 2^-x
+const ONE: u128 = 1_000_000_000_000;
+// ln(2)^k / k! for k = 1..4, scaled by ONE
+const C1: u128 = 693_147_180_560;
+const C2: u128 = 240_226_506_959;
+const C3: u128 = 55_504_108_665;
+const C4: u128 = 9_618_129_108;
+
+// 2^-x for 0 <= x < ONE, scaled by ONE
+// Taylor series of e^(-x ln 2) up to x^4. It stops on a positive term so
+// it never undershoots 0.5 at x = 1 and the price keeps decreasing across
+// half lives. Error < 0.25%.
+fn exp2_neg(x: u128) -> u128 {
+    let x2 = x * x / ONE;
+    let x3 = x2 * x / ONE;
+    let x4 = x3 * x / ONE;
+    ONE + C2 * x2 / ONE + C4 * x4 / ONE - C1 * x / ONE - C3 * x3 / ONE
+}
+
+// Price of 1 whole sell token at time now, PRICE_SCALE = 1 whole buy token
+// Same function is used by buy and by clients to quote a price.
+// Returns start_price before start_time and end_price from end_time.
+pub fn price_at(auction: &Auction, now: u64) -> Result<u64, ProgramError> {
+    if now <= auction.start_time {
+        return Ok(auction.start_price);
+    }
+    if now >= auction.end_time {
+        return Ok(auction.end_price);
+    }
+
+    // u128 so that products of u64 can not overflow
+    let start_price = auction.start_price as u128;
+    let end_price = auction.end_price as u128;
+    let elapsed = (now - auction.start_time) as u128;
+    let duration = (auction.end_time - auction.start_time) as u128;
+    let drop = start_price
+        .checked_sub(end_price)
+        .ok_or(AuctionError::MathOverflow)?;
+
+    let price_decrease = match auction.price_curve()? {
+        PriceCurve::Linear => drop * elapsed / duration,
+        PriceCurve::Exponential { half_life } => {
+            let half_life = half_life as u128;
+            let halvings = elapsed / half_life;
+            // Distance left to end_price
+            let left = if halvings >= 128 {
+                0
+            } else {
+                let x = (elapsed % half_life) * ONE / half_life;
+                (drop >> halvings) * exp2_neg(x) / ONE
+            };
+            drop - left
+        }
+        PriceCurve::Stepped { step } => {
+            let elapsed = elapsed - elapsed % (step as u128);
+            drop * elapsed / duration
+        }
+    };
+
+    start_price
+        .checked_sub(price_decrease)
+        .and_then(|p| u64::try_from(p).ok())
+        .ok_or(AuctionError::MathOverflow.into())
+}
+
+// Floor of an oracle pegged auction, oracle_bps of the oracle price.
+// The oracle price has oracle_decimals decimals and is converted to
+// PRICE_SCALE, rounded down.
+pub fn oracle_floor(
+    oracle_price: u64,
+    oracle_decimals: u64,
+    oracle_bps: u64,
+) -> Result<u64, ProgramError> {
+    let num = (oracle_price as u128)
+        .checked_mul(PRICE_SCALE as u128)
+        .and_then(|n| n.checked_mul(oracle_bps as u128))
+        .ok_or(AuctionError::MathOverflow)?;
+    let den = 10u128.pow(oracle_decimals as u32) * 10_000;
+    u64::try_from(num / den).map_err(|_| AuctionError::MathOverflow.into())
+}
+
+// Price of 1 whole sell token at time now in a ReverseAuction
+// Rises linearly from start_price at start_time to end_price at end_time,
+// rounded down in favor of the buyer.
+pub fn reverse_price_at(
+    auction: &ReverseAuction,
+    now: u64,
+) -> Result<u64, ProgramError> {
+    if now <= auction.start_time {
+        return Ok(auction.start_price);
+    }
+    if now >= auction.end_time {
+        return Ok(auction.end_price);
+    }
+
+    // u128 so that products of u64 can not overflow
+    let rise = (auction.end_price - auction.start_price) as u128;
+    let elapsed = (now - auction.start_time) as u128;
+    let duration = (auction.end_time - auction.start_time) as u128;
+
+    u64::try_from(auction.start_price as u128 + rise * elapsed / duration)
+        .map_err(|_| AuctionError::MathOverflow.into())
+}
+
+// Amount of buy token (base units) to pay for buy_amt of sell token (base
+// units) at price. Rounded up, in favor of the seller.
+pub fn pay_amount(
+    buy_amt: u64,
+    price: u64,
+    decimals_sell: u8,
+    decimals_buy: u8,
+) -> Result<u64, ProgramError> {
+    let (num, den) = scale(buy_amt, price, decimals_sell, decimals_buy)?;
+    u64::try_from(num.div_ceil(den))
+        .map_err(|_| AuctionError::MathOverflow.into())
+}
+
+// Amount of buy token (base units) a seller receives for sell_amt of sell
+// token (base units) at price in a ReverseAuction. Rounded down, in favor
+// of the buyer who escrowed the payment, so partial fills never pay out
+// more than the escrow.
+pub fn receive_amount(
+    sell_amt: u64,
+    price: u64,
+    decimals_sell: u8,
+    decimals_buy: u8,
+) -> Result<u64, ProgramError> {
+    let (num, den) = scale(sell_amt, price, decimals_sell, decimals_buy)?;
+    u64::try_from(num / den).map_err(|_| AuctionError::MathOverflow.into())
+}
+
+// Numerator and denominator of amt * price in buy token base units
+// Only the difference of the decimals is scaled so that large lots of
+// mints with many decimals do not overflow.
+fn scale(
+    amt: u64,
+    price: u64,
+    decimals_sell: u8,
+    decimals_buy: u8,
+) -> Result<(u128, u128), ProgramError> {
+    // u64 * u64 fits in u128
+    let mut num = (amt as u128) * (price as u128);
+    let mut den = PRICE_SCALE as u128;
+    if decimals_buy >= decimals_sell {
+        num = num
+            .checked_mul(pow10(decimals_buy - decimals_sell)?)
+            .ok_or(AuctionError::MathOverflow)?;
+    } else {
+        den = den
+            .checked_mul(pow10(decimals_sell - decimals_buy)?)
+            .ok_or(AuctionError::MathOverflow)?;
+    }
+    Ok((num, den))
+}
+
+fn pow10(exp: u8) -> Result<u128, ProgramError> {
+    10u128
+        .checked_pow(exp.into())
+        .ok_or(AuctionError::MathOverflow.into())
+}
+
+// Returned by GetPrice
+#[derive(BorshDeserialize, BorshSerialize, Clone, Copy, Debug, PartialEq)]
+pub struct Quote {
+    // Price of 1 whole sell token, same as Buy would pay now
+    pub price: u64,
+    // Sell token left in the auction
+    pub remaining: u64,
+    // Seconds until end_time, 0 if the auction has ended
+    pub time_left: u64,
+    // Whether the sell token is an NFT checked by Init, and its collection
+    pub nft: bool,
+    pub collection: Pubkey,
+}
@@ -1,17 +1,518 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use bytemuck::{Pod, Zeroable};
+use solana_program::{
+    hash::hashv, program_error::ProgramError, pubkey::Pubkey,
+};
 
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+use crate::book::Book;
+
+// How the price moves from start_price to end_price, see price::price_at
+#[derive(
+    BorshDeserialize, BorshSerialize, Clone, Copy, Debug, Default, PartialEq,
+)]
+pub enum PriceCurve {
+    // Decreases linearly from start_time to end_time
+    #[default]
+    Linear,
+    // Distance to end_price halves every half_life seconds
+    Exponential {
+        half_life: u64,
+    },
+    // Linear, but only drops every step seconds
+    Stepped {
+        step: u64,
+    },
+}
+
+impl PriceCurve {
+    pub const LINEAR: u64 = 0;
+    pub const EXPONENTIAL: u64 = 1;
+    pub const STEPPED: u64 = 2;
+
+    // (kind, param) stored in Auction
+    pub fn pack(&self) -> (u64, u64) {
+        match *self {
+            PriceCurve::Linear => (Self::LINEAR, 0),
+            PriceCurve::Exponential { half_life } => {
+                (Self::EXPONENTIAL, half_life)
+            }
+            PriceCurve::Stepped { step } => (Self::STEPPED, step),
+        }
+    }
+
+    pub fn unpack(kind: u64, param: u64) -> Result<Self, ProgramError> {
+        match kind {
+            Self::LINEAR => Ok(PriceCurve::Linear),
+            Self::EXPONENTIAL => {
+                Ok(PriceCurve::Exponential { half_life: param })
+            }
+            Self::STEPPED => Ok(PriceCurve::Stepped { step: param }),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
+
+// Zero copy account, stored after an 8 byte discriminator
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
 pub struct Auction {
+    // Stored first so that clients can filter auctions by seller
+    pub seller: Pubkey,
     pub mint_sell: Pubkey,
     pub mint_buy: Pubkey,
-    // Price of 1 sell token, 1e6 = 1 buy token
+    // Id handed out by the seller's Counter
+    pub id: u64,
+    // Price of 1 whole sell token, price::PRICE_SCALE = 1 whole buy token
     pub start_price: u64,
     pub end_price: u64,
     pub start_time: u64,
     pub end_time: u64,
+    // Amount of sell token left, buyers can take part of it
+    pub remaining: u64,
+    // PriceCurve packed as (kind, param)
+    pub curve: u64,
+    pub curve_param: u64,
+    // Root of the merkle tree of buyers allowed to buy, see merkle.rs.
+    // All zeros if anyone can buy.
+    pub merkle_root: [u8; 32],
+    // Max amount of sell token per buyer, 0 if there is no cap
+    pub wallet_cap: u64,
+    // Oracle account, default if the floor is end_price
+    pub oracle: Pubkey,
+    // Program that must own the oracle account, copied from the Config by
+    // Init
+    pub oracle_program: Pubkey,
+    // Decimals of the oracle price, converted to PRICE_SCALE by
+    // price::oracle_floor
+    pub oracle_decimals: u64,
+    // Price never drops below oracle_bps of the oracle price
+    pub oracle_bps: u64,
+    // Sell token sold and buy token paid so far, copied to the
+    // AuctionResult
+    pub sold: u64,
+    pub proceeds: u64,
+    // Bought sell token goes to a Vesting PDA of the buyer, released
+    // linearly over vest_duration seconds from end_time and nothing before
+    // vest_cliff. vest_duration is 0 if buyers receive the tokens directly.
+    pub vest_cliff: u64,
+    pub vest_duration: u64,
+    // 1 if buyers place bids with BatchBid and all winners pay the uniform
+    // price found by Clear, see Batch. 0 if buyers buy with Buy.
+    pub batch: u64,
+    // 1 if mint_sell is an NFT checked by Init, see check_nft
+    pub nft: u64,
+    // Collection the NFT was checked to be a member of, default if not
+    // checked
+    pub collection: Pubkey,
 }
 
 impl Auction {
     pub const SEED_PREFIX: &'static [u8; 7] = b"auction";
+    // sha256("account:Auction")[..8], same as Anchor
+    pub const DISCRIMINATOR: [u8; 8] = [218, 94, 247, 242, 126, 233, 131, 81];
+    // 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 32 + 32
+    // + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32
+    pub const SPACE: u64 = 368;
+    // Offset of seller in account data, for getProgramAccounts filters
+    pub const SELLER_OFFSET: usize = 8;
+    // Share of the auction PDA rent paid to whoever expires the auction
+    pub const CRANK_REWARD_BPS: u64 = 1_000;
+    // Max seconds since the last oracle update
+    pub const MAX_ORACLE_AGE: u64 = 60;
+    // Max decimals of an oracle price
+    pub const MAX_ORACLE_DECIMALS: u8 = 18;
+
+    pub fn price_curve(&self) -> Result<PriceCurve, ProgramError> {
+        PriceCurve::unpack(self.curve, self.curve_param)
+    }
+}
+
+// Outcome of a Dutch auction, created when the auction PDA is closed
+// PDA of the Auction, its rent is taken from the closed auction PDA.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct AuctionResult {
+    // Same order as Auction so that clients can filter by seller or mint
+    pub seller: Pubkey,
+    pub mint_sell: Pubkey,
+    pub mint_buy: Pubkey,
+    // Buyer of the last lot, default if the auction did not sell out
+    pub buyer: Pubkey,
+    pub id: u64,
+    // Total sell token sold and buy token paid, including the fee
+    pub sold: u64,
+    pub proceeds: u64,
+    // Price of the last lot, 0 if the auction did not sell out.
+    // Clearing price of a batch auction.
+    pub price: u64,
+    pub closed_at: u64,
+    // SOLD, CANCELLED or EXPIRED
+    pub outcome: u64,
+}
+
+impl AuctionResult {
+    pub const SEED_PREFIX: &'static [u8; 6] = b"result";
+    // sha256("account:AuctionResult")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [182, 105, 71, 113, 228, 147, 117, 135];
+    // 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8
+    pub const SPACE: u64 = 184;
+    // Offsets in account data, for getProgramAccounts filters
+    pub const SELLER_OFFSET: usize = 8;
+    pub const MINT_SELL_OFFSET: usize = 40;
+    pub const MINT_BUY_OFFSET: usize = 72;
+
+    pub const SOLD: u64 = 0;
+    pub const CANCELLED: u64 = 1;
+    pub const EXPIRED: u64 = 2;
+}
+
+// Sell token bought in an auction with vesting, released by Claim
+// PDA of the Auction and buyer, holds the tokens in its mint_sell ATA.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Vesting {
+    // Seed of the PDA, the auction may be closed before the last claim
+    pub auction: Pubkey,
+    pub buyer: Pubkey,
+    pub mint_sell: Pubkey,
+    // Sell token bought and sell token already claimed
+    pub total: u64,
+    pub claimed: u64,
+    // Copied from the auction, start is its end_time
+    pub start: u64,
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+impl Vesting {
+    pub const SEED_PREFIX: &'static [u8; 7] = b"vesting";
+    // sha256("account:Vesting")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [100, 149, 66, 138, 95, 200, 128, 241];
+    // 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8
+    pub const SPACE: u64 = 144;
+
+    // Part of total released at time now, rounded down
+    pub fn vested(&self, now: u64) -> u64 {
+        let elapsed = now.saturating_sub(self.start);
+        if elapsed < self.cliff {
+            return 0;
+        }
+        if elapsed >= self.duration {
+            return self.total;
+        }
+        (self.total as u128 * elapsed as u128 / self.duration as u128) as u64
+    }
+}
+
+// Bids of a batch Dutch auction, PDA of the Auction
+// Bids are escrowed in the auction's mint_buy ATA. Clear sets the uniform
+// price once the bids in the book cover the supply at the current price, or
+// at end_time. Bidders collect with BatchClaim.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Batch {
+    pub auction: Pubkey,
+    pub num_bids: u64,
+    // BatchBids closed by BatchClaim
+    pub num_claimed: u64,
+    // 1 after Clear
+    pub cleared: u64,
+    // Set by Clear
+    pub clearing_price: u64,
+    // Sell token for sale when cleared, shared by the book fills
+    pub supply: u64,
+    // Bids that win part of the supply
+    pub book: Book,
+}
+
+impl Batch {
+    pub const SEED_PREFIX: &'static [u8; 5] = b"batch";
+    // sha256("account:Batch")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [156, 194, 70, 44, 22, 88, 137, 44];
+    // 8 + 32 + 5 * 8 + (8 + 8 * (32 + 8 + 8))
+    pub const SPACE: u64 = 472;
+}
+
+// Bid of a bidder in a batch auction, PDA of the Auction and bidder
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct BatchBid {
+    pub bidder: Pubkey,
+    // Max price of 1 whole sell token
+    pub price: u64,
+    // Amount of sell token
+    pub amount: u64,
+    // Buy token escrowed, the payment at price
+    pub escrowed: u64,
+}
+
+impl BatchBid {
+    pub const SEED_PREFIX: &'static [u8; 9] = b"batch_bid";
+    // sha256("account:BatchBid")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [37, 247, 91, 112, 79, 109, 56, 91];
+    // 8 + 32 + 8 + 8 + 8
+    pub const SPACE: u64 = 64;
+}
+
+// Sell token bought by a buyer in an auction with a wallet_cap
+// PDA of the Auction and buyer
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Purchase {
+    pub bought: u64,
+}
+
+impl Purchase {
+    pub const SEED_PREFIX: &'static [u8; 8] = b"purchase";
+    // sha256("account:Purchase")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [33, 203, 1, 252, 231, 228, 8, 67];
+    // 8 + 8
+    pub const SPACE: u64 = 16;
+}
+
+// English (ascending bid) auction of a lot of sell_amt sell token
+// Bids are amounts of buy token for the whole lot.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct EnglishAuction {
+    pub seller: Pubkey,
+    pub mint_sell: Pubkey,
+    pub mint_buy: Pubkey,
+    // Highest bidder, default until the first bid
+    pub bidder: Pubkey,
+    // Id handed out by the seller's Counter
+    pub id: u64,
+    pub sell_amt: u64,
+    // Minimum first bid
+    pub reserve: u64,
+    // Minimum raise over the highest bid
+    pub min_increment: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    // Bids less than extension seconds before end_time move end_time to
+    // now + extension
+    pub extension: u64,
+    // Highest bid, escrowed in the PDA's mint_buy ATA. 0 if no bids.
+    pub bid: u64,
 }
+
+impl EnglishAuction {
+    pub const SEED_PREFIX: &'static [u8; 7] = b"english";
+    // sha256("account:EnglishAuction")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [184, 165, 14, 85, 78, 187, 79, 157];
+    // 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8
+    pub const SPACE: u64 = 200;
+}
+
+// Sealed bid auction of sell_amt sell token
+// Bidders commit to a hash of their bid, reveal it after commit_end and
+// winners pay the uniform clearing price.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct SealedAuction {
+    pub seller: Pubkey,
+    pub mint_sell: Pubkey,
+    pub mint_buy: Pubkey,
+    // Id handed out by the seller's Counter
+    pub id: u64,
+    pub sell_amt: u64,
+    // Minimum price of 1 whole sell token
+    pub reserve: u64,
+    // Buy token escrowed with each commitment, lost if not revealed
+    pub deposit: u64,
+    // Commits before commit_end, reveals from commit_end to reveal_end
+    pub commit_end: u64,
+    pub reveal_end: u64,
+    pub num_commits: u64,
+    pub num_revealed: u64,
+    // Revealed commitments closed by SealedClaim
+    pub num_claimed: u64,
+    // 1 after SealedSettle
+    pub settled: u64,
+    // Set by SealedSettle
+    pub clearing_price: u64,
+    // Revealed bids that win part of sell_amt
+    pub book: Book,
+}
+
+impl SealedAuction {
+    pub const SEED_PREFIX: &'static [u8; 6] = b"sealed";
+    // sha256("account:SealedAuction")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [192, 62, 29, 122, 164, 55, 40, 41];
+    // 8 + 3 * 32 + 11 * 8 + (8 + 8 * (32 + 8 + 8))
+    pub const SPACE: u64 = 584;
+}
+
+// Sealed bid of a bidder, PDA of the SealedAuction and bidder
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Commitment {
+    pub bidder: Pubkey,
+    // Commitment::hash(price, amount, salt)
+    pub hash: [u8; 32],
+    // Copied from the auction so that unrevealed commitments can be closed
+    pub reveal_end: u64,
+    // Buy token escrowed, the deposit and after reveal the full payment
+    pub escrowed: u64,
+    // Set by Reveal
+    pub price: u64,
+    pub amount: u64,
+    pub revealed: u64,
+}
+
+impl Commitment {
+    pub const SEED_PREFIX: &'static [u8; 10] = b"commitment";
+    // sha256("account:Commitment")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [61, 112, 129, 128, 24, 147, 77, 87];
+    // 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8
+    pub const SPACE: u64 = 112;
+
+    // Hash committed by bidders, salt keeps bids from being guessed
+    pub fn hash(price: u64, amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+        hashv(&[&price.to_le_bytes(), &amount.to_le_bytes(), salt]).to_bytes()
+    }
+}
+
+// Reverse (procurement) Dutch auction, the mirror image of Auction
+// The buyer escrows the payment at end_price for buy_amt sell token. The
+// price of 1 sell token rises linearly from start_price to end_price and
+// sellers accept it by delivering sell token.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct ReverseAuction {
+    pub buyer: Pubkey,
+    pub mint_sell: Pubkey,
+    pub mint_buy: Pubkey,
+    // Id handed out by the buyer's Counter
+    pub id: u64,
+    pub start_price: u64,
+    pub end_price: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    // Amount of sell token wanted
+    pub buy_amt: u64,
+    // Amount of sell token still wanted
+    pub remaining: u64,
+}
+
+impl ReverseAuction {
+    pub const SEED_PREFIX: &'static [u8; 7] = b"reverse";
+    // sha256("account:ReverseAuction")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [172, 200, 118, 248, 71, 37, 252, 248];
+    // 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8
+    pub const SPACE: u64 = 160;
+}
+
+// One per seller, hands out auction ids
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Counter {
+    // Id of the next auction created by the seller
+    pub next_id: u64,
+}
+
+impl Counter {
+    pub const SEED_PREFIX: &'static [u8; 7] = b"counter";
+    // sha256("account:Counter")[..8], same as Anchor
+    pub const DISCRIMINATOR: [u8; 8] = [255, 176, 4, 245, 188, 253, 124, 25];
+    // 8 + 8
+    pub const SPACE: u64 = 16;
+}
+
+// Protocol fee taken from every Buy, a single PDA for the program
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Pod, Zeroable)]
+pub struct Config {
+    // Can change fee_bps
+    pub admin: Pubkey,
+    // Receives the fee in its mint_buy ATA
+    pub treasury: Pubkey,
+    // Fee in basis points of the payment, at most MAX_FEE_BPS
+    pub fee_bps: u64,
+    // Program that must own the oracle accounts of oracle pegged auctions
+    pub oracle_program: Pubkey,
+}
+
+impl Config {
+    pub const SEED_PREFIX: &'static [u8; 6] = b"config";
+    // sha256("account:Config")[..8], same as Anchor
+    pub const DISCRIMINATOR: [u8; 8] = [155, 12, 170, 224, 30, 250, 204, 130];
+    // 8 + 32 + 32 + 8 + 32
+    pub const SPACE: u64 = 112;
+    // Hard cap on fee_bps, 10%
+    pub const MAX_FEE_BPS: u64 = 1_000;
+
+    // Part of amt sent to the treasury, rounded down
+    pub fn fee(&self, amt: u64) -> u64 {
+        (amt as u128 * self.fee_bps as u128 / 10_000) as u64
+    }
+}
+
+// init, load, load_mut and unpack for a zero copy account
+macro_rules! zero_copy {
+    ($t:ty) => {
+        impl $t {
+            // Write discriminator to uninitialized account data
+            pub fn init(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+                if data.len() != Self::SPACE as usize {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                if data[..8] != [0; 8] {
+                    return Err(ProgramError::AccountAlreadyInitialized);
+                }
+                data[..8].copy_from_slice(&Self::DISCRIMINATOR);
+                Self::load_mut(data)
+            }
+
+            pub fn load(data: &[u8]) -> Result<&Self, ProgramError> {
+                if data.len() != Self::SPACE as usize
+                    || data[..8] != Self::DISCRIMINATOR
+                {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                bytemuck::try_from_bytes(&data[8..])
+                    .map_err(|_| ProgramError::InvalidAccountData)
+            }
+
+            pub fn load_mut(
+                data: &mut [u8],
+            ) -> Result<&mut Self, ProgramError> {
+                if data.len() != Self::SPACE as usize
+                    || data[..8] != Self::DISCRIMINATOR
+                {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                bytemuck::try_from_bytes_mut(&mut data[8..])
+                    .map_err(|_| ProgramError::InvalidAccountData)
+            }
+
+            // Copy out of account data that may not be aligned
+            // (clients and tests)
+            pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+                if data.len() != Self::SPACE as usize
+                    || data[..8] != Self::DISCRIMINATOR
+                {
+                    return Err(ProgramError::InvalidAccountData);
+                }
+                bytemuck::try_pod_read_unaligned(&data[8..])
+                    .map_err(|_| ProgramError::InvalidAccountData)
+            }
+        }
+
+        const _: () =
+            assert!(<$t>::SPACE as usize == 8 + std::mem::size_of::<$t>());
+    };
+}
+
+zero_copy!(Auction);
+zero_copy!(AuctionResult);
+zero_copy!(Purchase);
+zero_copy!(Vesting);
+zero_copy!(Batch);
+zero_copy!(BatchBid);
+zero_copy!(EnglishAuction);
+zero_copy!(SealedAuction);
+zero_copy!(Commitment);
+zero_copy!(ReverseAuction);
+zero_copy!(Counter);
+zero_copy!(Config);
@@ -1,34 +1,546 @@
-use litesvm::LiteSVM;
-use litesvm_token::{
-    CreateAssociatedTokenAccount, CreateMint, MintTo, get_spl_account,
-    spl_token::state::Account as TokenAccount,
-};
+use std::borrow::Borrow;
+
+use borsh::BorshDeserialize;
+use litesvm::{LiteSVM, types::FailedTransactionMetadata};
+use litesvm_token::{CreateAssociatedTokenAccount, CreateMint, MintTo};
 use solana_address::Address;
 use solana_sdk::{
+    account::Account,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use spl_associated_token_account_interface::{
+    address::get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account,
+};
+use spl_token_2022_interface::{
+    extension::{
+        ExtensionType, StateWithExtensions, metadata_pointer, transfer_fee,
+        transfer_hook,
+    },
+    state::{Account as TokenAccount, Mint},
 };
-use spl_associated_token_account_interface::address::get_associated_token_address;
+use spl_token_metadata_interface::state::TokenMetadata;
 
-use auction::{Cmd, state::Auction};
+use auction::{
+    Cmd, InitParams,
+    error::AuctionError,
+    merkle,
+    price::Quote,
+    state::{
+        Auction, AuctionResult, Batch, BatchBid, Commitment, Config, Counter,
+        EnglishAuction, Purchase, ReverseAuction, SealedAuction, Vesting,
+    },
+};
+
+pub const DECIMALS: u8 = 6;
+// Receives the protocol fee
+pub const TREASURY: Pubkey = Pubkey::new_from_array([7; 32]);
+// Owner of the oracle accounts, set in the Config
+pub const ORACLE_PROGRAM: Pubkey = Pubkey::new_from_array([8; 32]);
 
-pub fn create_mint(svm: &mut LiteSVM, payer: &Keypair) -> Pubkey {
+pub fn create_mint(svm: &mut LiteSVM, payer: &Keypair, decimals: u8) -> Pubkey {
     CreateMint::new(svm, payer)
         .authority(&payer.pubkey())
-        .decimals(1e6 as u8)
+        .decimals(decimals)
         .send()
         .unwrap()
 }
 
 pub fn get_ata(mint: &Pubkey, owner: &Pubkey) -> Pubkey {
-    let ata_addr = get_associated_token_address(
+    get_ata_with_program(mint, owner, &token_program())
+}
+
+pub fn get_ata_with_program(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    let ata_addr = get_associated_token_address_with_program_id(
         &Address::from(owner.to_bytes()),
         &Address::from(mint.to_bytes()),
+        &Address::from(token_program.to_bytes()),
     );
     Pubkey::from(ata_addr.to_bytes())
 }
 
+pub fn token_program() -> Pubkey {
+    Pubkey::from(spl_token_interface::ID.to_bytes())
+}
+
+pub fn token_2022_program() -> Pubkey {
+    Pubkey::from(spl_token_2022_interface::ID.to_bytes())
+}
+
+// Instruction builders pass the Token program and its ATAs, swap them for
+// token_program and its ATAs
+pub fn with_token_program(
+    mut ix: Instruction,
+    token_program: Pubkey,
+) -> Instruction {
+    // The mint and owner of every ATA are passed to the instruction too
+    let keys: Vec<Pubkey> = ix.accounts.iter().map(|acc| acc.pubkey).collect();
+    let mut atas = vec![];
+    for mint in keys.iter() {
+        for owner in keys.iter() {
+            atas.push((get_ata(mint, owner), *mint, *owner));
+        }
+    }
+
+    for account in ix.accounts.iter_mut() {
+        if account.pubkey == self::token_program() {
+            account.pubkey = token_program;
+        } else if let Some((_, mint, owner)) =
+            atas.iter().find(|(ata, _, _)| *ata == account.pubkey)
+        {
+            account.pubkey = get_ata_with_program(mint, owner, &token_program);
+        }
+    }
+    ix
+}
+
+// Instructions that move both mints take token_program_sell then
+// token_program_buy, swap them and the ATAs of each mint for its program
+pub fn with_token_programs(
+    mut ix: Instruction,
+    mint_sell: Pubkey,
+    token_program_sell: Pubkey,
+    mint_buy: Pubkey,
+    token_program_buy: Pubkey,
+) -> Instruction {
+    let owners: Vec<Pubkey> =
+        ix.accounts.iter().map(|acc| acc.pubkey).collect();
+    let mut programs = [token_program_sell, token_program_buy].into_iter();
+
+    for account in ix.accounts.iter_mut() {
+        if account.pubkey == self::token_program() {
+            if let Some(program) = programs.next() {
+                account.pubkey = program;
+            }
+            continue;
+        }
+        for (mint, program) in [
+            (mint_sell, token_program_sell),
+            (mint_buy, token_program_buy),
+        ] {
+            if let Some(owner) = owners
+                .iter()
+                .find(|owner| get_ata(&mint, owner) == account.pubkey)
+            {
+                account.pubkey = get_ata_with_program(&mint, owner, &program);
+            }
+        }
+    }
+    ix
+}
+
+// Convert an instruction of the SPL interface crates
+fn from_spl_ix(spl_ix: solana_instruction::Instruction) -> Instruction {
+    Instruction {
+        program_id: Pubkey::from(spl_ix.program_id.to_bytes()),
+        accounts: spl_ix
+            .accounts
+            .iter()
+            .map(|acc| AccountMeta {
+                pubkey: Pubkey::from(acc.pubkey.to_bytes()),
+                is_signer: acc.is_signer,
+                is_writable: acc.is_writable,
+            })
+            .collect(),
+        data: spl_ix.data,
+    }
+}
+
+pub fn create_ata_with_program(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    owner: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    let ix = create_associated_token_account(
+        &Address::from(payer.pubkey().to_bytes()),
+        &Address::from(owner.to_bytes()),
+        &Address::from(mint.to_bytes()),
+        &Address::from(token_program.to_bytes()),
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[from_spl_ix(ix)],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+    get_ata_with_program(mint, owner, token_program)
+}
+
+// Token-2022 NFT minted to owner. Its MetadataPointer points to the mint,
+// which stores the TokenMetadata.
+pub fn create_nft(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    owner: &Pubkey,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let token_program = Address::from(token_2022_program().to_bytes());
+    let mint_addr = Address::from(mint.pubkey().to_bytes());
+    let payer_addr = Address::from(payer.pubkey().to_bytes());
+
+    let metadata = TokenMetadata {
+        name: "Auction NFT".to_string(),
+        symbol: "ANFT".to_string(),
+        uri: "https://example.com/nft.json".to_string(),
+        ..TokenMetadata::default()
+    };
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::MetadataPointer,
+    ])
+    .unwrap();
+    // Token-2022 reallocs the mint for the metadata, fund it upfront
+    let lamports = svm.minimum_balance_for_rent_exemption(
+        space + metadata.tlv_size_of().unwrap(),
+    );
+
+    let ixs = [
+        solana_sdk::system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            lamports,
+            space as u64,
+            &token_2022_program(),
+        ),
+        from_spl_ix(
+            metadata_pointer::instruction::initialize(
+                &token_program,
+                &mint_addr,
+                Some(payer_addr),
+                Some(mint_addr),
+            )
+            .unwrap(),
+        ),
+        from_spl_ix(
+            spl_token_2022_interface::instruction::initialize_mint2(
+                &token_program,
+                &mint_addr,
+                &payer_addr,
+                None,
+                0,
+            )
+            .unwrap(),
+        ),
+        from_spl_ix(spl_token_metadata_interface::instruction::initialize(
+            &token_program,
+            &mint_addr,
+            &payer_addr,
+            &mint_addr,
+            &payer_addr,
+            metadata.name,
+            metadata.symbol,
+            metadata.uri,
+        )),
+    ];
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&payer.pubkey()),
+        &[payer, &mint],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    let ata = create_ata_with_program(
+        svm,
+        payer,
+        owner,
+        &mint.pubkey(),
+        &token_2022_program(),
+    );
+    let ix = spl_token_2022_interface::instruction::mint_to(
+        &token_program,
+        &mint_addr,
+        &Address::from(ata.to_bytes()),
+        &payer_addr,
+        &[],
+        1,
+    )
+    .unwrap();
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[from_spl_ix(ix)],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    mint.pubkey()
+}
+
+// Token-2022 mint with the transfer hook of the hook crate, deployed at
+// hook_program. Creates the validation and counter PDAs of the hook and
+// mints amount to owner.
+pub fn create_hook_mint(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    owner: &Pubkey,
+    hook_program: &Pubkey,
+    amount: u64,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let token_program = Address::from(token_2022_program().to_bytes());
+    let mint_addr = Address::from(mint.pubkey().to_bytes());
+    let payer_addr = Address::from(payer.pubkey().to_bytes());
+
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::TransferHook,
+    ])
+    .unwrap();
+
+    let ixs = [
+        solana_sdk::system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            svm.minimum_balance_for_rent_exemption(space),
+            space as u64,
+            &token_2022_program(),
+        ),
+        from_spl_ix(
+            transfer_hook::instruction::initialize(
+                &token_program,
+                &mint_addr,
+                Some(payer_addr),
+                Some(Address::from(hook_program.to_bytes())),
+            )
+            .unwrap(),
+        ),
+        from_spl_ix(
+            spl_token_2022_interface::instruction::initialize_mint2(
+                &token_program,
+                &mint_addr,
+                &payer_addr,
+                None,
+                DECIMALS,
+            )
+            .unwrap(),
+        ),
+        Instruction::new_with_bytes(
+            *hook_program,
+            &hook::INIT_EXTRA_ACCOUNT_METAS,
+            vec![
+                AccountMeta {
+                    pubkey: hook::get_validation_pda(
+                        hook_program,
+                        &mint.pubkey(),
+                    )
+                    .0,
+                    is_signer: false,
+                    is_writable: true,
+                },
+                AccountMeta {
+                    pubkey: mint.pubkey(),
+                    is_signer: false,
+                    is_writable: false,
+                },
+                AccountMeta {
+                    pubkey: payer.pubkey(),
+                    is_signer: true,
+                    is_writable: true,
+                },
+                AccountMeta {
+                    pubkey: solana_sdk::system_program::id(),
+                    is_signer: false,
+                    is_writable: false,
+                },
+                AccountMeta {
+                    pubkey: hook::get_counter_pda(hook_program, &mint.pubkey())
+                        .0,
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ],
+        ),
+    ];
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&payer.pubkey()),
+        &[payer, &mint],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    let ata = create_ata_with_program(
+        svm,
+        payer,
+        owner,
+        &mint.pubkey(),
+        &token_2022_program(),
+    );
+    let ix = spl_token_2022_interface::instruction::mint_to(
+        &token_program,
+        &mint_addr,
+        &Address::from(ata.to_bytes()),
+        &payer_addr,
+        &[],
+        amount,
+    )
+    .unwrap();
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[from_spl_ix(ix)],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    mint.pubkey()
+}
+
+// Accounts of the transfer hook of a mint created by create_hook_mint, passed
+// as the remaining accounts of auction instructions
+pub fn get_hook_accounts(
+    hook_program: &Pubkey,
+    mint: &Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta {
+            pubkey: *hook_program,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: hook::get_validation_pda(hook_program, mint).0,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: hook::get_counter_pda(hook_program, mint).0,
+            is_signer: false,
+            is_writable: true,
+        },
+    ]
+}
+
+// Number of transfers of mint counted by the transfer hook
+pub fn get_hook_count(
+    svm: &LiteSVM,
+    hook_program: &Pubkey,
+    mint: &Pubkey,
+) -> u64 {
+    let (counter_pda, _) = hook::get_counter_pda(hook_program, mint);
+    let data = svm.get_account(&counter_pda).unwrap().data;
+    u64::from_le_bytes(data[..8].try_into().unwrap())
+}
+
+// Remove the mint authority of mint, payer must be the authority
+pub fn revoke_mint_authority(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    mint: &Pubkey,
+) {
+    let payer_addr = Address::from(payer.pubkey().to_bytes());
+    let ix = spl_token_2022_interface::instruction::set_authority(
+        &Address::from(token_2022_program().to_bytes()),
+        &Address::from(mint.to_bytes()),
+        None,
+        spl_token_2022_interface::instruction::AuthorityType::MintTokens,
+        &payer_addr,
+        &[],
+    )
+    .unwrap();
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[from_spl_ix(ix)],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+}
+
+// Token-2022 mint with a transfer fee of fee_bps, capped at max_fee.
+// Mints amount to owner.
+pub fn create_fee_mint(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    owner: &Pubkey,
+    fee_bps: u16,
+    max_fee: u64,
+    amount: u64,
+) -> Pubkey {
+    let mint = Keypair::new();
+    let token_program = Address::from(token_2022_program().to_bytes());
+    let mint_addr = Address::from(mint.pubkey().to_bytes());
+    let payer_addr = Address::from(payer.pubkey().to_bytes());
+
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::TransferFeeConfig,
+    ])
+    .unwrap();
+
+    let ixs = [
+        solana_sdk::system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            svm.minimum_balance_for_rent_exemption(space),
+            space as u64,
+            &token_2022_program(),
+        ),
+        from_spl_ix(
+            transfer_fee::instruction::initialize_transfer_fee_config(
+                &token_program,
+                &mint_addr,
+                Some(&payer_addr),
+                Some(&payer_addr),
+                fee_bps,
+                max_fee,
+            )
+            .unwrap(),
+        ),
+        from_spl_ix(
+            spl_token_2022_interface::instruction::initialize_mint2(
+                &token_program,
+                &mint_addr,
+                &payer_addr,
+                None,
+                DECIMALS,
+            )
+            .unwrap(),
+        ),
+    ];
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &ixs,
+        Some(&payer.pubkey()),
+        &[payer, &mint],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    let ata = create_ata_with_program(
+        svm,
+        payer,
+        owner,
+        &mint.pubkey(),
+        &token_2022_program(),
+    );
+    let ix = spl_token_2022_interface::instruction::mint_to(
+        &token_program,
+        &mint_addr,
+        &Address::from(ata.to_bytes()),
+        &payer_addr,
+        &[],
+        amount,
+    )
+    .unwrap();
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[from_spl_ix(ix)],
+        Some(&payer.pubkey()),
+        &[payer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    mint.pubkey()
+}
+
 pub fn create_ata(
     svm: &mut LiteSVM,
     payer: &Keypair,
@@ -55,33 +567,143 @@ pub fn mint_to(
 }
 
 pub fn get_token_balance(svm: &LiteSVM, account: &Pubkey) -> u64 {
-    let token_account: TokenAccount = get_spl_account(svm, account).unwrap();
-    token_account.amount
+    // Token-2022 accounts may have extensions after the base state
+    let data = svm.get_account(account).unwrap().data;
+    StateWithExtensions::<TokenAccount>::unpack(&data)
+        .unwrap()
+        .base
+        .amount
+}
+
+pub fn get_auction_pda(
+    program_id: &Pubkey,
+    seller: &Pubkey,
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            Auction::SEED_PREFIX,
+            seller.as_ref(),
+            mint_sell.as_ref(),
+            mint_buy.as_ref(),
+            &id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn get_purchase_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+    buyer: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Purchase::SEED_PREFIX, auction_pda.as_ref(), buyer.as_ref()],
+        program_id,
+    )
+}
+
+pub fn get_vesting_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+    buyer: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Vesting::SEED_PREFIX, auction_pda.as_ref(), buyer.as_ref()],
+        program_id,
+    )
+}
+
+pub fn get_config_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[Config::SEED_PREFIX], program_id)
+}
+
+// ProgramData account of program_id, holds its upgrade authority
+pub fn get_program_data(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref()],
+        &solana_program::bpf_loader_upgradeable::ID,
+    )
+    .0
+}
+
+// LiteSVM loads programs without a ProgramData account, set one with
+// authority as the upgrade authority
+pub fn set_upgrade_authority(
+    svm: &mut LiteSVM,
+    program_id: &Pubkey,
+    authority: &Pubkey,
+) {
+    // Tag 3, slot 0, Some(authority)
+    let mut data = vec![3, 0, 0, 0];
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.push(1);
+    data.extend_from_slice(authority.as_ref());
+
+    svm.set_account(
+        get_program_data(program_id),
+        Account {
+            lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+            data,
+            owner: solana_program::bpf_loader_upgradeable::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .unwrap();
+}
+
+pub fn get_result_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[AuctionResult::SEED_PREFIX, auction_pda.as_ref()],
+        program_id,
+    )
+}
+
+pub fn get_batch_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[Batch::SEED_PREFIX, auction_pda.as_ref()],
+        program_id,
+    )
+}
+
+pub fn get_batch_bid_pda(
+    program_id: &Pubkey,
+    auction_pda: &Pubkey,
+    bidder: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[BatchBid::SEED_PREFIX, auction_pda.as_ref(), bidder.as_ref()],
+        program_id,
+    )
 }
 
 pub fn create_init_ix(
     program_id: Pubkey,
-    start_price: u64,
-    end_price: u64,
-    start_time: u64,
-    end_time: u64,
-    sell_amt: u64,
-    bump: u8,
+    // batch_bump is derived here
+    params: InitParams,
     seller: Pubkey,
     mint_sell: Pubkey,
     mint_buy: Pubkey,
+    counter_pda: Pubkey,
     auction_pda: Pubkey,
     auction_sell_ata: Pubkey,
     seller_sell_ata: Pubkey,
 ) -> Instruction {
-    let cmd = Cmd::Init {
-        start_price,
-        end_price,
-        start_time,
-        end_time,
-        sell_amt,
-        bump,
-    };
+    let (batch_pda, batch_bump) = get_batch_pda(&program_id, &auction_pda);
+
+    let cmd = Cmd::Init(InitParams {
+        batch_bump,
+        ..params
+    });
 
     Instruction::new_with_borsh(
         program_id,
@@ -102,6 +724,11 @@ pub fn create_init_ix(
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: counter_pda,
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: auction_pda,
                 is_signer: false,
@@ -122,6 +749,11 @@ pub fn create_init_ix(
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: Pubkey::from(
                     spl_associated_token_account_interface::program::ID
@@ -140,14 +772,35 @@ pub fn create_init_ix(
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: batch_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_config_pda(&program_id).0,
+                is_signer: false,
+                is_writable: false,
+            },
         ],
     )
 }
 
 pub fn create_buy_ix(
     program_id: Pubkey,
+    id: u64,
     max_price: u64,
+    buy_amt: u64,
     bump: u8,
+    // Merkle proof of the buyer, empty if anyone can buy
+    proof: Vec<[u8; 32]>,
+    // Oracle of the auction, any account if it has none
+    oracle: Pubkey,
     buyer: Pubkey,
     seller: Pubkey,
     mint_sell: Pubkey,
@@ -158,7 +811,24 @@ pub fn create_buy_ix(
     buyer_buy_ata: Pubkey,
     seller_buy_ata: Pubkey,
 ) -> Instruction {
-    let cmd = Cmd::Buy { max_price, bump };
+    let (purchase_pda, purchase_bump) =
+        get_purchase_pda(&program_id, &auction_pda, &buyer);
+    let (config_pda, config_bump) = get_config_pda(&program_id);
+    let (result_pda, result_bump) = get_result_pda(&program_id, &auction_pda);
+    let (vesting_pda, vesting_bump) =
+        get_vesting_pda(&program_id, &auction_pda, &buyer);
+
+    let cmd = Cmd::Buy {
+        id,
+        max_price,
+        buy_amt,
+        bump,
+        purchase_bump,
+        config_bump,
+        result_bump,
+        vesting_bump,
+        proof,
+    };
 
     Instruction::new_with_borsh(
         program_id,
@@ -214,17 +884,76 @@ pub fn create_buy_ix(
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
             AccountMeta {
                 pubkey: solana_sdk::system_program::id(),
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: purchase_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: TREASURY,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &TREASURY),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: oracle,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: result_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &seller),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: vesting_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &vesting_pda),
+                is_signer: false,
+                is_writable: true,
+            },
         ],
     )
 }
 
 pub fn create_cancel_ix(
     program_id: Pubkey,
+    id: u64,
     bump: u8,
     seller: Pubkey,
     mint_sell: Pubkey,
@@ -233,7 +962,12 @@ pub fn create_cancel_ix(
     auction_sell_ata: Pubkey,
     seller_sell_ata: Pubkey,
 ) -> Instruction {
-    let cmd = Cmd::Cancel { bump };
+    let (result_pda, result_bump) = get_result_pda(&program_id, &auction_pda);
+    let cmd = Cmd::Cancel {
+        id,
+        bump,
+        result_bump,
+    };
 
     Instruction::new_with_borsh(
         program_id,
@@ -279,55 +1013,1914 @@ pub fn create_cancel_ix(
                 is_signer: false,
                 is_writable: true,
             },
+            AccountMeta {
+                pubkey: result_pda,
+                is_signer: false,
+                is_writable: true,
+            },
         ],
     )
 }
 
-pub struct Test {
-    pub program_id: Pubkey,
-    pub payer: Keypair,
-    pub seller: Keypair,
-    pub buyer: Keypair,
-    pub mint_sell: Pubkey,
-    pub mint_buy: Pubkey,
-    pub seller_sell_ata: Pubkey,
-    pub seller_buy_ata: Pubkey,
-    pub buyer_sell_ata: Pubkey,
-    pub buyer_buy_ata: Pubkey,
-    pub auction_pda: Pubkey,
-    pub auction_bump: u8,
-    pub auction_sell_ata: Pubkey,
-}
-
-pub fn setup(svm: &mut LiteSVM) -> Test {
-    let payer = Keypair::new();
-    let seller = Keypair::new();
-    let buyer = Keypair::new();
-    let program_keypair = Keypair::new();
-    let program_id = program_keypair.pubkey();
-    svm.add_program_from_file(program_id, "target/deploy/auction.so")
-        .unwrap();
-
-    // Airdrop
-    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
-    svm.airdrop(&seller.pubkey(), 1_000_000_000).unwrap();
-    svm.airdrop(&buyer.pubkey(), 1_000_000_000).unwrap();
-
-    // Mints
-    let mint_sell = create_mint(svm, &payer);
-    let mint_buy = create_mint(svm, &payer);
-
-    // Auction PDA
-    let (auction_pda, auction_bump) = Pubkey::find_program_address(
+pub fn get_english_pda(
+    program_id: &Pubkey,
+    seller: &Pubkey,
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
         &[
-            Auction::SEED_PREFIX,
-            seller.pubkey().as_ref(),
+            EnglishAuction::SEED_PREFIX,
+            seller.as_ref(),
             mint_sell.as_ref(),
             mint_buy.as_ref(),
+            &id.to_le_bytes(),
         ],
+        program_id,
+    )
+}
+
+pub fn create_english_init_ix(
+    program_id: Pubkey,
+    id: u64,
+    reserve: u64,
+    min_increment: u64,
+    start_time: u64,
+    end_time: u64,
+    extension: u64,
+    sell_amt: u64,
+    bump: u8,
+    counter_bump: u8,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    counter_pda: Pubkey,
+    english_pda: Pubkey,
+    english_sell_ata: Pubkey,
+    english_buy_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::EnglishInit {
+        id,
+        reserve,
+        min_increment,
+        start_time,
+        end_time,
+        extension,
+        sell_amt,
+        bump,
+        counter_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: seller,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: counter_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: english_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: english_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: english_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_bid_ix(
+    program_id: Pubkey,
+    id: u64,
+    amount: u64,
+    bump: u8,
+    bidder: Pubkey,
+    prev_bidder: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    english_pda: Pubkey,
+    english_buy_ata: Pubkey,
+    bidder_buy_ata: Pubkey,
+    prev_bidder_buy_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::Bid { id, amount, bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: prev_bidder,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: english_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: english_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: prev_bidder_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_settle_ix(
+    program_id: Pubkey,
+    id: u64,
+    bump: u8,
+    payer: Pubkey,
+    seller: Pubkey,
+    bidder: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    english_pda: Pubkey,
+    english_sell_ata: Pubkey,
+    english_buy_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+    seller_buy_ata: Pubkey,
+    bidder_sell_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::Settle { id, bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: english_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: english_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: english_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn get_sealed_pda(
+    program_id: &Pubkey,
+    seller: &Pubkey,
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            SealedAuction::SEED_PREFIX,
+            seller.as_ref(),
+            mint_sell.as_ref(),
+            mint_buy.as_ref(),
+            &id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn get_commitment_pda(
+    program_id: &Pubkey,
+    sealed_pda: &Pubkey,
+    bidder: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            Commitment::SEED_PREFIX,
+            sealed_pda.as_ref(),
+            bidder.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_sealed_init_ix(
+    program_id: Pubkey,
+    id: u64,
+    reserve: u64,
+    deposit: u64,
+    commit_end: u64,
+    reveal_end: u64,
+    sell_amt: u64,
+    bump: u8,
+    counter_bump: u8,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    counter_pda: Pubkey,
+    sealed_pda: Pubkey,
+    sealed_sell_ata: Pubkey,
+    sealed_buy_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::SealedInit {
+        id,
+        reserve,
+        deposit,
+        commit_end,
+        reveal_end,
+        sell_amt,
+        bump,
+        counter_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: seller,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: counter_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_commit_ix(
+    program_id: Pubkey,
+    id: u64,
+    hash: [u8; 32],
+    bump: u8,
+    commitment_bump: u8,
+    bidder: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    sealed_pda: Pubkey,
+    sealed_buy_ata: Pubkey,
+    bidder_buy_ata: Pubkey,
+    commitment_pda: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::Commit {
+        id,
+        hash,
+        bump,
+        commitment_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: commitment_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_reveal_ix(
+    program_id: Pubkey,
+    id: u64,
+    price: u64,
+    amount: u64,
+    salt: [u8; 32],
+    bump: u8,
+    commitment_bump: u8,
+    bidder: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    sealed_pda: Pubkey,
+    sealed_buy_ata: Pubkey,
+    bidder_buy_ata: Pubkey,
+    commitment_pda: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::Reveal {
+        id,
+        price,
+        amount,
+        salt,
+        bump,
+        commitment_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: commitment_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_sealed_settle_ix(
+    program_id: Pubkey,
+    id: u64,
+    bump: u8,
+    payer: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    sealed_pda: Pubkey,
+    sealed_sell_ata: Pubkey,
+    sealed_buy_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+    seller_buy_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::SealedSettle { id, bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_sealed_claim_ix(
+    program_id: Pubkey,
+    id: u64,
+    bump: u8,
+    commitment_bump: u8,
+    payer: Pubkey,
+    bidder: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    sealed_pda: Pubkey,
+    sealed_sell_ata: Pubkey,
+    sealed_buy_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+    seller_buy_ata: Pubkey,
+    bidder_sell_ata: Pubkey,
+    bidder_buy_ata: Pubkey,
+    commitment_pda: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::SealedClaim {
+        id,
+        bump,
+        commitment_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: sealed_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: commitment_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_expire_ix(
+    program_id: Pubkey,
+    id: u64,
+    bump: u8,
+    cranker: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    auction_sell_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+) -> Instruction {
+    let (result_pda, result_bump) = get_result_pda(&program_id, &auction_pda);
+    let cmd = Cmd::Expire {
+        id,
+        bump,
+        result_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: cranker,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: result_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_init_config_ix(
+    program_id: Pubkey,
+    fee_bps: u64,
+    bump: u8,
+    admin: Pubkey,
+    treasury: Pubkey,
+    config_pda: Pubkey,
+    oracle_program: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::InitConfig { fee_bps, bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: admin,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: treasury,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: get_program_data(&program_id),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: oracle_program,
+                is_signer: false,
+                is_writable: false,
+            },
+        ],
+    )
+}
+
+pub fn create_set_fee_ix(
+    program_id: Pubkey,
+    fee_bps: u64,
+    bump: u8,
+    admin: Pubkey,
+    config_pda: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::SetFee { fee_bps, bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: admin,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: config_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+// Levels of a merkle tree, from the leaves up to the root
+pub fn create_update_ix(
+    program_id: Pubkey,
+    id: u64,
+    start_price: u64,
+    end_price: u64,
+    start_time: u64,
+    end_time: u64,
+    sell_amt: u64,
+    bump: u8,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    auction_sell_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::Update {
+        id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+        bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: seller,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: false,
+            },
+        ],
+    )
+}
+
+pub fn create_get_price_ix(
+    program_id: Pubkey,
+    id: u64,
+    bump: u8,
+    // Oracle of the auction, any account if it has none
+    oracle: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::GetPrice { id, bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: oracle,
+                is_signer: false,
+                is_writable: false,
+            },
+        ],
+    )
+}
+
+// AuctionError a transaction failed with, None if it succeeded or failed
+// with another error
+// Takes the error as is or boxed, as returned by the test closures
+pub fn get_error<T, E: Borrow<FailedTransactionMetadata>>(
+    res: Result<T, E>,
+) -> Option<AuctionError> {
+    match res.as_ref().map_err(Borrow::borrow) {
+        Err(FailedTransactionMetadata {
+            err: TransactionError::InstructionError(_, err),
+            ..
+        }) => AuctionError::from_instruction_error(err),
+        _ => None,
+    }
+}
+
+// Simulate GetPrice and decode the Quote from the return data
+pub fn get_price(
+    svm: &LiteSVM,
+    payer: &Keypair,
+    ix: Instruction,
+) -> Result<Quote, Box<FailedTransactionMetadata>> {
+    let res = svm
+        .simulate_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[payer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)?;
+    Ok(Quote::try_from_slice(&res.meta.return_data.data).unwrap())
+}
+
+pub fn create_claim_ix(
+    program_id: Pubkey,
+    bump: u8,
+    buyer: Pubkey,
+    mint_sell: Pubkey,
+    vesting_pda: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::Claim { bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: buyer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: vesting_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &vesting_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &buyer),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: false,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: false,
+            },
+        ],
+    )
+}
+
+pub fn create_batch_bid_ix(
+    program_id: Pubkey,
+    id: u64,
+    price: u64,
+    amount: u64,
+    bump: u8,
+    bidder: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+    bidder_buy_ata: Pubkey,
+) -> Instruction {
+    let (batch_pda, batch_bump) = get_batch_pda(&program_id, &auction_pda);
+    let (bid_pda, bid_bump) =
+        get_batch_bid_pda(&program_id, &auction_pda, &bidder);
+
+    let cmd = Cmd::BatchBid {
+        id,
+        price,
+        amount,
+        bump,
+        batch_bump,
+        bid_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: batch_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bid_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_clear_ix(
+    program_id: Pubkey,
+    id: u64,
+    bump: u8,
+    payer: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+) -> Instruction {
+    let (batch_pda, batch_bump) = get_batch_pda(&program_id, &auction_pda);
+    let (result_pda, result_bump) = get_result_pda(&program_id, &auction_pda);
+
+    let cmd = Cmd::Clear {
+        id,
+        bump,
+        batch_bump,
+        result_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &seller),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &seller),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: batch_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: result_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_batch_claim_ix(
+    program_id: Pubkey,
+    id: u64,
+    bump: u8,
+    payer: Pubkey,
+    bidder: Pubkey,
+    seller: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    auction_pda: Pubkey,
+) -> Instruction {
+    let (batch_pda, batch_bump) = get_batch_pda(&program_id, &auction_pda);
+    let (bid_pda, bid_bump) =
+        get_batch_bid_pda(&program_id, &auction_pda, &bidder);
+    let (result_pda, result_bump) = get_result_pda(&program_id, &auction_pda);
+
+    let cmd = Cmd::BatchClaim {
+        id,
+        bump,
+        batch_bump,
+        bid_bump,
+        result_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: payer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bidder,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: auction_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &auction_pda),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &seller),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &seller),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_sell, &bidder),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: get_ata(&mint_buy, &bidder),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: batch_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: bid_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: result_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn get_reverse_pda(
+    program_id: &Pubkey,
+    buyer: &Pubkey,
+    mint_sell: &Pubkey,
+    mint_buy: &Pubkey,
+    id: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ReverseAuction::SEED_PREFIX,
+            buyer.as_ref(),
+            mint_sell.as_ref(),
+            mint_buy.as_ref(),
+            &id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_reverse_init_ix(
+    program_id: Pubkey,
+    id: u64,
+    start_price: u64,
+    end_price: u64,
+    start_time: u64,
+    end_time: u64,
+    buy_amt: u64,
+    bump: u8,
+    counter_bump: u8,
+    buyer: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    counter_pda: Pubkey,
+    reverse_pda: Pubkey,
+    reverse_buy_ata: Pubkey,
+    buyer_buy_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::ReverseInit {
+        id,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        buy_amt,
+        bump,
+        counter_bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: buyer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: counter_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: reverse_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: reverse_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: buyer_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::sysvar::rent::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_accept_ix(
+    program_id: Pubkey,
+    id: u64,
+    min_price: u64,
+    sell_amt: u64,
+    bump: u8,
+    seller: Pubkey,
+    buyer: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    reverse_pda: Pubkey,
+    reverse_buy_ata: Pubkey,
+    seller_sell_ata: Pubkey,
+    seller_buy_ata: Pubkey,
+    buyer_sell_ata: Pubkey,
+    buyer_buy_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::Accept {
+        id,
+        min_price,
+        sell_amt,
+        bump,
+    };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: seller,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: buyer,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: reverse_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: reverse_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: seller_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: buyer_sell_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: buyer_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(
+                    spl_associated_token_account_interface::program::ID
+                        .to_bytes(),
+                ),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn create_reverse_cancel_ix(
+    program_id: Pubkey,
+    id: u64,
+    bump: u8,
+    buyer: Pubkey,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+    reverse_pda: Pubkey,
+    reverse_buy_ata: Pubkey,
+    buyer_buy_ata: Pubkey,
+) -> Instruction {
+    let cmd = Cmd::ReverseCancel { id, bump };
+
+    Instruction::new_with_borsh(
+        program_id,
+        &cmd,
+        vec![
+            AccountMeta {
+                pubkey: buyer,
+                is_signer: true,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_sell,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: mint_buy,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: reverse_pda,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: reverse_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: buyer_buy_ata,
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: Pubkey::from(spl_token_interface::ID.to_bytes()),
+                is_signer: false,
+                is_writable: true,
+            },
+            AccountMeta {
+                pubkey: solana_sdk::system_program::id(),
+                is_signer: false,
+                is_writable: true,
+            },
+        ],
+    )
+}
+
+pub fn merkle_tree(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves];
+    while levels.last().unwrap().len() > 1 {
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => merkle::node(a, b),
+                // Odd node moves up a level
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+// Siblings of leaf i up to the root
+pub fn merkle_proof(levels: &[Vec<[u8; 32]>], mut i: usize) -> Vec<[u8; 32]> {
+    let mut proof = vec![];
+    for level in &levels[..levels.len() - 1] {
+        if let Some(sibling) = level.get(i ^ 1) {
+            proof.push(*sibling);
+        }
+        i /= 2;
+    }
+    proof
+}
+
+pub struct Test {
+    pub program_id: Pubkey,
+    pub payer: Keypair,
+    pub seller: Keypair,
+    pub buyer: Keypair,
+    pub mint_sell: Pubkey,
+    pub mint_buy: Pubkey,
+    pub seller_sell_ata: Pubkey,
+    pub seller_buy_ata: Pubkey,
+    pub buyer_sell_ata: Pubkey,
+    pub buyer_buy_ata: Pubkey,
+    pub auction_pda: Pubkey,
+    pub auction_bump: u8,
+    pub auction_sell_ata: Pubkey,
+    pub counter_pda: Pubkey,
+    pub counter_bump: u8,
+}
+
+pub fn setup(svm: &mut LiteSVM) -> Test {
+    setup_with_decimals(svm, DECIMALS, DECIMALS)
+}
+
+// Loads the program built at path under a new program id
+pub fn add_program(svm: &mut LiteSVM, path: &str) -> Pubkey {
+    let program_id = Pubkey::new_unique();
+    svm.add_program_from_file(program_id, path).unwrap();
+    program_id
+}
+
+pub fn setup_with_decimals(
+    svm: &mut LiteSVM,
+    decimals_sell: u8,
+    decimals_buy: u8,
+) -> Test {
+    let payer = Keypair::new();
+    let seller = Keypair::new();
+    let buyer = Keypair::new();
+    let program_id = add_program(svm, "target/deploy/auction.so");
+
+    // Airdrop
+    svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+    svm.airdrop(&seller.pubkey(), 1_000_000_000).unwrap();
+    svm.airdrop(&buyer.pubkey(), 1_000_000_000).unwrap();
+
+    // Mints
+    let mint_sell = create_mint(svm, &payer, decimals_sell);
+    let mint_buy = create_mint(svm, &payer, decimals_buy);
+
+    // Counter PDA
+    let (counter_pda, counter_bump) = Pubkey::find_program_address(
+        &[Counter::SEED_PREFIX, seller.pubkey().as_ref()],
         &program_id,
     );
 
+    // PDA of the seller's first auction
+    let (auction_pda, auction_bump) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        0,
+    );
+
     // ATA
     let seller_sell_ata = create_ata(svm, &payer, &seller.pubkey(), &mint_sell);
     let seller_buy_ata = create_ata(svm, &payer, &seller.pubkey(), &mint_buy);
@@ -339,6 +2932,25 @@ pub fn setup(svm: &mut LiteSVM) -> Test {
     mint_to(svm, &payer, &mint_sell, &seller_sell_ata, 1e9 as u64);
     mint_to(svm, &payer, &mint_buy, &buyer_buy_ata, 1e9 as u64);
 
+    // Config without fee, payer is the upgrade authority and the admin
+    set_upgrade_authority(svm, &program_id, &payer.pubkey());
+    let (config_pda, config_bump) = get_config_pda(&program_id);
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[create_init_config_ix(
+            program_id,
+            0,
+            config_bump,
+            payer.pubkey(),
+            TREASURY,
+            config_pda,
+            ORACLE_PROGRAM,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
     Test {
         program_id,
         payer,
@@ -353,5 +2965,7 @@ pub fn setup(svm: &mut LiteSVM) -> Test {
         auction_pda,
         auction_bump,
         auction_sell_ata,
+        counter_pda,
+        counter_bump,
     }
 }
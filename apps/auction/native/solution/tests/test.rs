@@ -1,14 +1,44 @@
-use borsh::BorshDeserialize;
 use litesvm::LiteSVM;
+use oracle::state::Oracle;
 use solana_program::sysvar::clock::Clock;
-use solana_sdk::{signature::Signer, transaction::Transaction};
+use solana_sdk::{
+    account::Account,
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
 
-use auction::{Cmd, state::Auction};
+use auction::{
+    InitParams,
+    error::AuctionError,
+    merkle,
+    price::{price_at, reverse_price_at},
+    state::{
+        Auction, AuctionResult, Batch, Commitment, Config, Counter,
+        EnglishAuction, PriceCurve, Purchase, ReverseAuction, SealedAuction,
+        Vesting,
+    },
+};
 
 mod helper;
 use helper::{
-    Test, create_buy_ix, create_cancel_ix, create_init_ix, get_token_balance,
-    setup,
+    ORACLE_PROGRAM, TREASURY, Test, add_program, create_accept_ix, create_ata,
+    create_ata_with_program, create_batch_bid_ix, create_batch_claim_ix,
+    create_bid_ix, create_buy_ix, create_cancel_ix, create_claim_ix,
+    create_clear_ix, create_commit_ix, create_english_init_ix,
+    create_expire_ix, create_fee_mint, create_get_price_ix, create_hook_mint,
+    create_init_config_ix, create_init_ix, create_nft, create_reveal_ix,
+    create_reverse_cancel_ix, create_reverse_init_ix, create_sealed_claim_ix,
+    create_sealed_init_ix, create_sealed_settle_ix, create_set_fee_ix,
+    create_settle_ix, create_update_ix, get_ata, get_ata_with_program,
+    get_auction_pda, get_batch_bid_pda, get_batch_pda, get_commitment_pda,
+    get_config_pda, get_english_pda, get_error, get_hook_accounts,
+    get_hook_count, get_price, get_purchase_pda, get_result_pda,
+    get_reverse_pda, get_sealed_pda, get_token_balance, get_vesting_pda,
+    merkle_proof, merkle_tree, mint_to, revoke_mint_authority,
+    set_upgrade_authority, setup, setup_with_decimals, token_2022_program,
+    token_program, with_token_program, with_token_programs,
 };
 
 #[test]
@@ -16,18 +46,16 @@ fn test_init() {
     let mut svm = LiteSVM::new();
     let Test {
         program_id,
-        payer,
         seller,
-        buyer,
         mint_sell,
         mint_buy,
         seller_sell_ata,
-        seller_buy_ata,
-        buyer_sell_ata,
-        buyer_buy_ata,
         auction_pda,
         auction_bump,
         auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
     } = setup(&mut svm);
 
     let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
@@ -40,15 +68,20 @@ fn test_init() {
     // Check that auction_pda matches expected PDA
     let ix = create_init_ix(
         program_id,
-        start_price,
-        end_price,
-        start_time,
-        end_time,
-        sell_amt,
-        auction_bump,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
         seller.pubkey(),
         mint_sell,
         mint_buy,
+        counter_pda,
         mint_sell,
         auction_sell_ata,
         seller_sell_ata,
@@ -65,15 +98,20 @@ fn test_init() {
     // Check auction_sell_ata
     let ix = create_init_ix(
         program_id,
-        start_price,
-        end_price,
-        start_time,
-        end_time,
-        sell_amt,
-        auction_bump,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
         seller.pubkey(),
         mint_sell,
         mint_buy,
+        counter_pda,
         auction_pda,
         auction_pda,
         seller_sell_ata,
@@ -85,20 +123,25 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_eq!(get_error(res), Some(AuctionError::InvalidTokenAccount));
 
     // Check seller_sell_ata
     let ix = create_init_ix(
         program_id,
-        start_price,
-        end_price,
-        start_time,
-        end_time,
-        sell_amt,
-        auction_bump,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
         seller.pubkey(),
         mint_sell,
         mint_buy,
+        counter_pda,
         auction_pda,
         auction_sell_ata,
         auction_sell_ata,
@@ -110,20 +153,25 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_eq!(get_error(res), Some(AuctionError::InvalidTokenAccount));
 
     // Check sell token != buy token
     let ix = create_init_ix(
         program_id,
-        start_price,
-        end_price,
-        start_time,
-        end_time,
-        sell_amt,
-        auction_bump,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
         seller.pubkey(),
         mint_sell,
         mint_sell,
+        counter_pda,
         auction_pda,
         auction_sell_ata,
         seller_sell_ata,
@@ -140,15 +188,20 @@ fn test_init() {
     // Check start_price >= end_price
     let ix = create_init_ix(
         program_id,
-        start_price,
-        start_price + 1,
-        start_time,
-        end_time,
-        sell_amt,
-        auction_bump,
+        InitParams {
+            start_price,
+            end_price: start_price + 1,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
         seller.pubkey(),
         mint_sell,
         mint_sell,
+        counter_pda,
         auction_pda,
         auction_sell_ata,
         seller_sell_ata,
@@ -160,20 +213,25 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_eq!(get_error(res), Some(AuctionError::InvalidPrices));
 
     // Check now <= start_time < end_time
     let ix = create_init_ix(
         program_id,
-        start_price,
-        end_price,
-        end_time,
-        end_time,
-        sell_amt,
-        auction_bump,
+        InitParams {
+            start_price,
+            end_price,
+            start_time: end_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
         seller.pubkey(),
         mint_sell,
         mint_sell,
+        counter_pda,
         auction_pda,
         auction_sell_ata,
         seller_sell_ata,
@@ -185,20 +243,24 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_eq!(get_error(res), Some(AuctionError::InvalidEndTime));
 
     // Check sell_amt > 0
     let ix = create_init_ix(
         program_id,
-        start_price,
-        end_price,
-        start_time,
-        end_time,
-        0,
-        auction_bump,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
         seller.pubkey(),
         mint_sell,
         mint_sell,
+        counter_pda,
         auction_pda,
         auction_sell_ata,
         seller_sell_ata,
@@ -210,20 +272,25 @@ fn test_init() {
         &[&seller],
         svm.latest_blockhash(),
     ));
-    assert!(res.is_err());
+    assert_eq!(get_error(res), Some(AuctionError::InvalidSellAmount));
 
     // Init
     let ix = create_init_ix(
         program_id,
-        start_price,
-        end_price,
-        start_time,
-        end_time,
-        sell_amt,
-        auction_bump,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
         seller.pubkey(),
         mint_sell,
         mint_buy,
+        counter_pda,
         auction_pda,
         auction_sell_ata,
         seller_sell_ata,
@@ -238,17 +305,24 @@ fn test_init() {
     assert!(res.is_ok());
 
     let data = svm.get_account(&auction_pda).unwrap().data;
-    let auction = Auction::try_from_slice(&data).unwrap();
+    let auction = Auction::unpack(&data).unwrap();
 
+    assert_eq!(auction.seller, seller.pubkey());
     assert_eq!(auction.mint_sell, mint_sell);
     assert_eq!(auction.mint_buy, mint_buy);
+    assert_eq!(auction.id, 0);
     assert_eq!(auction.start_price, start_price);
     assert_eq!(auction.end_price, end_price);
     assert_eq!(auction.start_time, start_time);
     assert_eq!(auction.end_time, end_time);
+    assert_eq!(auction.remaining, sell_amt);
     assert!(svm.get_balance(&auction_pda).unwrap() > 0);
     assert!(svm.get_balance(&auction_sell_ata).unwrap() > 0);
     assert_eq!(get_token_balance(&svm, &auction_sell_ata), sell_amt);
+
+    let data = svm.get_account(&counter_pda).unwrap().data;
+    let counter = Counter::unpack(&data).unwrap();
+    assert_eq!(counter.next_id, 1);
 }
 
 #[test]
@@ -256,7 +330,6 @@ fn test_buy() {
     let mut svm = LiteSVM::new();
     let Test {
         program_id,
-        payer,
         seller,
         buyer,
         mint_sell,
@@ -268,6 +341,9 @@ fn test_buy() {
         auction_pda,
         auction_bump,
         auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
     } = setup(&mut svm);
 
     // Init
@@ -280,15 +356,20 @@ fn test_buy() {
 
     let ix = create_init_ix(
         program_id,
-        start_price,
-        end_price,
-        start_time,
-        end_time,
-        sell_amt,
-        auction_bump,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
         seller.pubkey(),
         mint_sell,
         mint_buy,
+        counter_pda,
         auction_pda,
         auction_sell_ata,
         seller_sell_ata,
@@ -311,8 +392,12 @@ fn test_buy() {
 
     let ix = create_buy_ix(
         program_id,
+        0,
         max_price,
+        sell_amt,
         auction_bump,
+        vec![],
+        Pubkey::default(),
         buyer.pubkey(),
         seller.pubkey(),
         mint_sell,
@@ -331,15 +416,33 @@ fn test_buy() {
         svm.latest_blockhash(),
     ));
     assert!(res.is_ok());
+    // CU budget of Buy, Auction is read in place
+    let cu = res.unwrap().compute_units_consumed;
+    assert!(cu < 100_000, "Buy used {cu} CU");
 
     assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
     assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
     assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
     assert!(get_token_balance(&svm, &seller_buy_ata) > 0);
+
+    // Outcome is kept after the auction is closed
+    let (result_pda, _) = get_result_pda(&program_id, &auction_pda);
+    let data = svm.get_account(&result_pda).unwrap().data;
+    let result = AuctionResult::unpack(&data).unwrap();
+    assert_eq!(result.outcome, AuctionResult::SOLD);
+    assert_eq!(result.seller, seller.pubkey());
+    assert_eq!(result.mint_sell, mint_sell);
+    assert_eq!(result.mint_buy, mint_buy);
+    assert_eq!(result.buyer, buyer.pubkey());
+    assert_eq!(result.id, 0);
+    assert_eq!(result.sold, sell_amt);
+    assert_eq!(result.proceeds, get_token_balance(&svm, &seller_buy_ata));
+    assert!(result.price <= max_price);
+    assert_eq!(result.closed_at, start_time + 2);
 }
 
 #[test]
-fn test_cancel() {
+fn test_buy_partial() {
     let mut svm = LiteSVM::new();
     let Test {
         program_id,
@@ -355,6 +458,171 @@ fn test_cancel() {
         auction_pda,
         auction_bump,
         auction_sell_ata,
+        counter_pda,
+        counter_bump,
+    } = setup(&mut svm);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let max_price = start_price - 1;
+    let buy = |svm: &mut LiteSVM, buy_amt: u64| {
+        let ix = create_buy_ix(
+            program_id,
+            0,
+            max_price,
+            buy_amt,
+            auction_bump,
+            vec![],
+            Pubkey::default(),
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            buyer_sell_ata,
+            buyer_buy_ata,
+            seller_buy_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check auction has started
+    assert_eq!(
+        get_error(buy(&mut svm, 1)),
+        Some(AuctionError::AuctionNotStarted)
+    );
+
+    // Check auction has not ended
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = end_time as i64;
+    svm.set_sysvar(&clock);
+    assert_eq!(
+        get_error(buy(&mut svm, 1)),
+        Some(AuctionError::AuctionEnded)
+    );
+
+    // Price = 1.9 buy token per sell token
+    clock.unix_timestamp = (start_time + 2) as i64;
+    svm.set_sysvar(&clock);
+
+    // Check buy_amt > 0
+    assert_eq!(
+        get_error(buy(&mut svm, 0)),
+        Some(AuctionError::InvalidBuyAmount)
+    );
+
+    // Check buy_amt <= remaining
+    assert_eq!(
+        get_error(buy(&mut svm, sell_amt + 1)),
+        Some(AuctionError::InvalidBuyAmount)
+    );
+
+    // Buy 1/4
+    buy(&mut svm, sell_amt / 4).unwrap();
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::unpack(&data).unwrap();
+    assert_eq!(auction.remaining, sell_amt - sell_amt / 4);
+    assert_eq!(
+        get_token_balance(&svm, &auction_sell_ata),
+        sell_amt - sell_amt / 4
+    );
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt / 4);
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), 475 * 1e5 as u64);
+
+    // 1 unit of sell token costs 1.9 units of buy token, rounded up
+    buy(&mut svm, 1).unwrap();
+    assert_eq!(
+        get_token_balance(&svm, &seller_buy_ata),
+        475 * 1e5 as u64 + 2
+    );
+
+    // Anyone can send sell token to auction_sell_ata
+    mint_to(&mut svm, &payer, &mint_sell, &auction_sell_ata, 1);
+    let seller_sell_bal = get_token_balance(&svm, &seller_sell_ata);
+
+    // Buy the rest, auction closes and dust is sent to seller
+    let remaining = sell_amt - sell_amt / 4 - 1;
+    buy(&mut svm, remaining).unwrap();
+
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
+    assert_eq!(
+        get_token_balance(&svm, &seller_sell_ata),
+        seller_sell_bal + 1
+    );
+    assert_eq!(
+        get_token_balance(&svm, &seller_buy_ata),
+        475 * 1e5 as u64 + 2 + (remaining * 19).div_ceil(10)
+    );
+
+    // Auction is closed
+    assert!(buy(&mut svm, 1).is_err());
+}
+
+#[test]
+fn test_cancel_after_partial_buy() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
     } = setup(&mut svm);
 
     // Init
@@ -367,15 +635,145 @@ fn test_cancel() {
 
     let ix = create_init_ix(
         program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Buy 1/4
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = (start_time + 2) as i64;
+    svm.set_sysvar(&clock);
+
+    let ix = create_buy_ix(
+        program_id,
+        0,
         start_price,
-        end_price,
-        start_time,
-        end_time,
-        sell_amt,
+        sell_amt / 4,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Cancel returns the rest to the seller
+    let ix = create_cancel_ix(
+        program_id,
+        0,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt / 4);
+    assert_eq!(
+        get_token_balance(&svm, &seller_sell_ata),
+        1e9 as u64 - sell_amt / 4
+    );
+
+    let (result_pda, _) = get_result_pda(&program_id, &auction_pda);
+    let data = svm.get_account(&result_pda).unwrap().data;
+    let result = AuctionResult::unpack(&data).unwrap();
+    assert_eq!(result.outcome, AuctionResult::CANCELLED);
+    assert_eq!(result.buyer, Pubkey::default());
+    assert_eq!(result.sold, sell_amt / 4);
+    assert_eq!(result.proceeds, get_token_balance(&svm, &seller_buy_ata));
+    assert_eq!(result.price, 0);
+}
+
+#[test]
+fn test_cancel() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        seller,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        auction_pda,
         auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
         seller.pubkey(),
         mint_sell,
         mint_buy,
+        counter_pda,
         auction_pda,
         auction_sell_ata,
         seller_sell_ata,
@@ -392,6 +790,7 @@ fn test_cancel() {
     // Cancel
     let ix = create_cancel_ix(
         program_id,
+        0,
         auction_bump,
         seller.pubkey(),
         mint_sell,
@@ -413,3 +812,3919 @@ fn test_cancel() {
     assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
     assert_eq!(get_token_balance(&svm, &seller_sell_ata), 1e9 as u64);
 }
+
+#[test]
+fn test_multiple_auctions() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let init = |svm: &mut LiteSVM, id: u64| {
+        let (auction_pda, auction_bump) = get_auction_pda(
+            &program_id,
+            &seller.pubkey(),
+            &mint_sell,
+            &mint_buy,
+            id,
+        );
+        let ix = create_init_ix(
+            program_id,
+            InitParams {
+                id,
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                sell_amt,
+                bump: auction_bump,
+                counter_bump,
+                ..Default::default()
+            },
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            counter_pda,
+            auction_pda,
+            get_ata(&mint_sell, &auction_pda),
+            seller_sell_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Same seller and mints, different ids
+    init(&mut svm, 0).unwrap();
+
+    // Check id = counter.next_id
+    assert_eq!(get_error(init(&mut svm, 0)), Some(AuctionError::InvalidId));
+    assert_eq!(get_error(init(&mut svm, 2)), Some(AuctionError::InvalidId));
+
+    init(&mut svm, 1).unwrap();
+
+    let data = svm.get_account(&counter_pda).unwrap().data;
+    let counter = Counter::unpack(&data).unwrap();
+    assert_eq!(counter.next_id, 2);
+
+    let (auction_pda_1, auction_bump_1) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        1,
+    );
+    let auction_sell_ata_1 = get_ata(&mint_sell, &auction_pda_1);
+
+    let data = svm.get_account(&auction_pda_1).unwrap().data;
+    let auction = Auction::unpack(&data).unwrap();
+    assert_eq!(auction.id, 1);
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata), sell_amt);
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata_1), sell_amt);
+
+    // Buy auction 1
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = (start_time + 2) as i64;
+    svm.set_sysvar(&clock);
+
+    let ix = create_buy_ix(
+        program_id,
+        1,
+        start_price,
+        sell_amt,
+        auction_bump_1,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda_1,
+        auction_sell_ata_1,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(svm.get_balance(&auction_pda_1).unwrap_or(0), 0);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
+
+    // Auction 0 is still open
+    assert!(svm.get_balance(&auction_pda).unwrap() > 0);
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata), sell_amt);
+
+    // Cancel auction 0
+    let ix = create_cancel_ix(
+        program_id,
+        0,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(
+        get_token_balance(&svm, &seller_sell_ata),
+        1e9 as u64 - sell_amt
+    );
+
+    // Ids are not reused
+    let data = svm.get_account(&counter_pda).unwrap().data;
+    let counter = Counter::unpack(&data).unwrap();
+    assert_eq!(counter.next_id, 2);
+}
+
+#[test]
+fn test_price_curves() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 100;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.0 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let init = |svm: &mut LiteSVM, id: u64, curve: PriceCurve| {
+        let (auction_pda, auction_bump) = get_auction_pda(
+            &program_id,
+            &seller.pubkey(),
+            &mint_sell,
+            &mint_buy,
+            id,
+        );
+        let ix = create_init_ix(
+            program_id,
+            InitParams {
+                id,
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                sell_amt,
+                curve,
+                bump: auction_bump,
+                counter_bump,
+                ..Default::default()
+            },
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            counter_pda,
+            auction_pda,
+            get_ata(&mint_sell, &auction_pda),
+            seller_sell_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Buy 1 sell token
+    let buy = |svm: &mut LiteSVM, id: u64, max_price: u64| {
+        let (auction_pda, auction_bump) = get_auction_pda(
+            &program_id,
+            &seller.pubkey(),
+            &mint_sell,
+            &mint_buy,
+            id,
+        );
+        let ix = create_buy_ix(
+            program_id,
+            id,
+            max_price,
+            1e6 as u64,
+            auction_bump,
+            vec![],
+            Pubkey::default(),
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            get_ata(&mint_sell, &auction_pda),
+            buyer_sell_ata,
+            buyer_buy_ata,
+            seller_buy_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check half_life > 0 and step > 0
+    assert_eq!(
+        get_error(init(&mut svm, 0, PriceCurve::Exponential { half_life: 0 })),
+        Some(AuctionError::InvalidCurve)
+    );
+    assert_eq!(
+        get_error(init(&mut svm, 0, PriceCurve::Stepped { step: 0 })),
+        Some(AuctionError::InvalidCurve)
+    );
+
+    let curves = [
+        PriceCurve::Linear,
+        PriceCurve::Exponential { half_life: 20 },
+        PriceCurve::Stepped { step: 30 },
+    ];
+    for (id, curve) in curves.iter().enumerate() {
+        init(&mut svm, id as u64, *curve).unwrap();
+    }
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::unpack(&data).unwrap();
+    assert_eq!(auction.price_curve().unwrap(), PriceCurve::Linear);
+
+    // Expected price of each curve after elapsed seconds
+    let expected: [(u64, [u64; 3]); 5] = [
+        (0, [2_000_000, 2_000_000, 2_000_000]),
+        (10, [1_900_000, 1_707_146, 2_000_000]),
+        (20, [1_800_000, 1_500_000, 2_000_000]),
+        (45, [1_550_000, 1_210_224, 1_700_000]),
+        (99, [1_010_000, 1_032_410, 1_100_000]),
+    ];
+
+    for (elapsed, prices) in expected {
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = (start_time + elapsed) as i64;
+        svm.set_sysvar(&clock);
+
+        for (id, price) in prices.into_iter().enumerate() {
+            let id = id as u64;
+            let (pda, _) = get_auction_pda(
+                &program_id,
+                &seller.pubkey(),
+                &mint_sell,
+                &mint_buy,
+                id,
+            );
+            let data = svm.get_account(&pda).unwrap().data;
+            let auction = Auction::unpack(&data).unwrap();
+
+            // Client quote
+            assert_eq!(
+                price_at(&auction, start_time + elapsed).unwrap(),
+                price
+            );
+
+            // On-chain price is the same
+            assert_eq!(
+                get_error(buy(&mut svm, id, price - 1)),
+                Some(AuctionError::MaxPrice)
+            );
+
+            let before = get_token_balance(&svm, &seller_buy_ata);
+            buy(&mut svm, id, price).unwrap();
+            assert_eq!(
+                get_token_balance(&svm, &seller_buy_ata) - before,
+                price
+            );
+        }
+    }
+}
+
+#[test]
+fn test_buy_decimals() {
+    let mut svm = LiteSVM::new();
+    // 1 whole sell token = 1e9, 1 whole buy token = 1e2
+    let Test {
+        program_id,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup_with_decimals(&mut svm, 9, 2);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    // 1.5 buy token per sell token
+    let start_price = (1.5 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e9 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let buy = |svm: &mut LiteSVM, buy_amt: u64| {
+        let ix = create_buy_ix(
+            program_id,
+            0,
+            start_price,
+            buy_amt,
+            auction_bump,
+            vec![],
+            Pubkey::default(),
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            buyer_sell_ata,
+            buyer_buy_ata,
+            seller_buy_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Dust is rounded up in favor of the seller
+    buy(&mut svm, 1).unwrap();
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), 1);
+
+    // 0.5 sell token costs 0.75 buy token
+    buy(&mut svm, 5e8 as u64).unwrap();
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), 1 + 75);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), 1 + 5e8 as u64);
+}
+
+#[test]
+fn test_buy_overflow() {
+    let mut svm = LiteSVM::new();
+    // Payment of a large lot does not fit in u128 before scaling down
+    let Test {
+        program_id,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup_with_decimals(&mut svm, 0, 30);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let ix = create_buy_ix(
+        program_id,
+        0,
+        start_price,
+        sell_amt,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_eq!(get_error(res), Some(AuctionError::MathOverflow));
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata), sell_amt);
+}
+
+#[test]
+fn test_english() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Second bidder
+    let bidder = Keypair::new();
+    svm.airdrop(&bidder.pubkey(), 1_000_000_000).unwrap();
+    let bidder_buy_ata =
+        create_ata(&mut svm, &payer, &bidder.pubkey(), &mint_buy);
+    mint_to(&mut svm, &payer, &mint_buy, &bidder_buy_ata, 1e9 as u64);
+
+    let (english_pda, english_bump) = get_english_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        0,
+    );
+    let english_sell_ata = get_ata(&mint_sell, &english_pda);
+    let english_buy_ata = get_ata(&mint_buy, &english_pda);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 100;
+    let extension = 10;
+    let reserve = 1e6 as u64;
+    let min_increment = 1e5 as u64;
+    let sell_amt = 1e8 as u64;
+
+    let init = |svm: &mut LiteSVM, min_increment: u64| {
+        let ix = create_english_init_ix(
+            program_id,
+            0,
+            reserve,
+            min_increment,
+            start_time,
+            end_time,
+            extension,
+            sell_amt,
+            english_bump,
+            counter_bump,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            counter_pda,
+            english_pda,
+            english_sell_ata,
+            english_buy_ata,
+            seller_sell_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    let bid = |svm: &mut LiteSVM,
+               bidder: &Keypair,
+               prev_bidder: &Pubkey,
+               amount: u64| {
+        let ix = create_bid_ix(
+            program_id,
+            0,
+            amount,
+            english_bump,
+            bidder.pubkey(),
+            *prev_bidder,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            english_pda,
+            english_buy_ata,
+            get_ata(&mint_buy, &bidder.pubkey()),
+            get_ata(&mint_buy, prev_bidder),
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&bidder.pubkey()),
+            &[bidder],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    let settle = |svm: &mut LiteSVM, bidder: &Pubkey| {
+        let ix = create_settle_ix(
+            program_id,
+            0,
+            english_bump,
+            payer.pubkey(),
+            seller.pubkey(),
+            *bidder,
+            mint_sell,
+            mint_buy,
+            english_pda,
+            english_sell_ata,
+            english_buy_ata,
+            seller_sell_ata,
+            seller_buy_ata,
+            get_ata(&mint_sell, bidder),
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check min_increment > 0
+    assert_eq!(
+        get_error(init(&mut svm, 0)),
+        Some(AuctionError::InvalidPrices)
+    );
+    init(&mut svm, min_increment).unwrap();
+    assert_eq!(get_token_balance(&svm, &english_sell_ata), sell_amt);
+
+    // Check auction has started
+    assert_eq!(
+        get_error(bid(&mut svm, &buyer, &buyer.pubkey(), reserve)),
+        Some(AuctionError::AuctionNotStarted)
+    );
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    // Check bid >= reserve
+    assert_eq!(
+        get_error(bid(&mut svm, &buyer, &buyer.pubkey(), reserve - 1)),
+        Some(AuctionError::BidTooLow)
+    );
+    bid(&mut svm, &buyer, &buyer.pubkey(), reserve).unwrap();
+    assert_eq!(get_token_balance(&svm, &english_buy_ata), reserve);
+
+    // Check bid >= highest bid + min_increment
+    let amount = reserve + min_increment;
+    assert_eq!(
+        get_error(bid(&mut svm, &bidder, &buyer.pubkey(), amount - 1)),
+        Some(AuctionError::BidTooLow)
+    );
+    // Check previous bidder
+    assert_eq!(
+        get_error(bid(&mut svm, &bidder, &bidder.pubkey(), amount)),
+        Some(AuctionError::InvalidAccount)
+    );
+
+    // Outbid, buyer is refunded
+    bid(&mut svm, &bidder, &buyer.pubkey(), amount).unwrap();
+    assert_eq!(get_token_balance(&svm, &english_buy_ata), amount);
+    assert_eq!(get_token_balance(&svm, &buyer_buy_ata), 1e9 as u64);
+    assert_eq!(
+        get_token_balance(&svm, &bidder_buy_ata),
+        1e9 as u64 - amount
+    );
+
+    // Check auction has not ended
+    assert_eq!(
+        get_error(settle(&mut svm, &bidder.pubkey())),
+        Some(AuctionError::AuctionNotEnded)
+    );
+
+    // Bid close to the end extends the auction
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = (end_time - 5) as i64;
+    svm.set_sysvar(&clock);
+
+    let amount = amount + min_increment;
+    bid(&mut svm, &buyer, &bidder.pubkey(), amount).unwrap();
+
+    let data = svm.get_account(&english_pda).unwrap().data;
+    let english = EnglishAuction::unpack(&data).unwrap();
+    assert_eq!(english.bidder, buyer.pubkey());
+    assert_eq!(english.bid, amount);
+    assert_eq!(english.end_time, end_time - 5 + extension);
+    assert_eq!(get_token_balance(&svm, &bidder_buy_ata), 1e9 as u64);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = end_time as i64;
+    svm.set_sysvar(&clock);
+    assert_eq!(
+        get_error(settle(&mut svm, &buyer.pubkey())),
+        Some(AuctionError::AuctionNotEnded)
+    );
+
+    // Anyone can settle after the end
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = english.end_time as i64;
+    svm.set_sysvar(&clock);
+    assert_eq!(
+        get_error(bid(&mut svm, &bidder, &buyer.pubkey(), 2 * amount)),
+        Some(AuctionError::AuctionEnded)
+    );
+
+    // Check bidder is the highest bidder
+    assert_eq!(
+        get_error(settle(&mut svm, &bidder.pubkey())),
+        Some(AuctionError::InvalidAccount)
+    );
+    settle(&mut svm, &buyer.pubkey()).unwrap();
+
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), amount);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
+    assert_eq!(svm.get_balance(&english_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&english_sell_ata).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&english_buy_ata).unwrap_or(0), 0);
+}
+
+#[test]
+fn test_english_no_bids() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    let (english_pda, english_bump) = get_english_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        0,
+    );
+    let english_sell_ata = get_ata(&mint_sell, &english_pda);
+    let english_buy_ata = get_ata(&mint_buy, &english_pda);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 100;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_english_init_ix(
+        program_id,
+        0,
+        1e6 as u64,
+        1e5 as u64,
+        start_time,
+        end_time,
+        10,
+        sell_amt,
+        english_bump,
+        counter_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        english_pda,
+        english_sell_ata,
+        english_buy_ata,
+        seller_sell_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&svm, &seller_sell_ata),
+        1e9 as u64 - sell_amt
+    );
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = end_time as i64;
+    svm.set_sysvar(&clock);
+
+    // Lot goes back to seller
+    let ix = create_settle_ix(
+        program_id,
+        0,
+        english_bump,
+        payer.pubkey(),
+        seller.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        english_pda,
+        english_sell_ata,
+        english_buy_ata,
+        seller_sell_ata,
+        seller_buy_ata,
+        get_ata(&mint_sell, &seller.pubkey()),
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    assert_eq!(get_token_balance(&svm, &seller_sell_ata), 1e9 as u64);
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), 0);
+    assert_eq!(svm.get_balance(&english_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&english_sell_ata).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&english_buy_ata).unwrap_or(0), 0);
+}
+
+#[test]
+fn test_sealed() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // More bidders, bidder_3 does not reveal, bidder_4 can not win and late
+    // commits too late
+    let bidder_2 = Keypair::new();
+    let bidder_3 = Keypair::new();
+    let bidder_4 = Keypair::new();
+    let late = Keypair::new();
+    for bidder in [&bidder_2, &bidder_3, &bidder_4, &late] {
+        svm.airdrop(&bidder.pubkey(), 1_000_000_000).unwrap();
+        let ata = create_ata(&mut svm, &payer, &bidder.pubkey(), &mint_buy);
+        mint_to(&mut svm, &payer, &mint_buy, &ata, 1e9 as u64);
+    }
+
+    let (sealed_pda, sealed_bump) =
+        get_sealed_pda(&program_id, &seller.pubkey(), &mint_sell, &mint_buy, 0);
+    let sealed_sell_ata = get_ata(&mint_sell, &sealed_pda);
+    let sealed_buy_ata = get_ata(&mint_buy, &sealed_pda);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let commit_end = now + 10;
+    let reveal_end = commit_end + 10;
+    let reserve = 1e6 as u64;
+    let deposit = 1e6 as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_sealed_init_ix(
+        program_id,
+        0,
+        reserve,
+        deposit,
+        commit_end,
+        reveal_end,
+        sell_amt,
+        sealed_bump,
+        counter_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        sealed_pda,
+        sealed_sell_ata,
+        sealed_buy_ata,
+        seller_sell_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    let commit = |svm: &mut LiteSVM, bidder: &Keypair, hash: [u8; 32]| {
+        let (commitment_pda, commitment_bump) =
+            get_commitment_pda(&program_id, &sealed_pda, &bidder.pubkey());
+        let ix = create_commit_ix(
+            program_id,
+            0,
+            hash,
+            sealed_bump,
+            commitment_bump,
+            bidder.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            sealed_pda,
+            sealed_buy_ata,
+            get_ata(&mint_buy, &bidder.pubkey()),
+            commitment_pda,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&bidder.pubkey()),
+            &[bidder],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    let reveal = |svm: &mut LiteSVM,
+                  bidder: &Keypair,
+                  price: u64,
+                  amount: u64,
+                  salt: [u8; 32]| {
+        let (commitment_pda, commitment_bump) =
+            get_commitment_pda(&program_id, &sealed_pda, &bidder.pubkey());
+        let ix = create_reveal_ix(
+            program_id,
+            0,
+            price,
+            amount,
+            salt,
+            sealed_bump,
+            commitment_bump,
+            bidder.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            sealed_pda,
+            sealed_buy_ata,
+            get_ata(&mint_buy, &bidder.pubkey()),
+            commitment_pda,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&bidder.pubkey()),
+            &[bidder],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    let settle = |svm: &mut LiteSVM| {
+        let ix = create_sealed_settle_ix(
+            program_id,
+            0,
+            sealed_bump,
+            payer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            sealed_pda,
+            sealed_sell_ata,
+            sealed_buy_ata,
+            seller_sell_ata,
+            seller_buy_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // signer claims for bidder
+    let claim = |svm: &mut LiteSVM, signer: &Keypair, bidder: &Keypair| {
+        let (commitment_pda, commitment_bump) =
+            get_commitment_pda(&program_id, &sealed_pda, &bidder.pubkey());
+        let ix = create_sealed_claim_ix(
+            program_id,
+            0,
+            sealed_bump,
+            commitment_bump,
+            signer.pubkey(),
+            bidder.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            sealed_pda,
+            sealed_sell_ata,
+            sealed_buy_ata,
+            seller_sell_ata,
+            seller_buy_ata,
+            get_ata(&mint_sell, &bidder.pubkey()),
+            get_ata(&mint_buy, &bidder.pubkey()),
+            commitment_pda,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&signer.pubkey()),
+            &[signer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // (bidder, price, amount, salt)
+    let bids = [
+        (&buyer, (2.0 * 1e6) as u64, 6e7 as u64, [1; 32]),
+        (&bidder_2, (1.5 * 1e6) as u64, 6e7 as u64, [2; 32]),
+        (&bidder_3, (3.0 * 1e6) as u64, 1e7 as u64, [3; 32]),
+        (&bidder_4, (1.2 * 1e6) as u64, 3e7 as u64, [4; 32]),
+    ];
+
+    // Commit phase
+    for (bidder, price, amount, salt) in bids {
+        let hash = Commitment::hash(price, amount, &salt);
+        commit(&mut svm, bidder, hash).unwrap();
+    }
+    assert_eq!(get_token_balance(&svm, &sealed_buy_ata), 4 * deposit);
+    // Check one commitment per bidder
+    assert!(commit(&mut svm, &buyer, [0; 32]).is_err());
+    // Check reveal phase has started
+    let (_, price, amount, salt) = bids[0];
+    assert_eq!(
+        get_error(reveal(&mut svm, &buyer, price, amount, salt)),
+        Some(AuctionError::AuctionNotStarted)
+    );
+
+    // Reveal phase
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = commit_end as i64;
+    svm.set_sysvar(&clock);
+
+    // Check commit phase has ended
+    assert_eq!(
+        get_error(commit(&mut svm, &late, [0; 32])),
+        Some(AuctionError::AuctionEnded)
+    );
+    // Check bid matches the commitment
+    assert_eq!(
+        get_error(reveal(&mut svm, &buyer, price + 1, amount, salt)),
+        Some(AuctionError::InvalidCommitment)
+    );
+
+    for &(bidder, price, amount, salt) in &bids[..2] {
+        reveal(&mut svm, bidder, price, amount, salt).unwrap();
+    }
+    // Check bid is revealed once
+    assert_eq!(
+        get_error(reveal(&mut svm, &buyer, price, amount, salt)),
+        Some(AuctionError::InvalidCommitment)
+    );
+    // bidder_4 can not win, only the deposit is kept
+    let (_, price, amount, salt) = bids[3];
+    reveal(&mut svm, &bidder_4, price, amount, salt).unwrap();
+    assert_eq!(
+        get_token_balance(&svm, &get_ata(&mint_buy, &bidder_4.pubkey())),
+        1e9 as u64 - deposit
+    );
+    // Full payments of the bids in the book are escrowed
+    assert_eq!(
+        get_token_balance(&svm, &sealed_buy_ata),
+        120 * 1e6 as u64 + 90 * 1e6 as u64 + 2 * deposit
+    );
+
+    // Check reveal phase has ended
+    assert_eq!(
+        get_error(settle(&mut svm)),
+        Some(AuctionError::AuctionNotEnded)
+    );
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = reveal_end as i64;
+    svm.set_sysvar(&clock);
+
+    // Check auction is settled
+    assert_eq!(
+        get_error(claim(&mut svm, &buyer, &buyer)),
+        Some(AuctionError::NotSettled)
+    );
+
+    settle(&mut svm).unwrap();
+    assert_eq!(
+        get_error(settle(&mut svm)),
+        Some(AuctionError::AlreadySettled)
+    );
+
+    let data = svm.get_account(&sealed_pda).unwrap().data;
+    let sealed = SealedAuction::unpack(&data).unwrap();
+    // Lowest winning bid
+    assert_eq!(sealed.clearing_price, (1.5 * 1e6) as u64);
+
+    // 60 sell token from buyer and 40 from bidder_2 at 1.5,
+    // plus the deposit of bidder_3
+    assert_eq!(
+        get_token_balance(&svm, &seller_buy_ata),
+        90 * 1e6 as u64 + 60 * 1e6 as u64 + deposit
+    );
+
+    claim(&mut svm, &buyer, &buyer).unwrap();
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), 6e7 as u64);
+    assert_eq!(
+        get_token_balance(&svm, &buyer_buy_ata),
+        1e9 as u64 - 90 * 1e6 as u64
+    );
+
+    claim(&mut svm, &bidder_2, &bidder_2).unwrap();
+    assert_eq!(
+        get_token_balance(&svm, &get_ata(&mint_sell, &bidder_2.pubkey())),
+        4e7 as u64
+    );
+    assert_eq!(
+        get_token_balance(&svm, &get_ata(&mint_buy, &bidder_2.pubkey())),
+        1e9 as u64 - 60 * 1e6 as u64
+    );
+
+    // Seller claims for bidder_4, who lost and gets the deposit and the
+    // commitment rent back
+    let (commitment_pda, _) =
+        get_commitment_pda(&program_id, &sealed_pda, &bidder_4.pubkey());
+    let rent = svm.get_balance(&commitment_pda).unwrap();
+    let lamports = svm.get_balance(&bidder_4.pubkey()).unwrap();
+    claim(&mut svm, &seller, &bidder_4).unwrap();
+    assert_eq!(
+        get_token_balance(&svm, &get_ata(&mint_buy, &bidder_4.pubkey())),
+        1e9 as u64
+    );
+    assert_eq!(svm.get_balance(&commitment_pda).unwrap_or(0), 0);
+    assert_eq!(
+        svm.get_balance(&bidder_4.pubkey()).unwrap(),
+        lamports + rent
+    );
+
+    // Closed after the last revealed bid is claimed
+    assert_eq!(svm.get_balance(&sealed_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&sealed_sell_ata).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&sealed_buy_ata).unwrap_or(0), 0);
+
+    // Deposit is lost, commitment rent is returned
+    claim(&mut svm, &bidder_3, &bidder_3).unwrap();
+    assert_eq!(
+        get_token_balance(&svm, &get_ata(&mint_buy, &bidder_3.pubkey())),
+        1e9 as u64 - deposit
+    );
+    let (commitment_pda, _) =
+        get_commitment_pda(&program_id, &sealed_pda, &bidder_3.pubkey());
+    assert_eq!(svm.get_balance(&commitment_pda).unwrap_or(0), 0);
+}
+
+#[test]
+fn test_allowlist() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Buyer that is not on the allowlist
+    let outsider = Keypair::new();
+    svm.airdrop(&outsider.pubkey(), 1_000_000_000).unwrap();
+    create_ata(&mut svm, &payer, &outsider.pubkey(), &mint_sell);
+    let outsider_buy_ata =
+        create_ata(&mut svm, &payer, &outsider.pubkey(), &mint_buy);
+    mint_to(&mut svm, &payer, &mint_buy, &outsider_buy_ata, 1e9 as u64);
+
+    // Odd number of leaves, buyer is the last one
+    let mut wallets: Vec<Pubkey> =
+        (0..4).map(|_| Pubkey::new_unique()).collect();
+    wallets.push(buyer.pubkey());
+    let tree = merkle_tree(wallets.iter().map(merkle::leaf).collect());
+    let merkle_root = tree.last().unwrap()[0];
+    let proof = merkle_proof(&tree, 4);
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+    let wallet_cap = 3e7 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            merkle_root,
+            wallet_cap,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let buy = |svm: &mut LiteSVM,
+               buyer: &Keypair,
+               buy_amt: u64,
+               proof: Vec<[u8; 32]>| {
+        let ix = create_buy_ix(
+            program_id,
+            0,
+            start_price,
+            buy_amt,
+            auction_bump,
+            proof,
+            Pubkey::default(),
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            get_ata(&mint_sell, &buyer.pubkey()),
+            get_ata(&mint_buy, &buyer.pubkey()),
+            seller_buy_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check buyer is on the allowlist
+    assert_eq!(
+        get_error(buy(&mut svm, &outsider, 1e6 as u64, vec![])),
+        Some(AuctionError::NotAllowed)
+    );
+    assert_eq!(
+        get_error(buy(&mut svm, &outsider, 1e6 as u64, proof.clone())),
+        Some(AuctionError::NotAllowed)
+    );
+    // Check proof
+    assert_eq!(
+        get_error(buy(&mut svm, &buyer, 1e6 as u64, vec![])),
+        Some(AuctionError::NotAllowed)
+    );
+    let invalid_proof = merkle_proof(&tree, 0);
+    assert_eq!(
+        get_error(buy(&mut svm, &buyer, 1e6 as u64, invalid_proof)),
+        Some(AuctionError::NotAllowed)
+    );
+
+    buy(&mut svm, &buyer, 2e7 as u64, proof.clone()).unwrap();
+
+    let (purchase_pda, _) =
+        get_purchase_pda(&program_id, &auction_pda, &buyer.pubkey());
+    let data = svm.get_account(&purchase_pda).unwrap().data;
+    assert_eq!(Purchase::unpack(&data).unwrap().bought, 2e7 as u64);
+
+    // Check wallet_cap
+    assert_eq!(
+        get_error(buy(&mut svm, &buyer, 2e7 as u64, proof.clone())),
+        Some(AuctionError::WalletCapExceeded)
+    );
+    buy(&mut svm, &buyer, 1e7 as u64, proof.clone()).unwrap();
+    assert_eq!(
+        get_error(buy(&mut svm, &buyer, 1, proof)),
+        Some(AuctionError::WalletCapExceeded)
+    );
+
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), wallet_cap);
+    assert_eq!(
+        get_token_balance(&svm, &auction_sell_ata),
+        sell_amt - wallet_cap
+    );
+}
+
+#[test]
+fn test_init_config() {
+    let mut svm = LiteSVM::new();
+    let admin = Keypair::new();
+    let attacker = Keypair::new();
+
+    let program_id = add_program(&mut svm, "target/deploy/auction.so");
+    svm.airdrop(&admin.pubkey(), 1e9 as u64).unwrap();
+    svm.airdrop(&attacker.pubkey(), 1e9 as u64).unwrap();
+    set_upgrade_authority(&mut svm, &program_id, &admin.pubkey());
+
+    let (config_pda, config_bump) = get_config_pda(&program_id);
+    let init_config = |svm: &mut LiteSVM, signer: &Keypair| {
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_init_config_ix(
+                program_id,
+                0,
+                config_bump,
+                signer.pubkey(),
+                TREASURY,
+                config_pda,
+                ORACLE_PROGRAM,
+            )],
+            Some(&signer.pubkey()),
+            &[signer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check only the upgrade authority can create the config
+    assert_eq!(
+        get_error(init_config(&mut svm, &attacker)),
+        Some(AuctionError::Unauthorized)
+    );
+    init_config(&mut svm, &admin).unwrap();
+
+    let data = svm.get_account(&config_pda).unwrap().data;
+    let config = Config::unpack(&data).unwrap();
+    assert_eq!(config.admin, admin.pubkey());
+    assert_eq!(config.treasury, TREASURY);
+    assert_eq!(config.oracle_program, ORACLE_PROGRAM);
+}
+
+#[test]
+fn test_fee() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+    } = setup(&mut svm);
+
+    let (config_pda, config_bump) = get_config_pda(&program_id);
+
+    let set_fee = |svm: &mut LiteSVM, admin: &Keypair, fee_bps: u64| {
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_set_fee_ix(
+                program_id,
+                fee_bps,
+                config_bump,
+                admin.pubkey(),
+                config_pda,
+            )],
+            Some(&admin.pubkey()),
+            &[admin],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check config can only be created once
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[create_init_config_ix(
+            program_id,
+            0,
+            config_bump,
+            seller.pubkey(),
+            seller.pubkey(),
+            config_pda,
+            ORACLE_PROGRAM,
+        )],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Check only admin can set fee
+    assert_eq!(
+        get_error(set_fee(&mut svm, &seller, 100)),
+        Some(AuctionError::Unauthorized)
+    );
+    // Check hard cap
+    assert_eq!(
+        get_error(set_fee(&mut svm, &payer, Config::MAX_FEE_BPS + 1)),
+        Some(AuctionError::InvalidFee)
+    );
+
+    let fee_bps = 250;
+    set_fee(&mut svm, &payer, fee_bps).unwrap();
+
+    let data = svm.get_account(&config_pda).unwrap().data;
+    let config = Config::unpack(&data).unwrap();
+    assert_eq!(config.admin, payer.pubkey());
+    assert_eq!(config.treasury, TREASURY);
+    assert_eq!(config.fee_bps, fee_bps);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Buy
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let buyer_buy_bal = get_token_balance(&svm, &buyer_buy_ata);
+
+    let ix = create_buy_ix(
+        program_id,
+        0,
+        start_price,
+        sell_amt,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // 100 sell token at 2 buy token
+    let pay_amt = 2e8 as u64;
+    let fee = pay_amt * fee_bps / 10_000;
+    assert_eq!(
+        buyer_buy_bal - get_token_balance(&svm, &buyer_buy_ata),
+        pay_amt
+    );
+    assert_eq!(get_token_balance(&svm, &get_ata(&mint_buy, &TREASURY)), fee);
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), pay_amt - fee);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
+}
+
+#[test]
+fn test_expire() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Buy part of sell_amt
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = (start_time + 2) as i64;
+    svm.set_sysvar(&clock);
+
+    let buy_amt = sell_amt / 4;
+    let ix = create_buy_ix(
+        program_id,
+        0,
+        start_price,
+        buy_amt,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Anyone can expire the auction
+    let cranker = Keypair::new();
+    svm.airdrop(&cranker.pubkey(), 1_000_000_000).unwrap();
+
+    let expire = |svm: &mut LiteSVM| {
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_expire_ix(
+                program_id,
+                0,
+                auction_bump,
+                cranker.pubkey(),
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                auction_pda,
+                auction_sell_ata,
+                seller_sell_ata,
+            )],
+            Some(&cranker.pubkey()),
+            &[&cranker],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check auction has ended
+    assert_eq!(
+        get_error(expire(&mut svm)),
+        Some(AuctionError::AuctionNotEnded)
+    );
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = end_time as i64;
+    svm.set_sysvar(&clock);
+
+    let pda_rent = svm.get_balance(&auction_pda).unwrap();
+    let ata_rent = svm.get_balance(&auction_sell_ata).unwrap();
+    let seller_lamports = svm.get_balance(&seller.pubkey()).unwrap();
+    let seller_sell_bal = get_token_balance(&svm, &seller_sell_ata);
+
+    expire(&mut svm).unwrap();
+
+    let reward = pda_rent * Auction::CRANK_REWARD_BPS / 10_000;
+    assert!(reward > 0);
+    // AuctionResult rent is taken from the auction PDA
+    let result_rent =
+        svm.minimum_balance_for_rent_exemption(AuctionResult::SPACE as usize);
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(
+        svm.get_balance(&seller.pubkey()).unwrap(),
+        seller_lamports + pda_rent + ata_rent - reward - result_rent
+    );
+
+    let (result_pda, _) = get_result_pda(&program_id, &auction_pda);
+    let data = svm.get_account(&result_pda).unwrap().data;
+    let result = AuctionResult::unpack(&data).unwrap();
+    assert_eq!(result.outcome, AuctionResult::EXPIRED);
+    assert_eq!(result.sold, buy_amt);
+    assert_eq!(result.closed_at, end_time);
+    assert_eq!(
+        get_token_balance(&svm, &seller_sell_ata),
+        seller_sell_bal + sell_amt - buy_amt
+    );
+}
+
+#[test]
+fn test_oracle_floor() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+    } = setup(&mut svm);
+
+    // Oracle owned by the oracle program of the Config, price with 8
+    // decimals
+    let oracle = Pubkey::new_unique();
+    let oracle_decimals = 8;
+    let set_oracle = |svm: &mut LiteSVM, owner: Pubkey, updated_at: u64| {
+        let data = borsh::to_vec(&Oracle {
+            owner: payer.pubkey(),
+            price: 180_000_000,
+            updated_at,
+        })
+        .unwrap();
+        svm.set_account(
+            oracle,
+            Account {
+                lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+    };
+
+    // Floor is 95% of the oracle price of 1.8, converted to PRICE_SCALE
+    let oracle_bps = 9_500;
+    let floor = 1_710_000;
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 100;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.0 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let init = |svm: &mut LiteSVM, oracle_decimals: u8, oracle_bps: u64| {
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_init_ix(
+                program_id,
+                InitParams {
+                    start_price,
+                    end_price,
+                    start_time,
+                    end_time,
+                    sell_amt,
+                    oracle,
+                    oracle_decimals,
+                    oracle_bps,
+                    bump: auction_bump,
+                    counter_bump,
+                    ..Default::default()
+                },
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                counter_pda,
+                auction_pda,
+                auction_sell_ata,
+                seller_sell_ata,
+            )],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check oracle_decimals <= MAX_ORACLE_DECIMALS
+    assert_eq!(
+        get_error(init(&mut svm, Auction::MAX_ORACLE_DECIMALS + 1, oracle_bps)),
+        Some(AuctionError::InvalidOracle)
+    );
+    // Check oracle_bps > 0
+    assert_eq!(
+        get_error(init(&mut svm, oracle_decimals, 0)),
+        Some(AuctionError::InvalidOracle)
+    );
+    init(&mut svm, oracle_decimals, oracle_bps).unwrap();
+
+    // Oracle program is the one of the Config, not picked by the seller
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::unpack(&data).unwrap();
+    assert_eq!(auction.oracle_program, ORACLE_PROGRAM);
+
+    let buy = |svm: &mut LiteSVM, oracle: Pubkey, max_price: u64| {
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_buy_ix(
+                program_id,
+                0,
+                max_price,
+                1e7 as u64,
+                auction_bump,
+                vec![],
+                oracle,
+                buyer.pubkey(),
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                auction_pda,
+                auction_sell_ata,
+                buyer_sell_ata,
+                buyer_buy_ata,
+                seller_buy_ata,
+            )],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Curve price is 1.1, below the floor
+    let now = start_time + 90;
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = now as i64;
+    svm.set_sysvar(&clock);
+
+    // Check oracle account
+    set_oracle(&mut svm, ORACLE_PROGRAM, now);
+    assert_eq!(
+        get_error(buy(&mut svm, Pubkey::new_unique(), floor)),
+        Some(AuctionError::InvalidOracle)
+    );
+    // Check oracle owner is the oracle program of the Config
+    set_oracle(&mut svm, Pubkey::new_unique(), now);
+    assert!(buy(&mut svm, oracle, floor).is_err());
+    // Check oracle is not stale
+    set_oracle(&mut svm, ORACLE_PROGRAM, now - Auction::MAX_ORACLE_AGE - 1);
+    assert_eq!(
+        get_error(buy(&mut svm, oracle, floor)),
+        Some(AuctionError::StaleOracle)
+    );
+
+    set_oracle(&mut svm, ORACLE_PROGRAM, now - Auction::MAX_ORACLE_AGE);
+    // Check price is the floor
+    assert_eq!(
+        get_error(buy(&mut svm, oracle, (1.2 * 1e6) as u64)),
+        Some(AuctionError::MaxPrice)
+    );
+
+    let buyer_buy_bal = get_token_balance(&svm, &buyer_buy_ata);
+    buy(&mut svm, oracle, floor).unwrap();
+
+    // 10 sell token at 1.71 buy token
+    assert_eq!(
+        buyer_buy_bal - get_token_balance(&svm, &buyer_buy_ata),
+        17_100_000
+    );
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), 1e7 as u64);
+}
+
+#[test]
+fn test_sol_payment() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        counter_pda,
+        counter_bump,
+        seller_sell_ata,
+        buyer_sell_ata,
+        ..
+    } = setup(&mut svm);
+
+    // Paid in lamports
+    let mint_buy =
+        Pubkey::from(spl_token_interface::native_mint::ID.to_bytes());
+    let (auction_pda, auction_bump) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        0,
+    );
+    let auction_sell_ata = get_ata(&mint_sell, &auction_pda);
+    // Not used, buyer and seller have no wrapped SOL
+    let buyer_buy_ata = get_ata(&mint_buy, &buyer.pubkey());
+    let seller_buy_ata = get_ata(&mint_buy, &seller.pubkey());
+
+    let (config_pda, config_bump) = get_config_pda(&program_id);
+    let fee_bps = 250;
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[create_set_fee_ix(
+            program_id,
+            fee_bps,
+            config_bump,
+            payer.pubkey(),
+            config_pda,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+    // Treasury must stay rent exempt after receiving the fee
+    svm.airdrop(&TREASURY, 1_000_000_000).unwrap();
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    // 0.002 SOL
+    let start_price = 2_000;
+    let end_price = 1_000;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // Buy
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let buyer_bal = svm.get_balance(&buyer.pubkey()).unwrap();
+    let seller_bal = svm.get_balance(&seller.pubkey()).unwrap();
+    let treasury_bal = svm.get_balance(&TREASURY).unwrap();
+
+    // Half so that the auction stays open and no rent goes to seller
+    let buy_amt = sell_amt / 2;
+    let ix = create_buy_ix(
+        program_id,
+        0,
+        start_price,
+        buy_amt,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // 50 sell token at 0.002 SOL
+    let pay_amt = 1e8 as u64;
+    let fee = pay_amt * fee_bps / 10_000;
+    assert!(buyer_bal - svm.get_balance(&buyer.pubkey()).unwrap() >= pay_amt);
+    assert_eq!(
+        svm.get_balance(&seller.pubkey()).unwrap() - seller_bal,
+        pay_amt - fee
+    );
+    assert_eq!(svm.get_balance(&TREASURY).unwrap() - treasury_bal, fee);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), buy_amt);
+    assert!(svm.get_account(&buyer_buy_ata).is_none());
+    assert!(svm.get_account(&seller_buy_ata).is_none());
+}
+
+#[test]
+fn test_update() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 10;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    let update = |svm: &mut LiteSVM,
+                  start_price: u64,
+                  end_price: u64,
+                  start_time: u64,
+                  end_time: u64,
+                  sell_amt: u64| {
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_update_ix(
+                program_id,
+                0,
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                sell_amt,
+                auction_bump,
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                auction_pda,
+                auction_sell_ata,
+                seller_sell_ata,
+            )],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Same validation as init
+    // start_price < end_price
+    let res = update(
+        &mut svm,
+        end_price,
+        start_price,
+        start_time,
+        end_time,
+        sell_amt,
+    );
+    assert_eq!(get_error(res), Some(AuctionError::InvalidPrices));
+    // start_time < now
+    let res = update(
+        &mut svm,
+        start_price,
+        end_price,
+        now - 1,
+        end_time,
+        sell_amt,
+    );
+    assert_eq!(get_error(res), Some(AuctionError::InvalidStartTime));
+    // end_time <= start_time
+    let res = update(
+        &mut svm,
+        start_price,
+        end_price,
+        start_time,
+        start_time,
+        sell_amt,
+    );
+    assert_eq!(get_error(res), Some(AuctionError::InvalidEndTime));
+    // sell_amt = 0
+    let res = update(&mut svm, start_price, end_price, start_time, end_time, 0);
+    assert_eq!(get_error(res), Some(AuctionError::InvalidSellAmount));
+
+    // Top up
+    let start_time = now + 20;
+    let end_time = start_time + 20;
+    let start_price = (3.0 * 1e6) as u64;
+    let end_price = (1.0 * 1e6) as u64;
+    let res = update(
+        &mut svm,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        2 * sell_amt,
+    );
+    assert!(res.is_ok());
+
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata), 2 * sell_amt);
+    assert_eq!(
+        get_token_balance(&svm, &seller_sell_ata),
+        1e9 as u64 - 2 * sell_amt
+    );
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::unpack(&data).unwrap();
+    assert_eq!(auction.start_price, start_price);
+    assert_eq!(auction.end_price, end_price);
+    assert_eq!(auction.start_time, start_time);
+    assert_eq!(auction.end_time, end_time);
+    assert_eq!(auction.remaining, 2 * sell_amt);
+
+    // Withdraw
+    let sell_amt = sell_amt / 2;
+    let res = update(
+        &mut svm,
+        start_price,
+        end_price,
+        start_time,
+        end_time,
+        sell_amt,
+    );
+    assert!(res.is_ok());
+
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata), sell_amt);
+    assert_eq!(
+        get_token_balance(&svm, &seller_sell_ata),
+        1e9 as u64 - sell_amt
+    );
+
+    // Check update fails once the auction started
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let res = update(
+        &mut svm,
+        start_price,
+        end_price,
+        start_time + 1,
+        end_time,
+        sell_amt,
+    );
+    assert_eq!(get_error(res), Some(AuctionError::AuctionStarted));
+
+    // Buy everything at the updated start_price
+    let buyer_buy_bal = get_token_balance(&svm, &buyer_buy_ata);
+    let ix = create_buy_ix(
+        program_id,
+        0,
+        start_price,
+        sell_amt,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_ok());
+
+    // 50 sell token at 3 buy token
+    assert_eq!(
+        buyer_buy_bal - get_token_balance(&svm, &buyer_buy_ata),
+        1.5e8 as u64
+    );
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt);
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+}
+
+#[test]
+fn test_vesting() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+    } = setup(&mut svm);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+    let vest_cliff = 10;
+    let vest_duration = 100;
+
+    let init = |svm: &mut LiteSVM, vest_cliff: u64, vest_duration: u64| {
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_init_ix(
+                program_id,
+                InitParams {
+                    start_price,
+                    end_price,
+                    start_time,
+                    end_time,
+                    sell_amt,
+                    vest_cliff,
+                    vest_duration,
+                    bump: auction_bump,
+                    counter_bump,
+                    ..Default::default()
+                },
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                counter_pda,
+                auction_pda,
+                auction_sell_ata,
+                seller_sell_ata,
+            )],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check vest_cliff <= vest_duration
+    assert_eq!(
+        get_error(init(&mut svm, vest_duration + 1, vest_duration)),
+        Some(AuctionError::InvalidVesting)
+    );
+    init(&mut svm, vest_cliff, vest_duration).unwrap();
+
+    // Buy in 2 lots, tokens go to the vesting PDA
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let buy = |svm: &mut LiteSVM, buy_amt: u64| {
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_buy_ix(
+                program_id,
+                0,
+                start_price,
+                buy_amt,
+                auction_bump,
+                vec![],
+                Pubkey::default(),
+                buyer.pubkey(),
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                auction_pda,
+                auction_sell_ata,
+                buyer_sell_ata,
+                buyer_buy_ata,
+                seller_buy_ata,
+            )],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    buy(&mut svm, sell_amt / 4).unwrap();
+    buy(&mut svm, sell_amt - sell_amt / 4).unwrap();
+
+    let (vesting_pda, vesting_bump) =
+        get_vesting_pda(&program_id, &auction_pda, &buyer.pubkey());
+    let vesting_sell_ata = get_ata(&mint_sell, &vesting_pda);
+
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), 0);
+    assert_eq!(get_token_balance(&svm, &vesting_sell_ata), sell_amt);
+
+    let data = svm.get_account(&vesting_pda).unwrap().data;
+    let vesting = Vesting::unpack(&data).unwrap();
+    assert_eq!(vesting.auction, auction_pda);
+    assert_eq!(vesting.buyer, buyer.pubkey());
+    assert_eq!(vesting.total, sell_amt);
+    assert_eq!(vesting.claimed, 0);
+    assert_eq!(vesting.start, end_time);
+    assert_eq!(vesting.cliff, vest_cliff);
+    assert_eq!(vesting.duration, vest_duration);
+
+    let claim = |svm: &mut LiteSVM, t: u64| {
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = t as i64;
+        svm.set_sysvar(&clock);
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_claim_ix(
+                program_id,
+                vesting_bump,
+                buyer.pubkey(),
+                mint_sell,
+                vesting_pda,
+            )],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check nothing is released before the cliff
+    assert_eq!(
+        get_error(claim(&mut svm, end_time + vest_cliff - 1)),
+        Some(AuctionError::NothingToClaim)
+    );
+
+    // Linear from end_time, the cliff releases 10%
+    claim(&mut svm, end_time + vest_cliff).unwrap();
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt / 10);
+
+    // Check nothing new to claim
+    assert_eq!(
+        get_error(claim(&mut svm, end_time + vest_cliff)),
+        Some(AuctionError::NothingToClaim)
+    );
+
+    claim(&mut svm, end_time + vest_duration / 2).unwrap();
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt / 2);
+
+    // Anyone can send sell token to vesting_sell_ata
+    mint_to(&mut svm, &payer, &mint_sell, &vesting_sell_ata, 1);
+
+    // Last claim sends what is left to buyer and closes the vesting PDA and
+    // its ATA
+    claim(&mut svm, end_time + vest_duration + 1).unwrap();
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt + 1);
+    assert_eq!(svm.get_balance(&vesting_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&vesting_sell_ata).unwrap_or(0), 0);
+}
+
+#[test]
+fn test_batch() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+    } = setup(&mut svm);
+
+    // 2 more bidders
+    let bidder2 = Keypair::new();
+    let bidder3 = Keypair::new();
+    for bidder in [&bidder2, &bidder3] {
+        svm.airdrop(&bidder.pubkey(), 1_000_000_000).unwrap();
+        let ata = create_ata(&mut svm, &payer, &bidder.pubkey(), &mint_buy);
+        mint_to(&mut svm, &payer, &mint_buy, &ata, 1e9 as u64);
+    }
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 100;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.0 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            batch: true,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    let (batch_pda, _) = get_batch_pda(&program_id, &auction_pda);
+    let auction_buy_ata = get_ata(&mint_buy, &auction_pda);
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    assert_eq!(Auction::unpack(&data).unwrap().batch, 1);
+    let data = svm.get_account(&batch_pda).unwrap().data;
+    assert_eq!(Batch::unpack(&data).unwrap().auction, auction_pda);
+
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    // Check Buy is disabled
+    let ix = create_buy_ix(
+        program_id,
+        0,
+        start_price,
+        sell_amt,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ));
+    assert_eq!(get_error(res), Some(AuctionError::InvalidAuctionKind));
+
+    let bid = |svm: &mut LiteSVM, bidder: &Keypair, price: u64, amount: u64| {
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_batch_bid_ix(
+                program_id,
+                0,
+                price,
+                amount,
+                auction_bump,
+                bidder.pubkey(),
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                auction_pda,
+                get_ata(&mint_buy, &bidder.pubkey()),
+            )],
+            Some(&bidder.pubkey()),
+            &[bidder],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check price >= end_price
+    assert_eq!(
+        get_error(bid(&mut svm, &buyer, end_price - 1, sell_amt)),
+        Some(AuctionError::BidTooLow)
+    );
+
+    // Check amount >= min amount of the book
+    assert_eq!(
+        get_error(bid(&mut svm, &buyer, start_price, sell_amt / 8 - 1)),
+        Some(AuctionError::InvalidBuyAmount)
+    );
+
+    // Bids cover the supply at 1.6
+    bid(&mut svm, &buyer, (1.8 * 1e6) as u64, 6e7 as u64).unwrap();
+    bid(&mut svm, &bidder2, (1.6 * 1e6) as u64, 6e7 as u64).unwrap();
+
+    // Check bid that can not win is rejected instead of escrowed
+    assert_eq!(
+        get_error(bid(&mut svm, &bidder3, (1.2 * 1e6) as u64, 3e7 as u64)),
+        Some(AuctionError::BidTooLow)
+    );
+    assert_eq!(
+        get_token_balance(&svm, &get_ata(&mint_buy, &bidder3.pubkey())),
+        1e9 as u64
+    );
+
+    // Check one bid per bidder
+    assert!(bid(&mut svm, &buyer, (1.9 * 1e6) as u64, 2e7 as u64).is_err());
+
+    // Full payments are escrowed
+    assert_eq!(
+        get_token_balance(&svm, &auction_buy_ata),
+        (108e6 + 96e6) as u64
+    );
+
+    let data = svm.get_account(&batch_pda).unwrap().data;
+    let batch = Batch::unpack(&data).unwrap();
+    assert_eq!(batch.num_bids, 2);
+    assert_eq!(batch.book.len, 2);
+
+    // signer claims for bidder
+    let claim = |svm: &mut LiteSVM, signer: &Keypair, bidder: &Keypair| {
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_batch_claim_ix(
+                program_id,
+                0,
+                auction_bump,
+                signer.pubkey(),
+                bidder.pubkey(),
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                auction_pda,
+            )],
+            Some(&signer.pubkey()),
+            &[signer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check claim before clear
+    assert_eq!(
+        get_error(claim(&mut svm, &buyer, &buyer)),
+        Some(AuctionError::NotSettled)
+    );
+
+    let clear = |svm: &mut LiteSVM, t: u64| {
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = t as i64;
+        svm.set_sysvar(&clock);
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[create_clear_ix(
+                program_id,
+                0,
+                auction_bump,
+                payer.pubkey(),
+                seller.pubkey(),
+                mint_sell,
+                mint_buy,
+                auction_pda,
+            )],
+            Some(&payer.pubkey()),
+            &[&payer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check demand does not meet supply before the price drops to 1.6
+    assert_eq!(
+        get_error(clear(&mut svm, start_time + 39)),
+        Some(AuctionError::AuctionNotEnded)
+    );
+
+    clear(&mut svm, start_time + 40).unwrap();
+
+    let data = svm.get_account(&batch_pda).unwrap().data;
+    let batch = Batch::unpack(&data).unwrap();
+    assert_eq!(batch.cleared, 1);
+    assert_eq!(batch.clearing_price, (1.6 * 1e6) as u64);
+    assert_eq!(batch.supply, sell_amt);
+
+    // Seller is paid at the clearing price
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), 160e6 as u64);
+
+    // Check no bids after clear
+    let payer_buy_ata =
+        create_ata(&mut svm, &payer, &payer.pubkey(), &mint_buy);
+    mint_to(&mut svm, &payer, &mint_buy, &payer_buy_ata, 1e9 as u64);
+    assert!(bid(&mut svm, &payer, start_price, 2e7 as u64).is_err());
+
+    // buyer wins 60 and pays 96 out of 108 escrowed
+    claim(&mut svm, &buyer, &buyer).unwrap();
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), 6e7 as u64);
+    assert_eq!(get_token_balance(&svm, &buyer_buy_ata), (1e9 - 96e6) as u64);
+
+    // Seller claims for bidder2, who wins the 40 left, pays 64 out of 96
+    // escrowed and gets the bid rent back. The last claim closes the
+    // auction.
+    let (bid_pda, _) =
+        get_batch_bid_pda(&program_id, &auction_pda, &bidder2.pubkey());
+    let rent = svm.get_balance(&bid_pda).unwrap();
+    let lamports = svm.get_balance(&bidder2.pubkey()).unwrap();
+    claim(&mut svm, &seller, &bidder2).unwrap();
+    assert_eq!(svm.get_balance(&bid_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&bidder2.pubkey()).unwrap(), lamports + rent);
+    assert_eq!(
+        get_token_balance(&svm, &get_ata(&mint_sell, &bidder2.pubkey())),
+        4e7 as u64
+    );
+    assert_eq!(
+        get_token_balance(&svm, &get_ata(&mint_buy, &bidder2.pubkey())),
+        (1e9 - 64e6) as u64
+    );
+
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&batch_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_buy_ata).unwrap_or(0), 0);
+
+    let (result_pda, _) = get_result_pda(&program_id, &auction_pda);
+    let data = svm.get_account(&result_pda).unwrap().data;
+    let result = AuctionResult::unpack(&data).unwrap();
+    assert_eq!(result.outcome, AuctionResult::SOLD);
+    assert_eq!(result.sold, sell_amt);
+    assert_eq!(result.proceeds, 160e6 as u64);
+    assert_eq!(result.price, (1.6 * 1e6) as u64);
+}
+
+#[test]
+fn test_nft() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Token-2022 NFT with metadata, sold for lamports
+    let nft = create_nft(&mut svm, &payer, &seller.pubkey());
+    let mint_buy =
+        Pubkey::from(spl_token_interface::native_mint::ID.to_bytes());
+    let (auction_pda, auction_bump) =
+        get_auction_pda(&program_id, &seller.pubkey(), &nft, &mint_buy, 0);
+    let token_program_nft = token_2022_program();
+    let seller_nft_ata =
+        get_ata_with_program(&nft, &seller.pubkey(), &token_program_nft);
+    let auction_nft_ata =
+        get_ata_with_program(&nft, &auction_pda, &token_program_nft);
+    let buyer_nft_ata = create_ata_with_program(
+        &mut svm,
+        &payer,
+        &buyer.pubkey(),
+        &nft,
+        &token_program_nft,
+    );
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    // 0.5 SOL
+    let start_price = 500_000;
+    let end_price = 200_000;
+
+    let init = |svm: &mut LiteSVM,
+                sell_amt: u64,
+                metadata: Pubkey,
+                collection: Pubkey| {
+        let ix = create_init_ix(
+            program_id,
+            InitParams {
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                sell_amt,
+                nft: true,
+                metadata,
+                collection,
+                bump: auction_bump,
+                counter_bump,
+                ..Default::default()
+            },
+            seller.pubkey(),
+            nft,
+            mint_buy,
+            counter_pda,
+            auction_pda,
+            auction_nft_ata,
+            seller_nft_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[with_token_programs(
+                ix,
+                nft,
+                token_program_nft,
+                mint_buy,
+                token_program(),
+            )],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check the mint authority is revoked
+    assert_eq!(
+        get_error(init(&mut svm, 1, nft, Pubkey::default())),
+        Some(AuctionError::InvalidNft)
+    );
+    revoke_mint_authority(&mut svm, &payer, &nft);
+
+    // Check sell_amt == 1
+    assert_eq!(
+        get_error(init(&mut svm, 2, nft, Pubkey::default())),
+        Some(AuctionError::InvalidSellAmount)
+    );
+    // Check metadata pointer
+    let other = Pubkey::new_unique();
+    assert_eq!(
+        get_error(init(&mut svm, 1, other, Pubkey::default())),
+        Some(AuctionError::InvalidNft)
+    );
+    // Check collection, the NFT is not a member of any group
+    assert_eq!(
+        get_error(init(&mut svm, 1, nft, other)),
+        Some(AuctionError::InvalidNft)
+    );
+
+    init(&mut svm, 1, nft, Pubkey::default()).unwrap();
+    assert_eq!(get_token_balance(&svm, &auction_nft_ata), 1);
+    assert_eq!(get_token_balance(&svm, &seller_nft_ata), 0);
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::unpack(&data).unwrap();
+    assert_eq!(auction.nft, 1);
+    assert_eq!(auction.collection, Pubkey::default());
+
+    // Buy
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    // Buyers see that the auction sells an NFT
+    let quote = get_price(
+        &svm,
+        &buyer,
+        create_get_price_ix(
+            program_id,
+            0,
+            auction_bump,
+            solana_sdk::system_program::id(),
+            seller.pubkey(),
+            nft,
+            mint_buy,
+            auction_pda,
+        ),
+    )
+    .unwrap();
+    assert!(quote.nft);
+    assert_eq!(quote.collection, Pubkey::default());
+
+    let buyer_bal = svm.get_balance(&buyer.pubkey()).unwrap();
+
+    let ix = create_buy_ix(
+        program_id,
+        0,
+        start_price,
+        1,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        nft,
+        mint_buy,
+        auction_pda,
+        auction_nft_ata,
+        buyer_nft_ata,
+        get_ata(&mint_buy, &buyer.pubkey()),
+        get_ata(&mint_buy, &seller.pubkey()),
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[with_token_programs(
+            ix,
+            nft,
+            token_program_nft,
+            mint_buy,
+            token_program(),
+        )],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    // 1 NFT at 0.5 SOL
+    assert!(
+        buyer_bal - svm.get_balance(&buyer.pubkey()).unwrap() >= 5e8 as u64
+    );
+    assert_eq!(get_token_balance(&svm, &buyer_nft_ata), 1);
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_nft_ata).unwrap_or(0), 0);
+
+    let (result_pda, _) = get_result_pda(&program_id, &auction_pda);
+    let data = svm.get_account(&result_pda).unwrap().data;
+    let result = AuctionResult::unpack(&data).unwrap();
+    assert_eq!(result.outcome, AuctionResult::SOLD);
+    assert_eq!(result.buyer, buyer.pubkey());
+    assert_eq!(result.sold, 1);
+    assert_eq!(result.price, start_price);
+}
+
+#[test]
+fn test_transfer_fee() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Token-2022 mints with a 1% transfer fee
+    let mint_sell = create_fee_mint(
+        &mut svm,
+        &payer,
+        &seller.pubkey(),
+        100,
+        u64::MAX,
+        1e9 as u64,
+    );
+    let mint_buy = create_fee_mint(
+        &mut svm,
+        &payer,
+        &buyer.pubkey(),
+        100,
+        u64::MAX,
+        1e9 as u64,
+    );
+    let token_program = token_2022_program();
+    let (auction_pda, auction_bump) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        0,
+    );
+    let seller_sell_ata =
+        get_ata_with_program(&mint_sell, &seller.pubkey(), &token_program);
+    let auction_sell_ata =
+        get_ata_with_program(&mint_sell, &auction_pda, &token_program);
+    let buyer_buy_ata =
+        get_ata_with_program(&mint_buy, &buyer.pubkey(), &token_program);
+    let buyer_sell_ata = create_ata_with_program(
+        &mut svm,
+        &payer,
+        &buyer.pubkey(),
+        &mint_sell,
+        &token_program,
+    );
+    let seller_buy_ata = create_ata_with_program(
+        &mut svm,
+        &payer,
+        &seller.pubkey(),
+        &mint_buy,
+        &token_program,
+    );
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[with_token_program(ix, token_program)],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    // Seller pays the fee, the auction holds sell_amt
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata), sell_amt);
+    assert!(1e9 as u64 - get_token_balance(&svm, &seller_sell_ata) > sell_amt);
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::unpack(&data).unwrap();
+    assert_eq!(auction.remaining, sell_amt);
+
+    // Buy
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let ix = with_token_program(
+        create_buy_ix(
+            program_id,
+            0,
+            start_price,
+            sell_amt,
+            auction_bump,
+            vec![],
+            Pubkey::default(),
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            buyer_sell_ata,
+            buyer_buy_ata,
+            seller_buy_ata,
+        ),
+        token_program,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    // 100 sell token at 2, buyer pays the fee on the payment
+    let pay_amt = 2e8 as u64;
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), pay_amt);
+    assert!(1e9 as u64 - get_token_balance(&svm, &buyer_buy_ata) > pay_amt);
+    // 1% of the sell token is withheld
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), 99e6 as u64);
+
+    // Withheld fees are harvested before auction_sell_ata is closed
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+
+    let (result_pda, _) = get_result_pda(&program_id, &auction_pda);
+    let data = svm.get_account(&result_pda).unwrap().data;
+    let result = AuctionResult::unpack(&data).unwrap();
+    assert_eq!(result.outcome, AuctionResult::SOLD);
+    assert_eq!(result.sold, sell_amt);
+    assert_eq!(result.proceeds, pay_amt);
+}
+
+#[test]
+fn test_vesting_transfer_fee() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Token-2022 sell mint with a 1% transfer fee
+    let mint_sell = create_fee_mint(
+        &mut svm,
+        &payer,
+        &seller.pubkey(),
+        100,
+        u64::MAX,
+        1e9 as u64,
+    );
+    let mint_buy =
+        create_fee_mint(&mut svm, &payer, &buyer.pubkey(), 0, 0, 1e9 as u64);
+    let token_program = token_2022_program();
+    let (auction_pda, auction_bump) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        0,
+    );
+    let seller_sell_ata =
+        get_ata_with_program(&mint_sell, &seller.pubkey(), &token_program);
+    let auction_sell_ata =
+        get_ata_with_program(&mint_sell, &auction_pda, &token_program);
+    let buyer_sell_ata =
+        get_ata_with_program(&mint_sell, &buyer.pubkey(), &token_program);
+    let buyer_buy_ata =
+        get_ata_with_program(&mint_buy, &buyer.pubkey(), &token_program);
+    let seller_buy_ata = create_ata_with_program(
+        &mut svm,
+        &payer,
+        &seller.pubkey(),
+        &mint_buy,
+        &token_program,
+    );
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+    let vest_duration = 100;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            vest_duration,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[with_token_program(ix, token_program)],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    // Buy everything, tokens go to the vesting PDA
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let ix = create_buy_ix(
+        program_id,
+        0,
+        start_price,
+        sell_amt,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[with_token_program(ix, token_program)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    // Only what vesting_sell_ata received after the 1% fee is vested
+    let (vesting_pda, vesting_bump) =
+        get_vesting_pda(&program_id, &auction_pda, &buyer.pubkey());
+    let vesting_sell_ata =
+        get_ata_with_program(&mint_sell, &vesting_pda, &token_program);
+    let received = 99e6 as u64;
+    assert_eq!(get_token_balance(&svm, &vesting_sell_ata), received);
+
+    let data = svm.get_account(&vesting_pda).unwrap().data;
+    let vesting = Vesting::unpack(&data).unwrap();
+    assert_eq!(vesting.total, received);
+
+    let claim = |svm: &mut LiteSVM, t: u64| {
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = t as i64;
+        svm.set_sysvar(&clock);
+        svm.expire_blockhash();
+        let ix = create_claim_ix(
+            program_id,
+            vesting_bump,
+            buyer.pubkey(),
+            mint_sell,
+            vesting_pda,
+        );
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[with_token_program(ix, token_program)],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Half is released, buyer receives it less the 1% fee
+    claim(&mut svm, end_time + vest_duration / 2).unwrap();
+    assert_eq!(get_token_balance(&svm, &vesting_sell_ata), received / 2);
+    assert_eq!(
+        get_token_balance(&svm, &buyer_sell_ata),
+        received / 2 - received / 200
+    );
+
+    // Last claim empties vesting_sell_ata and closes the vesting PDA
+    claim(&mut svm, end_time + vest_duration).unwrap();
+    assert_eq!(
+        get_token_balance(&svm, &buyer_sell_ata),
+        received - received / 100
+    );
+    assert_eq!(svm.get_balance(&vesting_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&vesting_sell_ata).unwrap_or(0), 0);
+}
+
+#[test]
+fn test_mixed_token_programs() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_buy,
+        seller_buy_ata,
+        buyer_buy_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Token-2022 sell mint, buy mint of the Token program
+    let mint_sell =
+        create_fee_mint(&mut svm, &payer, &seller.pubkey(), 0, 0, 1e9 as u64);
+    let token_program_sell = token_2022_program();
+    let token_program_buy = token_program();
+    let (auction_pda, auction_bump) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        0,
+    );
+    let seller_sell_ata =
+        get_ata_with_program(&mint_sell, &seller.pubkey(), &token_program_sell);
+    let auction_sell_ata =
+        get_ata_with_program(&mint_sell, &auction_pda, &token_program_sell);
+    let buyer_sell_ata = create_ata_with_program(
+        &mut svm,
+        &payer,
+        &buyer.pubkey(),
+        &mint_sell,
+        &token_program_sell,
+    );
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+
+    // Check each token program is the program of its mint
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[with_token_program(ix.clone(), token_program_sell)],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_eq!(
+        res.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::IncorrectProgramId
+        )
+    );
+
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[with_token_programs(
+            ix,
+            mint_sell,
+            token_program_sell,
+            mint_buy,
+            token_program_buy,
+        )],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata), sell_amt);
+
+    // Buy half at start_price
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let ix = create_buy_ix(
+        program_id,
+        0,
+        start_price,
+        sell_amt / 2,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[with_token_programs(
+            ix,
+            mint_sell,
+            token_program_sell,
+            mint_buy,
+            token_program_buy,
+        )],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), sell_amt / 2);
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), sell_amt);
+
+    // Cancel only moves the sell token
+    let ix = create_cancel_ix(
+        program_id,
+        0,
+        auction_bump,
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[with_token_program(ix, token_program_sell)],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(
+        get_token_balance(&svm, &seller_sell_ata),
+        1e9 as u64 - sell_amt / 2
+    );
+}
+
+#[test]
+fn test_transfer_hook() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        payer,
+        seller,
+        buyer,
+        mint_buy,
+        seller_buy_ata,
+        buyer_buy_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Token-2022 sell mint with a transfer hook counting its transfers
+    let hook_program = add_program(&mut svm, "../hook/target/deploy/hook.so");
+    let mint_sell = create_hook_mint(
+        &mut svm,
+        &payer,
+        &seller.pubkey(),
+        &hook_program,
+        1e9 as u64,
+    );
+    let hook_accounts = get_hook_accounts(&hook_program, &mint_sell);
+    let token_program_sell = token_2022_program();
+    let (auction_pda, auction_bump) = get_auction_pda(
+        &program_id,
+        &seller.pubkey(),
+        &mint_sell,
+        &mint_buy,
+        0,
+    );
+    let seller_sell_ata =
+        get_ata_with_program(&mint_sell, &seller.pubkey(), &token_program_sell);
+    let auction_sell_ata =
+        get_ata_with_program(&mint_sell, &auction_pda, &token_program_sell);
+    let buyer_sell_ata = create_ata_with_program(
+        &mut svm,
+        &payer,
+        &buyer.pubkey(),
+        &mint_sell,
+        &token_program_sell,
+    );
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let mut ix = with_token_programs(
+        create_init_ix(
+            program_id,
+            InitParams {
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                sell_amt,
+                bump: auction_bump,
+                counter_bump,
+                ..Default::default()
+            },
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            counter_pda,
+            auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+        ),
+        mint_sell,
+        token_program_sell,
+        mint_buy,
+        token_program(),
+    );
+
+    // Check the hook accounts are passed
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix.clone()],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ));
+    assert_eq!(
+        res.unwrap_err().err,
+        TransactionError::InstructionError(
+            0,
+            InstructionError::NotEnoughAccountKeys
+        )
+    );
+
+    ix.accounts.extend(hook_accounts.clone());
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    assert_eq!(get_token_balance(&svm, &auction_sell_ata), sell_amt);
+    assert_eq!(get_hook_count(&svm, &hook_program, &mint_sell), 1);
+
+    // Buy half
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = start_time as i64;
+    svm.set_sysvar(&clock);
+
+    let buy_amt = sell_amt / 2;
+    let mut ix = with_token_programs(
+        create_buy_ix(
+            program_id,
+            0,
+            start_price,
+            buy_amt,
+            auction_bump,
+            vec![],
+            Pubkey::default(),
+            buyer.pubkey(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            buyer_sell_ata,
+            buyer_buy_ata,
+            seller_buy_ata,
+        ),
+        mint_sell,
+        token_program_sell,
+        mint_buy,
+        token_program(),
+    );
+    ix.accounts.extend(hook_accounts.clone());
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), buy_amt);
+    assert_eq!(
+        get_token_balance(&svm, &auction_sell_ata),
+        sell_amt - buy_amt
+    );
+    assert_eq!(get_hook_count(&svm, &hook_program, &mint_sell), 2);
+
+    // Cancel refunds the rest
+    let mut ix = with_token_program(
+        create_cancel_ix(
+            program_id,
+            0,
+            auction_bump,
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+            auction_sell_ata,
+            seller_sell_ata,
+        ),
+        token_program_sell,
+    );
+    ix.accounts.extend(hook_accounts);
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    assert_eq!(
+        get_token_balance(&svm, &seller_sell_ata),
+        1e9 as u64 - buy_amt
+    );
+    assert_eq!(svm.get_balance(&auction_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&auction_sell_ata).unwrap_or(0), 0);
+    assert_eq!(get_hook_count(&svm, &hook_program, &mint_sell), 3);
+}
+
+#[test]
+fn test_get_price() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        auction_pda,
+        auction_bump,
+        auction_sell_ata,
+        counter_pda,
+        counter_bump,
+        ..
+    } = setup(&mut svm);
+
+    // Init
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 1;
+    let end_time = start_time + 10;
+    let start_price = (2.0 * 1e6) as u64;
+    let end_price = (1.5 * 1e6) as u64;
+    let sell_amt = 1e8 as u64;
+
+    let ix = create_init_ix(
+        program_id,
+        InitParams {
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            sell_amt,
+            curve: PriceCurve::Exponential { half_life: 3 },
+            bump: auction_bump,
+            counter_bump,
+            ..Default::default()
+        },
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        counter_pda,
+        auction_pda,
+        auction_sell_ata,
+        seller_sell_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&seller.pubkey()),
+        &[&seller],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    let get_price_ix = |id: u64| {
+        create_get_price_ix(
+            program_id,
+            id,
+            auction_bump,
+            solana_sdk::system_program::id(),
+            seller.pubkey(),
+            mint_sell,
+            mint_buy,
+            auction_pda,
+        )
+    };
+
+    // Check auction_pda, the bump does not match id 1
+    assert!(get_price(&svm, &buyer, get_price_ix(1)).is_err());
+
+    let data = svm.get_account(&auction_pda).unwrap().data;
+    let auction = Auction::unpack(&data).unwrap();
+
+    // Quote matches the price curve
+    let t = start_time + 4;
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = t as i64;
+    svm.set_sysvar(&clock);
+
+    let quote = get_price(&svm, &buyer, get_price_ix(0)).unwrap();
+    assert_eq!(quote.price, price_at(&auction, t).unwrap());
+    assert!(end_price < quote.price && quote.price < start_price);
+    assert_eq!(quote.remaining, sell_amt);
+    assert_eq!(quote.time_left, end_time - t);
+
+    // Simulating does not change the auction
+    assert_eq!(svm.get_account(&auction_pda).unwrap().data, data);
+
+    // Buy at the quoted price
+    let buy_amt = sell_amt / 4;
+    let ix = create_buy_ix(
+        program_id,
+        0,
+        quote.price,
+        buy_amt,
+        auction_bump,
+        vec![],
+        Pubkey::default(),
+        buyer.pubkey(),
+        seller.pubkey(),
+        mint_sell,
+        mint_buy,
+        auction_pda,
+        auction_sell_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        seller_buy_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    let quote = get_price(&svm, &buyer, get_price_ix(0)).unwrap();
+    assert_eq!(quote.remaining, sell_amt - buy_amt);
+
+    // end_price and no time left after end_time
+    clock.unix_timestamp = (end_time + 5) as i64;
+    svm.set_sysvar(&clock);
+
+    let quote = get_price(&svm, &buyer, get_price_ix(0)).unwrap();
+    assert_eq!(quote.price, end_price);
+    assert_eq!(quote.time_left, 0);
+}
+
+#[test]
+fn test_reverse() {
+    let mut svm = LiteSVM::new();
+    let Test {
+        program_id,
+        seller,
+        buyer,
+        mint_sell,
+        mint_buy,
+        seller_sell_ata,
+        seller_buy_ata,
+        buyer_sell_ata,
+        buyer_buy_ata,
+        ..
+    } = setup(&mut svm);
+
+    // Buyer's Counter hands out the ids of reverse auctions
+    let (counter_pda, counter_bump) = Pubkey::find_program_address(
+        &[Counter::SEED_PREFIX, buyer.pubkey().as_ref()],
+        &program_id,
+    );
+    let reverse = |id: u64| {
+        let (reverse_pda, reverse_bump) = get_reverse_pda(
+            &program_id,
+            &buyer.pubkey(),
+            &mint_sell,
+            &mint_buy,
+            id,
+        );
+        (reverse_pda, reverse_bump, get_ata(&mint_buy, &reverse_pda))
+    };
+
+    let now = svm.get_sysvar::<Clock>().unix_timestamp as u64;
+    let start_time = now + 10;
+    let end_time = start_time + 100;
+    // Price rises from 1 to 2 buy token per sell token
+    let start_price = 1e6 as u64;
+    let end_price = (2.0 * 1e6) as u64;
+    let buy_amt = 1e8 as u64;
+
+    let init = |svm: &mut LiteSVM, id: u64, start_price: u64| {
+        let (reverse_pda, reverse_bump, reverse_buy_ata) = reverse(id);
+        let ix = create_reverse_init_ix(
+            program_id,
+            id,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            buy_amt,
+            reverse_bump,
+            counter_bump,
+            buyer.pubkey(),
+            mint_sell,
+            mint_buy,
+            counter_pda,
+            reverse_pda,
+            reverse_buy_ata,
+            buyer_buy_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&buyer.pubkey()),
+            &[&buyer],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    let accept = |svm: &mut LiteSVM, id: u64, min_price: u64, sell_amt: u64| {
+        let (reverse_pda, reverse_bump, reverse_buy_ata) = reverse(id);
+        let ix = create_accept_ix(
+            program_id,
+            id,
+            min_price,
+            sell_amt,
+            reverse_bump,
+            seller.pubkey(),
+            buyer.pubkey(),
+            mint_sell,
+            mint_buy,
+            reverse_pda,
+            reverse_buy_ata,
+            seller_sell_ata,
+            seller_buy_ata,
+            buyer_sell_ata,
+            buyer_buy_ata,
+        );
+        svm.expire_blockhash();
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&seller.pubkey()),
+            &[&seller],
+            svm.latest_blockhash(),
+        ))
+        .map_err(Box::new)
+    };
+
+    // Check start_price <= end_price
+    assert_eq!(
+        get_error(init(&mut svm, 0, end_price + 1)),
+        Some(AuctionError::InvalidPrices)
+    );
+
+    // Buyer escrows the payment for buy_amt at end_price
+    init(&mut svm, 0, start_price).unwrap();
+
+    let (reverse_pda, _, reverse_buy_ata) = reverse(0);
+    let data = svm.get_account(&reverse_pda).unwrap().data;
+    let auction = ReverseAuction::unpack(&data).unwrap();
+    assert_eq!(auction.buyer, buyer.pubkey());
+    assert_eq!(auction.mint_sell, mint_sell);
+    assert_eq!(auction.mint_buy, mint_buy);
+    assert_eq!(auction.id, 0);
+    assert_eq!(auction.buy_amt, buy_amt);
+    assert_eq!(auction.remaining, buy_amt);
+    assert_eq!(get_token_balance(&svm, &reverse_buy_ata), 2e8 as u64);
+    assert_eq!(get_token_balance(&svm, &buyer_buy_ata), 8e8 as u64);
+
+    // Check auction has started
+    assert_eq!(
+        get_error(accept(&mut svm, 0, 0, buy_amt)),
+        Some(AuctionError::AuctionNotStarted)
+    );
+
+    // Price = 1.5 buy token per sell token
+    let mut clock = svm.get_sysvar::<Clock>();
+    clock.unix_timestamp = (start_time + 50) as i64;
+    svm.set_sysvar(&clock);
+    let price = reverse_price_at(&auction, start_time + 50).unwrap();
+    assert_eq!(price, (1.5 * 1e6) as u64);
+
+    // Check price >= min_price
+    assert_eq!(
+        get_error(accept(&mut svm, 0, price + 1, buy_amt)),
+        Some(AuctionError::MinPrice)
+    );
+    // Check 0 < sell_amt <= remaining
+    assert_eq!(
+        get_error(accept(&mut svm, 0, price, 0)),
+        Some(AuctionError::InvalidSellAmount)
+    );
+    assert_eq!(
+        get_error(accept(&mut svm, 0, price, buy_amt + 1)),
+        Some(AuctionError::InvalidSellAmount)
+    );
+
+    // Seller delivers 2/5
+    accept(&mut svm, 0, price, 4e7 as u64).unwrap();
+
+    let data = svm.get_account(&reverse_pda).unwrap().data;
+    let auction = ReverseAuction::unpack(&data).unwrap();
+    assert_eq!(auction.remaining, 6e7 as u64);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), 4e7 as u64);
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), 6e7 as u64);
+    assert_eq!(get_token_balance(&svm, &reverse_buy_ata), 1.4e8 as u64);
+
+    // Price = 1.75, seller delivers the rest and the auction closes
+    clock.unix_timestamp = (start_time + 75) as i64;
+    svm.set_sysvar(&clock);
+    accept(&mut svm, 0, (1.75 * 1e6) as u64, 6e7 as u64).unwrap();
+
+    assert_eq!(svm.get_balance(&reverse_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&reverse_buy_ata).unwrap_or(0), 0);
+    assert_eq!(get_token_balance(&svm, &buyer_sell_ata), buy_amt);
+    assert_eq!(get_token_balance(&svm, &seller_buy_ata), 1.65e8 as u64);
+    // Rest of the escrow is refunded
+    assert_eq!(get_token_balance(&svm, &buyer_buy_ata), 8.35e8 as u64);
+
+    // Second auction, nobody accepts before end_time
+    clock.unix_timestamp = now as i64;
+    svm.set_sysvar(&clock);
+    init(&mut svm, 1, start_price).unwrap();
+    assert_eq!(get_token_balance(&svm, &buyer_buy_ata), 6.35e8 as u64);
+
+    clock.unix_timestamp = end_time as i64;
+    svm.set_sysvar(&clock);
+
+    // Check auction has not ended
+    assert_eq!(
+        get_error(accept(&mut svm, 1, 0, 1)),
+        Some(AuctionError::AuctionEnded)
+    );
+
+    // Buyer cancels and gets the escrow back
+    let (reverse_pda, reverse_bump, reverse_buy_ata) = reverse(1);
+    let ix = create_reverse_cancel_ix(
+        program_id,
+        1,
+        reverse_bump,
+        buyer.pubkey(),
+        mint_sell,
+        mint_buy,
+        reverse_pda,
+        reverse_buy_ata,
+        buyer_buy_ata,
+    );
+    svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        svm.latest_blockhash(),
+    ))
+    .unwrap();
+
+    assert_eq!(svm.get_balance(&reverse_pda).unwrap_or(0), 0);
+    assert_eq!(svm.get_balance(&reverse_buy_ata).unwrap_or(0), 0);
+    assert_eq!(get_token_balance(&svm, &buyer_buy_ata), 8.35e8 as u64);
+}
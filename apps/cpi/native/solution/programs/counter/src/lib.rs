@@ -23,6 +23,7 @@ pub struct Counter {
     pub count: u64,
 }
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
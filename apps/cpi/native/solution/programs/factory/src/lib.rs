@@ -18,6 +18,7 @@ pub enum Cmd {
     Inc,
 }
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
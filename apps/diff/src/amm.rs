@@ -0,0 +1,410 @@
+use litesvm::LiteSVM;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use crate::token::{
+    ata_program, create_ata, create_mint, get_ata, get_balance, get_supply,
+    mint_to, token_program,
+};
+use crate::{Action, App, Rng, Side, anchor_ix_data, exists};
+
+pub const NATIVE_SO: &str = "../amm/native/solution/target/deploy/amm.so";
+pub const ANCHOR_SO: &str = "../amm/anchor/solution/target/deploy/amm.so";
+pub const ANCHOR_PROGRAM_ID: Pubkey =
+    pubkey!("C8ft4mixLcvxcum1JiMMi8SLR8muASoEKvsQG8XQf7JJ");
+
+// Pools are keyed by fee, a small set makes ops hit the same pools
+pub const FEES: [u16; 3] = [0, 30, 500];
+// Tokens minted to every user
+pub const USER_AMOUNT: u64 = 1_000 * (1e6 as u64);
+
+#[derive(Debug)]
+pub enum Op {
+    InitPool {
+        payer: usize,
+        fee: u16,
+        max_price_impact_bps: u16,
+        max_fee: u16,
+    },
+    AddLiquidity {
+        payer: usize,
+        fee: u16,
+        amount_a: u64,
+        amount_b: u64,
+    },
+    RemoveLiquidity {
+        payer: usize,
+        fee: u16,
+        shares: u64,
+        min_amount_a: u64,
+        min_amount_b: u64,
+    },
+    Swap {
+        payer: usize,
+        fee: u16,
+        a_for_b: bool,
+        amount_in: u64,
+        min_amount_out: u64,
+    },
+}
+
+struct Pool {
+    addr: Pubkey,
+    bump: u8,
+    mint_pool: Pubkey,
+    mint_pool_bump: u8,
+    pool_a: Pubkey,
+    pool_b: Pubkey,
+}
+
+pub struct Amm {
+    side: Side,
+    program_id: Pubkey,
+    users: Vec<Pubkey>,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+}
+
+impl Amm {
+    fn get_pool(&self, fee: u16) -> Pool {
+        let (addr, bump) = Pubkey::find_program_address(
+            &[
+                b"pool_auth",
+                self.mint_a.as_ref(),
+                self.mint_b.as_ref(),
+                &fee.to_le_bytes(),
+            ],
+            &self.program_id,
+        );
+        let (mint_pool, mint_pool_bump) = Pubkey::find_program_address(
+            &[
+                b"pool_mint",
+                self.mint_a.as_ref(),
+                self.mint_b.as_ref(),
+                &fee.to_le_bytes(),
+            ],
+            &self.program_id,
+        );
+        Pool {
+            addr,
+            bump,
+            mint_pool,
+            mint_pool_bump,
+            pool_a: get_ata(&self.mint_a, &addr),
+            pool_b: get_ata(&self.mint_b, &addr),
+        }
+    }
+
+    // Accounts shared by both sides, in the order both programs read them
+    fn accounts(&self, payer: &Pubkey, pool: &Pool) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(pool.addr, false),
+            AccountMeta::new_readonly(self.mint_a, false),
+            AccountMeta::new_readonly(self.mint_b, false),
+            AccountMeta::new(pool.pool_a, false),
+            AccountMeta::new(pool.pool_b, false),
+        ]
+    }
+
+    fn payer_accounts(&self, payer: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(get_ata(&self.mint_a, payer), false),
+            AccountMeta::new(get_ata(&self.mint_b, payer), false),
+        ]
+    }
+
+    fn programs(&self, rent: bool) -> Vec<AccountMeta> {
+        let mut accounts = vec![
+            AccountMeta::new_readonly(token_program(), false),
+            AccountMeta::new_readonly(ata_program(), false),
+            AccountMeta::new_readonly(solana_sdk::system_program::id(), false),
+        ];
+        if rent {
+            accounts.push(AccountMeta::new_readonly(
+                solana_sdk::sysvar::rent::id(),
+                false,
+            ));
+        }
+        accounts
+    }
+}
+
+impl App for Amm {
+    const NAME: &'static str = "amm";
+
+    type Op = Op;
+
+    fn setup(svm: &mut LiteSVM, side: Side, users: &[Keypair]) -> Self {
+        let program_id = match side {
+            Side::Native => {
+                let program_id = Pubkey::new_unique();
+                svm.add_program_from_file(program_id, NATIVE_SO).unwrap();
+                program_id
+            }
+            Side::Anchor => {
+                svm.add_program_from_file(ANCHOR_PROGRAM_ID, ANCHOR_SO)
+                    .unwrap();
+                ANCHOR_PROGRAM_ID
+            }
+        };
+
+        let mint_a = create_mint(svm, &users[0]);
+        let mint_b = create_mint(svm, &users[0]);
+
+        for user in users {
+            for mint in [&mint_a, &mint_b] {
+                let ata = create_ata(svm, &users[0], &user.pubkey(), mint);
+                mint_to(svm, &users[0], mint, &ata, USER_AMOUNT);
+            }
+        }
+
+        Self {
+            side,
+            program_id,
+            users: users.iter().map(|u| u.pubkey()).collect(),
+            mint_a,
+            mint_b,
+        }
+    }
+
+    fn gen_op(rng: &mut Rng) -> Op {
+        let amount = |rng: &mut Rng| {
+            rng.pick(&[0, 1, 1_000, 1_000_000, 100 * (1e6 as u64)])
+                + rng.range(0, 1_000)
+        };
+
+        match rng.range(0, 10) {
+            0 => Op::InitPool {
+                payer: rng.user(),
+                fee: rng.pick(&FEES),
+                max_price_impact_bps: rng.pick(&[0, 1_000, 10_000]),
+                max_fee: rng.pick(&[0, 1_000, 5_000]),
+            },
+            1..4 => Op::AddLiquidity {
+                payer: rng.user(),
+                fee: rng.pick(&FEES),
+                amount_a: amount(rng),
+                amount_b: amount(rng),
+            },
+            4..6 => Op::RemoveLiquidity {
+                payer: rng.user(),
+                fee: rng.pick(&FEES),
+                shares: amount(rng),
+                min_amount_a: rng.pick(&[0, 1_000]),
+                min_amount_b: rng.pick(&[0, 1_000]),
+            },
+            _ => Op::Swap {
+                payer: rng.user(),
+                fee: rng.pick(&FEES),
+                a_for_b: rng.bool(),
+                amount_in: amount(rng),
+                min_amount_out: rng.pick(&[0, 1_000]),
+            },
+        }
+    }
+
+    fn action(&self, op: &Op, users: &[Keypair]) -> Action {
+        let (payer, program_accounts, data) = match *op {
+            Op::InitPool {
+                payer,
+                fee,
+                max_price_impact_bps,
+                max_fee,
+            } => {
+                let pool = self.get_pool(fee);
+                let mut accounts = self.accounts(&self.users[payer], &pool);
+                accounts.push(AccountMeta::new(pool.mint_pool, false));
+
+                let data = match self.side {
+                    Side::Native => {
+                        accounts.extend(self.programs(true));
+                        borsh::to_vec(&amm::Cmd::InitPool {
+                            fee,
+                            max_price_impact_bps,
+                            max_fee,
+                            pool_bump: pool.bump,
+                            mint_pool_bump: pool.mint_pool_bump,
+                        })
+                        .unwrap()
+                    }
+                    Side::Anchor => {
+                        accounts.extend(self.programs(false));
+                        anchor_ix_data(
+                            "init_pool",
+                            &(fee, max_price_impact_bps, max_fee),
+                        )
+                    }
+                };
+                (payer, accounts, data)
+            }
+            Op::AddLiquidity {
+                payer,
+                fee,
+                amount_a,
+                amount_b,
+            } => {
+                let pool = self.get_pool(fee);
+                let payer_key = self.users[payer];
+                let mut accounts = self.accounts(&payer_key, &pool);
+                accounts.push(AccountMeta::new(pool.mint_pool, false));
+                accounts.extend(self.payer_accounts(&payer_key));
+                accounts.push(AccountMeta::new(
+                    get_ata(&pool.mint_pool, &payer_key),
+                    false,
+                ));
+
+                let data = match self.side {
+                    Side::Native => {
+                        accounts.extend(self.programs(true));
+                        borsh::to_vec(&amm::Cmd::AddLiquidity {
+                            fee,
+                            amount_a,
+                            amount_b,
+                            pool_bump: pool.bump,
+                            mint_pool_bump: pool.mint_pool_bump,
+                        })
+                        .unwrap()
+                    }
+                    Side::Anchor => {
+                        accounts.extend(self.programs(false));
+                        anchor_ix_data(
+                            "add_liquidity",
+                            &(fee, amount_a, amount_b),
+                        )
+                    }
+                };
+                (payer, accounts, data)
+            }
+            Op::RemoveLiquidity {
+                payer,
+                fee,
+                shares,
+                min_amount_a,
+                min_amount_b,
+            } => {
+                let pool = self.get_pool(fee);
+                let payer_key = self.users[payer];
+                let mut accounts = self.accounts(&payer_key, &pool);
+                accounts.push(AccountMeta::new(pool.mint_pool, false));
+                accounts.extend(self.payer_accounts(&payer_key));
+                accounts.push(AccountMeta::new(
+                    get_ata(&pool.mint_pool, &payer_key),
+                    false,
+                ));
+
+                let data = match self.side {
+                    Side::Native => {
+                        accounts.push(AccountMeta::new_readonly(
+                            token_program(),
+                            false,
+                        ));
+                        borsh::to_vec(&amm::Cmd::RemoveLiquidity {
+                            fee,
+                            shares,
+                            min_amount_a,
+                            min_amount_b,
+                            pool_bump: pool.bump,
+                            mint_pool_bump: pool.mint_pool_bump,
+                        })
+                        .unwrap()
+                    }
+                    Side::Anchor => {
+                        accounts.extend(self.programs(false));
+                        anchor_ix_data(
+                            "remove_liquidity",
+                            &(fee, shares, min_amount_a, min_amount_b),
+                        )
+                    }
+                };
+                (payer, accounts, data)
+            }
+            Op::Swap {
+                payer,
+                fee,
+                a_for_b,
+                amount_in,
+                min_amount_out,
+            } => {
+                let pool = self.get_pool(fee);
+                let payer_key = self.users[payer];
+                let mut accounts = self.accounts(&payer_key, &pool);
+                accounts.extend(self.payer_accounts(&payer_key));
+
+                let data = match self.side {
+                    Side::Native => {
+                        accounts.push(AccountMeta::new_readonly(
+                            token_program(),
+                            false,
+                        ));
+                        borsh::to_vec(&amm::Cmd::Swap {
+                            fee,
+                            a_for_b,
+                            amount_in,
+                            min_amount_out,
+                            pool_bump: pool.bump,
+                        })
+                        .unwrap()
+                    }
+                    Side::Anchor => {
+                        accounts.extend(self.programs(false));
+                        anchor_ix_data(
+                            "swap",
+                            &(fee, a_for_b, amount_in, min_amount_out),
+                        )
+                    }
+                };
+                (payer, accounts, data)
+            }
+        };
+
+        Action::Tx {
+            ixs: vec![Instruction {
+                program_id: self.program_id,
+                accounts: program_accounts,
+                data,
+            }],
+            signers: vec![users[payer].insecure_clone()],
+        }
+    }
+
+    fn observe(&self, svm: &LiteSVM) -> Vec<(String, u64)> {
+        let mut obs = Vec::new();
+
+        for fee in FEES {
+            let pool = self.get_pool(fee);
+            let key = format!("pool[{fee}]");
+            obs.push((format!("{key}.exists"), exists(svm, &pool.addr)));
+            obs.push((format!("{key}.a"), get_balance(svm, &pool.pool_a)));
+            obs.push((format!("{key}.b"), get_balance(svm, &pool.pool_b)));
+            obs.push((
+                format!("{key}.supply"),
+                get_supply(svm, &pool.mint_pool),
+            ));
+
+            for (i, user) in self.users.iter().enumerate() {
+                obs.push((
+                    format!("{key}.user[{i}].shares"),
+                    get_balance(svm, &get_ata(&pool.mint_pool, user)),
+                ));
+            }
+        }
+
+        for (i, user) in self.users.iter().enumerate() {
+            obs.push((
+                format!("user[{i}].a"),
+                get_balance(svm, &get_ata(&self.mint_a, user)),
+            ));
+            obs.push((
+                format!("user[{i}].b"),
+                get_balance(svm, &get_ata(&self.mint_b, user)),
+            ));
+        }
+
+        obs
+    }
+}
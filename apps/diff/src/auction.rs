@@ -0,0 +1,665 @@
+use litesvm::LiteSVM;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+use auction::state::PriceCurve;
+
+use crate::token::{
+    ata_program, create_ata, create_mint, get_ata, get_balance, mint_to,
+    token_program,
+};
+use crate::{
+    Action, App, Rng, START_TIME, Side, anchor_ix_data, excess_lamports,
+    exists, read_u64,
+};
+
+pub const NATIVE_SO: &str =
+    "../auction/native/solution/target/deploy/auction.so";
+pub const ANCHOR_SO: &str =
+    "../auction/anchor/solution/target/deploy/auction.so";
+pub const ANCHOR_PROGRAM_ID: Pubkey =
+    pubkey!("Hp6iqFudQ9vr2Rz9cdXTk2gCHf4eu6Zr8jLyWe6vsPiL");
+// Owner of the ProgramData account holding the upgrade authority
+pub const BPF_LOADER_UPGRADEABLE: Pubkey =
+    pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+// Tokens minted to every user
+pub const USER_AMOUNT: u64 = 1_000 * (1e6 as u64);
+// Auction ids used per seller
+pub const NUM_IDS: u64 = 3;
+// Receives the protocol fee
+pub const TREASURY: Pubkey = Pubkey::new_from_array([7; 32]);
+// Fee set by users[0], the admin, in setup
+pub const INIT_FEE_BPS: u64 = 30;
+
+#[derive(Debug)]
+pub enum Op {
+    Init {
+        seller: usize,
+        id: u64,
+        start_price: u64,
+        end_price: u64,
+        start_time: u64,
+        end_time: u64,
+        sell_amt: u64,
+        curve: PriceCurve,
+    },
+    Buy {
+        buyer: usize,
+        seller: usize,
+        id: u64,
+        max_price: u64,
+        buy_amt: u64,
+    },
+    Cancel {
+        seller: usize,
+        id: u64,
+    },
+    SetFee {
+        admin: usize,
+        fee_bps: u64,
+    },
+    Update {
+        seller: usize,
+        id: u64,
+        start_price: u64,
+        end_price: u64,
+        start_time: u64,
+        end_time: u64,
+        sell_amt: u64,
+    },
+    Warp(i64),
+}
+
+pub struct Auction {
+    side: Side,
+    program_id: Pubkey,
+    users: Vec<Pubkey>,
+    mint_sell: Pubkey,
+    mint_buy: Pubkey,
+}
+
+impl Auction {
+    fn get_pda(&self, seller: &Pubkey, id: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                b"auction",
+                seller.as_ref(),
+                self.mint_sell.as_ref(),
+                self.mint_buy.as_ref(),
+                &id.to_le_bytes(),
+            ],
+            &self.program_id,
+        )
+    }
+
+    fn get_counter_pda(&self, seller: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"counter", seller.as_ref()],
+            &self.program_id,
+        )
+    }
+
+    fn get_config_pda(&self) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"config"], &self.program_id)
+    }
+
+    fn get_result_pda(&self, pda: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"result", pda.as_ref()],
+            &self.program_id,
+        )
+    }
+
+    fn data<T: borsh::BorshSerialize>(
+        &self,
+        cmd: &auction::Cmd,
+        name: &str,
+        args: &T,
+    ) -> Vec<u8> {
+        match self.side {
+            Side::Native => borsh::to_vec(cmd).unwrap(),
+            Side::Anchor => anchor_ix_data(name, args),
+        }
+    }
+}
+
+impl App for Auction {
+    const NAME: &'static str = "auction";
+
+    type Op = Op;
+
+    fn setup(svm: &mut LiteSVM, side: Side, users: &[Keypair]) -> Self {
+        let program_id = match side {
+            Side::Native => {
+                let program_id = Pubkey::new_unique();
+                svm.add_program_from_file(program_id, NATIVE_SO).unwrap();
+                program_id
+            }
+            Side::Anchor => {
+                svm.add_program_from_file(ANCHOR_PROGRAM_ID, ANCHOR_SO)
+                    .unwrap();
+                ANCHOR_PROGRAM_ID
+            }
+        };
+
+        // LiteSVM loads programs without a ProgramData account, set one with
+        // users[0] as the upgrade authority so that it can create the config
+        let (program_data, _) = Pubkey::find_program_address(
+            &[program_id.as_ref()],
+            &BPF_LOADER_UPGRADEABLE,
+        );
+        let mut data = vec![3, 0, 0, 0];
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(users[0].pubkey().as_ref());
+        svm.set_account(
+            program_data,
+            solana_sdk::account::Account {
+                lamports: svm.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: BPF_LOADER_UPGRADEABLE,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let mint_sell = create_mint(svm, &users[0]);
+        let mint_buy = create_mint(svm, &users[0]);
+
+        for user in users {
+            for mint in [&mint_sell, &mint_buy] {
+                let ata = create_ata(svm, &users[0], &user.pubkey(), mint);
+                mint_to(svm, &users[0], mint, &ata, USER_AMOUNT);
+            }
+        }
+
+        let app = Self {
+            side,
+            program_id,
+            users: users.iter().map(|u| u.pubkey()).collect(),
+            mint_sell,
+            mint_buy,
+        };
+
+        // Config with users[0] as admin
+        let (config_pda, bump) = app.get_config_pda();
+        let ix = Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(users[0].pubkey(), true),
+                AccountMeta::new_readonly(TREASURY, false),
+                AccountMeta::new(config_pda, false),
+                AccountMeta::new_readonly(
+                    solana_sdk::system_program::id(),
+                    false,
+                ),
+                AccountMeta::new_readonly(program_data, false),
+                // No oracle program, Anchor ignores it
+                AccountMeta::new_readonly(Pubkey::default(), false),
+            ],
+            data: app.data(
+                &auction::Cmd::InitConfig {
+                    fee_bps: INIT_FEE_BPS,
+                    bump,
+                },
+                "init_config",
+                &INIT_FEE_BPS,
+            ),
+        };
+        svm.send_transaction(Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&users[0].pubkey()),
+            &[&users[0]],
+            svm.latest_blockhash(),
+        ))
+        .unwrap();
+
+        app
+    }
+
+    fn gen_op(rng: &mut Rng) -> Op {
+        match rng.range(0, 10) {
+            0..3 => {
+                let start_time = START_TIME as u64 + rng.range(0, 200);
+                Op::Init {
+                    seller: rng.user(),
+                    id: rng.range(0, NUM_IDS),
+                    start_price: rng.range(0, 2_000_000),
+                    end_price: rng.range(0, 2_000_000),
+                    start_time,
+                    end_time: start_time + rng.range(0, 200),
+                    sell_amt: rng.pick(&[0, 1, 1_000_000, 100_000_000]),
+                    curve: match rng.range(0, 3) {
+                        0 => PriceCurve::Linear,
+                        1 => PriceCurve::Exponential {
+                            half_life: rng.pick(&[0, 1, 30]),
+                        },
+                        _ => PriceCurve::Stepped {
+                            step: rng.pick(&[0, 1, 30]),
+                        },
+                    },
+                }
+            }
+            3..6 => Op::Buy {
+                buyer: rng.user(),
+                seller: rng.user(),
+                id: rng.range(0, NUM_IDS),
+                max_price: rng.range(0, 2_000_000),
+                buy_amt: rng.pick(&[0, 1, 1_000_000, 50_000_000, 100_000_000]),
+            },
+            6 => Op::Cancel {
+                seller: rng.user(),
+                id: rng.range(0, NUM_IDS),
+            },
+            7 => Op::SetFee {
+                admin: rng.user(),
+                fee_bps: rng.pick(&[0, 30, 1_000, 1_001]),
+            },
+            8 => {
+                let start_time = START_TIME as u64 + rng.range(0, 200);
+                Op::Update {
+                    seller: rng.user(),
+                    id: rng.range(0, NUM_IDS),
+                    start_price: rng.range(0, 2_000_000),
+                    end_price: rng.range(0, 2_000_000),
+                    start_time,
+                    end_time: start_time + rng.range(0, 200),
+                    sell_amt: rng.pick(&[0, 1, 1_000_000, 100_000_000]),
+                }
+            }
+            _ => Op::Warp(rng.range(0, 60) as i64),
+        }
+    }
+
+    fn action(&self, op: &Op, users: &[Keypair]) -> Action {
+        let (signer, accounts, data) = match *op {
+            Op::Init {
+                seller,
+                id,
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                sell_amt,
+                curve,
+            } => {
+                let seller_key = self.users[seller];
+                let (pda, bump) = self.get_pda(&seller_key, id);
+                let (counter_pda, counter_bump) =
+                    self.get_counter_pda(&seller_key);
+
+                let mut accounts = vec![
+                    AccountMeta::new(seller_key, true),
+                    AccountMeta::new_readonly(self.mint_sell, false),
+                    AccountMeta::new_readonly(self.mint_buy, false),
+                    AccountMeta::new(counter_pda, false),
+                    AccountMeta::new(pda, false),
+                    AccountMeta::new(get_ata(&self.mint_sell, &pda), false),
+                    AccountMeta::new(
+                        get_ata(&self.mint_sell, &seller_key),
+                        false,
+                    ),
+                ];
+                if self.side == Side::Anchor {
+                    accounts.push(AccountMeta::new(
+                        get_ata(&self.mint_buy, &seller_key),
+                        false,
+                    ));
+                }
+                // token_program_sell and token_program_buy
+                accounts.extend([
+                    AccountMeta::new_readonly(token_program(), false),
+                    AccountMeta::new_readonly(token_program(), false),
+                ]);
+                accounts.extend([
+                    AccountMeta::new_readonly(ata_program(), false),
+                    AccountMeta::new_readonly(
+                        solana_sdk::system_program::id(),
+                        false,
+                    ),
+                ]);
+                // Native takes the batch PDA and mint_buy ATA, only used by
+                // batch auctions, then the Config holding the oracle program
+                if self.side == Side::Native {
+                    accounts.extend([
+                        AccountMeta::new_readonly(
+                            solana_sdk::sysvar::rent::id(),
+                            false,
+                        ),
+                        AccountMeta::new_readonly(
+                            solana_sdk::system_program::id(),
+                            false,
+                        ),
+                        AccountMeta::new_readonly(
+                            solana_sdk::system_program::id(),
+                            false,
+                        ),
+                        AccountMeta::new_readonly(
+                            self.get_config_pda().0,
+                            false,
+                        ),
+                    ]);
+                }
+
+                let data = self.data(
+                    &auction::Cmd::Init(auction::InitParams {
+                        id,
+                        start_price,
+                        end_price,
+                        start_time,
+                        end_time,
+                        sell_amt,
+                        curve,
+                        bump,
+                        counter_bump,
+                        ..Default::default()
+                    }),
+                    "init",
+                    &(
+                        id,
+                        start_price,
+                        end_price,
+                        start_time,
+                        end_time,
+                        sell_amt,
+                        curve,
+                    ),
+                );
+                (seller, accounts, data)
+            }
+            Op::Buy {
+                buyer,
+                seller,
+                id,
+                max_price,
+                buy_amt,
+            } => {
+                let buyer_key = self.users[buyer];
+                let seller_key = self.users[seller];
+                let (pda, bump) = self.get_pda(&seller_key, id);
+
+                let buyer_sell_ata = AccountMeta::new(
+                    get_ata(&self.mint_sell, &buyer_key),
+                    false,
+                );
+                let buyer_buy_ata = AccountMeta::new(
+                    get_ata(&self.mint_buy, &buyer_key),
+                    false,
+                );
+
+                let mut accounts = vec![
+                    AccountMeta::new(buyer_key, true),
+                    AccountMeta::new(seller_key, false),
+                    // Writable to harvest withheld Token-2022 transfer fees
+                    AccountMeta::new(self.mint_sell, false),
+                    AccountMeta::new_readonly(self.mint_buy, false),
+                    AccountMeta::new(pda, false),
+                    AccountMeta::new(get_ata(&self.mint_sell, &pda), false),
+                ];
+                // Anchor reads buyer_buy_ata before buyer_sell_ata
+                match self.side {
+                    Side::Native => {
+                        accounts.extend([buyer_sell_ata, buyer_buy_ata])
+                    }
+                    Side::Anchor => {
+                        accounts.extend([buyer_buy_ata, buyer_sell_ata])
+                    }
+                }
+                accounts.push(AccountMeta::new(
+                    get_ata(&self.mint_buy, &seller_key),
+                    false,
+                ));
+                // Receives what is left in the auction's ATA when the
+                // auction sells out
+                let seller_sell_ata = AccountMeta::new(
+                    get_ata(&self.mint_sell, &seller_key),
+                    false,
+                );
+                if self.side == Side::Anchor {
+                    accounts.push(seller_sell_ata.clone());
+                }
+                // token_program_sell and token_program_buy
+                accounts.extend([
+                    AccountMeta::new_readonly(token_program(), false),
+                    AccountMeta::new_readonly(token_program(), false),
+                ]);
+                if self.side == Side::Anchor {
+                    accounts
+                        .push(AccountMeta::new_readonly(ata_program(), false));
+                }
+                accounts.push(AccountMeta::new_readonly(
+                    solana_sdk::system_program::id(),
+                    false,
+                ));
+                // Purchase account is only read for auctions with a wallet
+                // cap, which the harness does not create
+                if self.side == Side::Native {
+                    accounts.push(AccountMeta::new_readonly(
+                        solana_sdk::system_program::id(),
+                        false,
+                    ));
+                }
+                let (config_pda, config_bump) = self.get_config_pda();
+                accounts.extend([
+                    AccountMeta::new_readonly(config_pda, false),
+                    AccountMeta::new(TREASURY, false),
+                    AccountMeta::new(get_ata(&self.mint_buy, &TREASURY), false),
+                ]);
+                // Oracle is only read for oracle pegged auctions and vesting
+                // for auctions with vesting. Native records the outcome of
+                // the auction when it sells out.
+                let (result_pda, result_bump) = self.get_result_pda(&pda);
+                if self.side == Side::Native {
+                    accounts.extend([
+                        AccountMeta::new_readonly(ata_program(), false),
+                        AccountMeta::new_readonly(
+                            solana_sdk::system_program::id(),
+                            false,
+                        ),
+                        AccountMeta::new(result_pda, false),
+                        seller_sell_ata,
+                        AccountMeta::new_readonly(
+                            solana_sdk::system_program::id(),
+                            false,
+                        ),
+                        AccountMeta::new_readonly(
+                            solana_sdk::system_program::id(),
+                            false,
+                        ),
+                    ]);
+                }
+
+                let data = self.data(
+                    &auction::Cmd::Buy {
+                        id,
+                        max_price,
+                        buy_amt,
+                        bump,
+                        purchase_bump: 0,
+                        config_bump,
+                        result_bump,
+                        vesting_bump: 0,
+                        proof: vec![],
+                    },
+                    "buy",
+                    &(id, max_price, buy_amt),
+                );
+                (buyer, accounts, data)
+            }
+            Op::Cancel { seller, id } => {
+                let seller_key = self.users[seller];
+                let (pda, bump) = self.get_pda(&seller_key, id);
+
+                let mut accounts = vec![
+                    AccountMeta::new(seller_key, true),
+                    // Writable to harvest withheld Token-2022 transfer fees
+                    AccountMeta::new(self.mint_sell, false),
+                    AccountMeta::new_readonly(self.mint_buy, false),
+                    AccountMeta::new(pda, false),
+                    AccountMeta::new(get_ata(&self.mint_sell, &pda), false),
+                    AccountMeta::new(
+                        get_ata(&self.mint_sell, &seller_key),
+                        false,
+                    ),
+                    AccountMeta::new_readonly(token_program(), false),
+                ];
+                if self.side == Side::Anchor {
+                    accounts
+                        .push(AccountMeta::new_readonly(ata_program(), false));
+                }
+                accounts.push(AccountMeta::new_readonly(
+                    solana_sdk::system_program::id(),
+                    false,
+                ));
+                // Native records the cancellation
+                let (result_pda, result_bump) = self.get_result_pda(&pda);
+                if self.side == Side::Native {
+                    accounts.push(AccountMeta::new(result_pda, false));
+                }
+
+                let data = self.data(
+                    &auction::Cmd::Cancel {
+                        id,
+                        bump,
+                        result_bump,
+                    },
+                    "cancel",
+                    &id,
+                );
+                (seller, accounts, data)
+            }
+            Op::SetFee { admin, fee_bps } => {
+                let (config_pda, bump) = self.get_config_pda();
+
+                let accounts = vec![
+                    AccountMeta::new(self.users[admin], true),
+                    AccountMeta::new(config_pda, false),
+                ];
+
+                let data = self.data(
+                    &auction::Cmd::SetFee { fee_bps, bump },
+                    "set_fee",
+                    &fee_bps,
+                );
+                (admin, accounts, data)
+            }
+            Op::Update {
+                seller,
+                id,
+                start_price,
+                end_price,
+                start_time,
+                end_time,
+                sell_amt,
+            } => {
+                let seller_key = self.users[seller];
+                let (pda, bump) = self.get_pda(&seller_key, id);
+
+                let accounts = vec![
+                    AccountMeta::new(seller_key, true),
+                    AccountMeta::new_readonly(self.mint_sell, false),
+                    AccountMeta::new_readonly(self.mint_buy, false),
+                    AccountMeta::new(pda, false),
+                    AccountMeta::new(get_ata(&self.mint_sell, &pda), false),
+                    AccountMeta::new(
+                        get_ata(&self.mint_sell, &seller_key),
+                        false,
+                    ),
+                    AccountMeta::new_readonly(token_program(), false),
+                ];
+
+                let data = self.data(
+                    &auction::Cmd::Update {
+                        id,
+                        start_price,
+                        end_price,
+                        start_time,
+                        end_time,
+                        sell_amt,
+                        bump,
+                    },
+                    "update",
+                    &(
+                        id,
+                        start_price,
+                        end_price,
+                        start_time,
+                        end_time,
+                        sell_amt,
+                    ),
+                );
+                (seller, accounts, data)
+            }
+            Op::Warp(secs) => return Action::Warp(secs),
+        };
+
+        Action::Tx {
+            ixs: vec![Instruction {
+                program_id: self.program_id,
+                accounts,
+                data,
+            }],
+            signers: vec![users[signer].insecure_clone()],
+        }
+    }
+
+    fn observe(&self, svm: &LiteSVM) -> Vec<(String, u64)> {
+        let mut obs = Vec::new();
+
+        for (i, user) in self.users.iter().enumerate() {
+            let (counter_pda, _) = self.get_counter_pda(user);
+            obs.push((
+                format!("counter[{i}].next_id"),
+                read_u64(svm, &counter_pda, 8),
+            ));
+
+            for id in 0..NUM_IDS {
+                let (pda, _) = self.get_pda(user, id);
+                let key = format!("auction[{i}/{id}]");
+                obs.push((format!("{key}.exists"), exists(svm, &pda)));
+                obs.push((format!("{key}.excess"), excess_lamports(svm, &pda)));
+                obs.push((
+                    format!("{key}.sell"),
+                    get_balance(svm, &get_ata(&self.mint_sell, &pda)),
+                ));
+                // Both sides store the discriminator, remaining is after
+                // 3 pubkeys and 5 u64
+                obs.push((
+                    format!("{key}.remaining"),
+                    read_u64(svm, &pda, 144),
+                ));
+            }
+        }
+
+        // Discriminator and 2 pubkeys before fee_bps
+        let (config_pda, _) = self.get_config_pda();
+        obs.push((
+            "config.fee_bps".to_string(),
+            read_u64(svm, &config_pda, 72),
+        ));
+        obs.push((
+            "treasury.buy".to_string(),
+            get_balance(svm, &get_ata(&self.mint_buy, &TREASURY)),
+        ));
+
+        for (i, user) in self.users.iter().enumerate() {
+            obs.push((
+                format!("user[{i}].sell"),
+                get_balance(svm, &get_ata(&self.mint_sell, user)),
+            ));
+            obs.push((
+                format!("user[{i}].buy"),
+                get_balance(svm, &get_ata(&self.mint_buy, user)),
+            ));
+        }
+
+        obs
+    }
+}
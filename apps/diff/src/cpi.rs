@@ -0,0 +1,185 @@
+use litesvm::LiteSVM;
+use solana_sdk::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    signer::keypair::keypair_from_seed,
+};
+
+use crate::{Action, App, Rng, Side, anchor_ix_data, exists, read_u64};
+
+pub const NATIVE_COUNTER_SO: &str =
+    "../cpi/native/solution/programs/counter/target/deploy/counter.so";
+pub const NATIVE_FACTORY_SO: &str =
+    "../cpi/native/solution/programs/factory/target/deploy/factory.so";
+pub const ANCHOR_COUNTER_SO: &str =
+    "../cpi/anchor/solution/target/deploy/counter.so";
+pub const ANCHOR_FACTORY_SO: &str =
+    "../cpi/anchor/solution/target/deploy/factory.so";
+pub const ANCHOR_COUNTER_PROGRAM_ID: Pubkey =
+    pubkey!("6n5mmdpDHxuyZBkju2cQvrhnfhmQxMemXAss45oJYqW9");
+pub const ANCHOR_FACTORY_PROGRAM_ID: Pubkey =
+    pubkey!("9WUK6RjaUJ2tY5ymQiKHtoXHX1B63F2XfcHbm6FShBGc");
+
+#[derive(Debug)]
+pub enum Op {
+    // Factory -> counter init
+    Init { payer: usize },
+    // Factory -> counter inc
+    Inc { payer: usize },
+    // Call counter inc directly
+    CounterInc { payer: usize },
+}
+
+pub struct Cpi {
+    side: Side,
+    counter_program_id: Pubkey,
+    factory_program_id: Pubkey,
+    // Same counter account on both sides, a second Init must fail
+    counter: Keypair,
+}
+
+impl App for Cpi {
+    const NAME: &'static str = "cpi";
+
+    type Op = Op;
+
+    fn setup(svm: &mut LiteSVM, side: Side, users: &[Keypair]) -> Self {
+        let (counter_program_id, factory_program_id) = match side {
+            Side::Native => {
+                let counter_program_id = Pubkey::new_unique();
+                let factory_program_id = Pubkey::new_unique();
+                svm.add_program_from_file(
+                    counter_program_id,
+                    NATIVE_COUNTER_SO,
+                )
+                .unwrap();
+                svm.add_program_from_file(
+                    factory_program_id,
+                    NATIVE_FACTORY_SO,
+                )
+                .unwrap();
+                (counter_program_id, factory_program_id)
+            }
+            Side::Anchor => {
+                svm.add_program_from_file(
+                    ANCHOR_COUNTER_PROGRAM_ID,
+                    ANCHOR_COUNTER_SO,
+                )
+                .unwrap();
+                svm.add_program_from_file(
+                    ANCHOR_FACTORY_PROGRAM_ID,
+                    ANCHOR_FACTORY_SO,
+                )
+                .unwrap();
+                (ANCHOR_COUNTER_PROGRAM_ID, ANCHOR_FACTORY_PROGRAM_ID)
+            }
+        };
+
+        // Derived from the users so both sides use the same address
+        let counter =
+            keypair_from_seed(&hash(users[0].pubkey().as_ref()).to_bytes())
+                .unwrap();
+
+        Self {
+            side,
+            counter_program_id,
+            factory_program_id,
+            counter,
+        }
+    }
+
+    fn gen_op(rng: &mut Rng) -> Op {
+        match rng.range(0, 5) {
+            0 => Op::Init { payer: rng.user() },
+            1..4 => Op::Inc { payer: rng.user() },
+            _ => Op::CounterInc { payer: rng.user() },
+        }
+    }
+
+    fn action(&self, op: &Op, users: &[Keypair]) -> Action {
+        let counter = self.counter.pubkey();
+
+        match *op {
+            Op::Init { payer } => {
+                let accounts = vec![
+                    AccountMeta::new(users[payer].pubkey(), true),
+                    AccountMeta::new(counter, true),
+                    AccountMeta::new_readonly(self.counter_program_id, false),
+                    AccountMeta::new_readonly(
+                        solana_sdk::system_program::id(),
+                        false,
+                    ),
+                ];
+
+                let data = match self.side {
+                    Side::Native => borsh::to_vec(&factory::Cmd::Init).unwrap(),
+                    Side::Anchor => anchor_ix_data("init", &()),
+                };
+
+                Action::Tx {
+                    ixs: vec![Instruction {
+                        program_id: self.factory_program_id,
+                        accounts,
+                        data,
+                    }],
+                    signers: vec![
+                        users[payer].insecure_clone(),
+                        self.counter.insecure_clone(),
+                    ],
+                }
+            }
+            Op::Inc { payer } => {
+                let accounts = vec![
+                    AccountMeta::new(counter, false),
+                    AccountMeta::new_readonly(self.counter_program_id, false),
+                ];
+
+                let data = match self.side {
+                    Side::Native => borsh::to_vec(&factory::Cmd::Inc).unwrap(),
+                    Side::Anchor => anchor_ix_data("inc", &()),
+                };
+
+                Action::Tx {
+                    ixs: vec![Instruction {
+                        program_id: self.factory_program_id,
+                        accounts,
+                        data,
+                    }],
+                    signers: vec![users[payer].insecure_clone()],
+                }
+            }
+            Op::CounterInc { payer } => {
+                let data = match self.side {
+                    Side::Native => borsh::to_vec(&counter::Cmd::Inc).unwrap(),
+                    Side::Anchor => anchor_ix_data("inc", &()),
+                };
+
+                Action::Tx {
+                    ixs: vec![Instruction {
+                        program_id: self.counter_program_id,
+                        accounts: vec![AccountMeta::new(counter, false)],
+                        data,
+                    }],
+                    signers: vec![users[payer].insecure_clone()],
+                }
+            }
+        }
+    }
+
+    fn observe(&self, svm: &LiteSVM) -> Vec<(String, u64)> {
+        let counter = self.counter.pubkey();
+        // Counter state starts after the 8 byte discriminator on Anchor
+        let offset = match self.side {
+            Side::Native => 0,
+            Side::Anchor => 8,
+        };
+
+        vec![
+            ("counter.exists".to_string(), exists(svm, &counter)),
+            ("counter.count".to_string(), read_u64(svm, &counter, offset)),
+        ]
+    }
+}
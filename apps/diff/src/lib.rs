@@ -0,0 +1,322 @@
+use litesvm::LiteSVM;
+use solana_sdk::{
+    clock::Clock,
+    hash::hash,
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+    signer::keypair::keypair_from_seed,
+    transaction::Transaction,
+};
+use std::fmt;
+
+pub mod amm;
+pub mod auction;
+pub mod cpi;
+pub mod oracle;
+pub mod piggy;
+pub mod token;
+
+// Clock both programs start from
+pub const START_TIME: i64 = 1_700_000_000;
+pub const NUM_USERS: usize = 3;
+pub const AIRDROP: u64 = 100 * (1e9 as u64);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Native,
+    Anchor,
+}
+
+// What a single step does to the chain
+pub enum Action {
+    // Send a transaction, first signer pays the fees
+    Tx {
+        ixs: Vec<Instruction>,
+        signers: Vec<Keypair>,
+    },
+    // Move the clock forward by some seconds
+    Warp(i64),
+}
+
+// An app implemented both as a native and as an Anchor program.
+// One instance is created per side, ops are generated once and
+// applied to both sides.
+pub trait App: Sized {
+    const NAME: &'static str;
+
+    type Op: fmt::Debug;
+
+    // Load the program(s) for this side and create accounts
+    fn setup(svm: &mut LiteSVM, side: Side, users: &[Keypair]) -> Self;
+
+    // Random op, must not depend on the side
+    fn gen_op(rng: &mut Rng) -> Self::Op;
+
+    // Build the transaction for this side
+    fn action(&self, op: &Self::Op, users: &[Keypair]) -> Action;
+
+    // Labelled values compared between the two sides.
+    // Values that differ by design (account sizes, Anchor discriminators)
+    // must be left out or normalized, for example with excess_lamports.
+    fn observe(&self, svm: &LiteSVM) -> Vec<(String, u64)>;
+}
+
+// xorshift64*, same seed = same op sequence
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // State must not be 0
+        Self(seed ^ 0x9e37_79b9_7f4a_7c15)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // Random number in [lo, hi)
+    pub fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.next_u64() % (hi - lo)
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    pub fn user(&mut self) -> usize {
+        self.range(0, NUM_USERS as u64) as usize
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.range(0, items.len() as u64) as usize]
+    }
+
+    pub fn keypair(&mut self) -> Keypair {
+        let mut seed = [0u8; 32];
+        for chunk in seed.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes());
+        }
+        keypair_from_seed(&seed).unwrap()
+    }
+}
+
+// Anchor instruction data = sha256("global:<name>")[..8] + borsh args
+pub fn anchor_ix_data<T: borsh::BorshSerialize>(
+    name: &str,
+    args: &T,
+) -> Vec<u8> {
+    let mut data =
+        hash(format!("global:{name}").as_bytes()).to_bytes()[..8].to_vec();
+    data.extend(borsh::to_vec(args).unwrap());
+    data
+}
+
+// Lamports held above the rent exempt minimum, 0 if the account is closed.
+// Native and Anchor accounts have different sizes so rent differs,
+// the value held by the program must not.
+pub fn excess_lamports(
+    svm: &LiteSVM,
+    addr: &solana_sdk::pubkey::Pubkey,
+) -> u64 {
+    match svm.get_account(addr) {
+        Some(account) => account.lamports.saturating_sub(
+            svm.minimum_balance_for_rent_exemption(account.data.len()),
+        ),
+        None => 0,
+    }
+}
+
+pub fn exists(svm: &LiteSVM, addr: &solana_sdk::pubkey::Pubkey) -> u64 {
+    match svm.get_account(addr) {
+        Some(account) => (account.lamports > 0) as u64,
+        None => 0,
+    }
+}
+
+// Read u64 at offset, 0 if the account is closed or too small
+pub fn read_u64(
+    svm: &LiteSVM,
+    addr: &solana_sdk::pubkey::Pubkey,
+    offset: usize,
+) -> u64 {
+    svm.get_account(addr)
+        .and_then(|account| {
+            account
+                .data
+                .get(offset..offset + 8)
+                .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+        })
+        .unwrap_or(0)
+}
+
+enum Outcome {
+    Ok,
+    Err(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Ok => write!(f, "ok"),
+            Outcome::Err(e) => write!(f, "err {e}"),
+        }
+    }
+}
+
+struct Env<A: App> {
+    svm: LiteSVM,
+    app: A,
+}
+
+impl<A: App> Env<A> {
+    fn new(side: Side, users: &[Keypair]) -> Self {
+        let mut svm = LiteSVM::new();
+
+        let mut clock = svm.get_sysvar::<Clock>();
+        clock.unix_timestamp = START_TIME;
+        svm.set_sysvar(&clock);
+
+        for user in users {
+            svm.airdrop(&user.pubkey(), AIRDROP).unwrap();
+        }
+
+        let app = A::setup(&mut svm, side, users);
+
+        Self { svm, app }
+    }
+
+    fn apply(&mut self, op: &A::Op, users: &[Keypair]) -> Outcome {
+        match self.app.action(op, users) {
+            Action::Tx { ixs, signers } => {
+                // The same user may fill several roles, sign once
+                let mut unique: Vec<&Keypair> = Vec::new();
+                for signer in &signers {
+                    if !unique.iter().any(|s| s.pubkey() == signer.pubkey()) {
+                        unique.push(signer);
+                    }
+                }
+                let signers = unique;
+                let tx = Transaction::new_signed_with_payer(
+                    &ixs,
+                    Some(&signers[0].pubkey()),
+                    &signers,
+                    self.svm.latest_blockhash(),
+                );
+                let res = self.svm.send_transaction(tx);
+                // Identical transactions are allowed in the next step
+                self.svm.expire_blockhash();
+                match res {
+                    Ok(_) => Outcome::Ok,
+                    Err(e) => {
+                        let log =
+                            e.meta.logs.last().cloned().unwrap_or_default();
+                        Outcome::Err(format!("{:?} | {log}", e.err))
+                    }
+                }
+            }
+            Action::Warp(secs) => {
+                let mut clock = self.svm.get_sysvar::<Clock>();
+                clock.unix_timestamp += secs;
+                self.svm.set_sysvar(&clock);
+                Outcome::Ok
+            }
+        }
+    }
+}
+
+// First point where the native and Anchor programs disagree
+pub struct Divergence {
+    pub app: &'static str,
+    pub seed: u64,
+    pub trace: Vec<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} diverged (seed {})", self.app, self.seed)?;
+        for line in &self.trace {
+            writeln!(f, "{line}")?;
+        }
+        write!(f, "{}", self.reason)
+    }
+}
+
+impl fmt::Debug for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+fn diff(native: &[(String, u64)], anchor: &[(String, u64)]) -> Option<String> {
+    let mut lines = Vec::new();
+    for ((key, n), (_, a)) in native.iter().zip(anchor) {
+        if n != a {
+            lines.push(format!("  {key}: native {n} != anchor {a}"));
+        }
+    }
+    if native.len() != anchor.len() {
+        lines.push(format!(
+            "  observations: native {} != anchor {}",
+            native.len(),
+            anchor.len()
+        ));
+    }
+    if lines.is_empty() {
+        None
+    } else {
+        Some(format!("state mismatch\n{}", lines.join("\n")))
+    }
+}
+
+// Run `steps` random ops against both programs
+pub fn run<A: App>(seed: u64, steps: usize) -> Result<(), Divergence> {
+    let mut rng = Rng::new(seed);
+    let users: Vec<Keypair> = (0..NUM_USERS).map(|_| rng.keypair()).collect();
+
+    let mut native = Env::<A>::new(Side::Native, &users);
+    let mut anchor = Env::<A>::new(Side::Anchor, &users);
+
+    let mut trace = Vec::new();
+
+    let divergence = |trace: &Vec<String>, reason: String| Divergence {
+        app: A::NAME,
+        seed,
+        trace: trace.clone(),
+        reason,
+    };
+
+    if let Some(reason) = diff(
+        &native.app.observe(&native.svm),
+        &anchor.app.observe(&anchor.svm),
+    ) {
+        return Err(divergence(&trace, format!("after setup, {reason}")));
+    }
+
+    for step in 1..=steps {
+        let op = A::gen_op(&mut rng);
+
+        let n = native.apply(&op, &users);
+        let a = anchor.apply(&op, &users);
+
+        trace.push(format!(
+            "{step:>4} {op:?}\n       native: {n}\n       anchor: {a}"
+        ));
+
+        if matches!(n, Outcome::Ok) != matches!(a, Outcome::Ok) {
+            return Err(divergence(&trace, "outcome mismatch".to_string()));
+        }
+
+        if let Some(reason) = diff(
+            &native.app.observe(&native.svm),
+            &anchor.app.observe(&anchor.svm),
+        ) {
+            return Err(divergence(&trace, reason));
+        }
+    }
+
+    Ok(())
+}
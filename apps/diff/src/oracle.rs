@@ -0,0 +1,214 @@
+use litesvm::LiteSVM;
+use solana_sdk::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    signer::keypair::keypair_from_seed,
+    system_instruction,
+};
+
+use crate::{
+    Action, App, NUM_USERS, Rng, Side, anchor_ix_data, exists, read_u64,
+};
+
+pub const NATIVE_SO: &str = "../oracle/native/solution/target/deploy/oracle.so";
+pub const ANCHOR_SO: &str = "../oracle/anchor/solution/target/deploy/oracle.so";
+pub const ANCHOR_PROGRAM_ID: Pubkey =
+    pubkey!("2LAxkM9uLztwKxvzeCKJhPV6WxF1S1mdYLToqQNxTCW9");
+
+// Native oracle state, owner + price + updated_at
+const NATIVE_SPACE: u64 = 32 + 8 + 8;
+
+#[derive(Debug)]
+pub enum Op {
+    Init {
+        payer: usize,
+        owner: usize,
+        price: u64,
+    },
+    Update {
+        signer: usize,
+        price: u64,
+    },
+}
+
+pub struct Oracle {
+    side: Side,
+    program_id: Pubkey,
+    users: Vec<Pubkey>,
+    // Same oracle account on both sides, a second Init must fail
+    oracle: Keypair,
+    // Rent for the native oracle account
+    rent: u64,
+}
+
+impl App for Oracle {
+    const NAME: &'static str = "oracle";
+
+    type Op = Op;
+
+    fn setup(svm: &mut LiteSVM, side: Side, users: &[Keypair]) -> Self {
+        let program_id = match side {
+            Side::Native => {
+                let program_id = Pubkey::new_unique();
+                svm.add_program_from_file(program_id, NATIVE_SO).unwrap();
+                program_id
+            }
+            Side::Anchor => {
+                svm.add_program_from_file(ANCHOR_PROGRAM_ID, ANCHOR_SO)
+                    .unwrap();
+                ANCHOR_PROGRAM_ID
+            }
+        };
+
+        // Derived from the users so both sides use the same address
+        let oracle =
+            keypair_from_seed(&hash(users[0].pubkey().as_ref()).to_bytes())
+                .unwrap();
+
+        Self {
+            side,
+            program_id,
+            users: users.iter().map(|u| u.pubkey()).collect(),
+            oracle,
+            rent: svm.minimum_balance_for_rent_exemption(NATIVE_SPACE as usize),
+        }
+    }
+
+    fn gen_op(rng: &mut Rng) -> Op {
+        if rng.range(0, 4) == 0 {
+            Op::Init {
+                payer: rng.user(),
+                owner: rng.user(),
+                price: rng.range(0, 1_000_000),
+            }
+        } else {
+            Op::Update {
+                signer: rng.user(),
+                price: rng.range(0, 1_000_000),
+            }
+        }
+    }
+
+    fn action(&self, op: &Op, users: &[Keypair]) -> Action {
+        let oracle = self.oracle.pubkey();
+
+        match *op {
+            Op::Init {
+                payer,
+                owner,
+                price,
+            } => {
+                let signers = vec![
+                    users[payer].insecure_clone(),
+                    users[owner].insecure_clone(),
+                    self.oracle.insecure_clone(),
+                ];
+
+                match self.side {
+                    // Native program expects the client to create the
+                    // oracle account. Owner is passed as an extra signer
+                    // so both sides require the same signatures.
+                    Side::Native => Action::Tx {
+                        ixs: vec![
+                            system_instruction::create_account(
+                                &self.users[payer],
+                                &oracle,
+                                self.rent,
+                                NATIVE_SPACE,
+                                &self.program_id,
+                            ),
+                            Instruction::new_with_borsh(
+                                self.program_id,
+                                &oracle::Cmd::Init(self.users[owner], price),
+                                vec![
+                                    AccountMeta::new(oracle, false),
+                                    AccountMeta::new_readonly(
+                                        self.users[owner],
+                                        true,
+                                    ),
+                                ],
+                            ),
+                        ],
+                        signers,
+                    },
+                    Side::Anchor => Action::Tx {
+                        ixs: vec![Instruction {
+                            program_id: self.program_id,
+                            accounts: vec![
+                                AccountMeta::new(self.users[payer], true),
+                                AccountMeta::new_readonly(
+                                    self.users[owner],
+                                    true,
+                                ),
+                                AccountMeta::new(oracle, true),
+                                AccountMeta::new_readonly(
+                                    solana_sdk::system_program::id(),
+                                    false,
+                                ),
+                            ],
+                            data: anchor_ix_data("init", &price),
+                        }],
+                        signers,
+                    },
+                }
+            }
+            Op::Update { signer, price } => {
+                let (accounts, data) = match self.side {
+                    Side::Native => (
+                        vec![
+                            AccountMeta::new(oracle, false),
+                            AccountMeta::new(self.users[signer], true),
+                        ],
+                        borsh::to_vec(&oracle::Cmd::Update(price)).unwrap(),
+                    ),
+                    Side::Anchor => (
+                        vec![
+                            AccountMeta::new_readonly(self.users[signer], true),
+                            AccountMeta::new(oracle, false),
+                        ],
+                        anchor_ix_data("update", &price),
+                    ),
+                };
+
+                Action::Tx {
+                    ixs: vec![Instruction {
+                        program_id: self.program_id,
+                        accounts,
+                        data,
+                    }],
+                    signers: vec![users[signer].insecure_clone()],
+                }
+            }
+        }
+    }
+
+    fn observe(&self, svm: &LiteSVM) -> Vec<(String, u64)> {
+        let oracle = self.oracle.pubkey();
+        // Oracle state starts after the 8 byte discriminator on Anchor
+        let offset = match self.side {
+            Side::Native => 0,
+            Side::Anchor => 8,
+        };
+
+        // Index of the owner in users, NUM_USERS if none
+        let owner = svm
+            .get_account(&oracle)
+            .and_then(|account| {
+                let owner = account.data.get(offset..offset + 32)?;
+                self.users.iter().position(|u| u.as_ref() == owner)
+            })
+            .unwrap_or(NUM_USERS);
+
+        vec![
+            ("oracle.exists".to_string(), exists(svm, &oracle)),
+            ("oracle.owner".to_string(), owner as u64),
+            (
+                "oracle.price".to_string(),
+                read_u64(svm, &oracle, offset + 32),
+            ),
+        ]
+    }
+}
@@ -0,0 +1,192 @@
+use litesvm::LiteSVM;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+use crate::{
+    Action, App, NUM_USERS, Rng, START_TIME, Side, anchor_ix_data,
+    excess_lamports, exists, read_u64,
+};
+
+pub const NATIVE_SO: &str = "../piggy/native/solution/target/deploy/piggy.so";
+pub const ANCHOR_SO: &str = "../piggy/anchor/solution/target/deploy/piggy.so";
+pub const ANCHOR_PROGRAM_ID: Pubkey =
+    pubkey!("HaEoLt3pf1A7E7CrNYTooVPY8HJqUD2s4UTJX1JQ3kxC");
+
+#[derive(Debug)]
+pub enum Op {
+    Lock {
+        payer: usize,
+        dst: usize,
+        amt: u64,
+        exp: u64,
+    },
+    Unlock {
+        payer: usize,
+        dst: usize,
+    },
+    Warp(i64),
+}
+
+pub struct Piggy {
+    side: Side,
+    program_id: Pubkey,
+    users: Vec<Pubkey>,
+}
+
+impl Piggy {
+    fn get_pda(&self, payer: &Pubkey, dst: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"lock", payer.as_ref(), dst.as_ref()],
+            &self.program_id,
+        )
+    }
+}
+
+impl App for Piggy {
+    const NAME: &'static str = "piggy";
+
+    type Op = Op;
+
+    fn setup(svm: &mut LiteSVM, side: Side, users: &[Keypair]) -> Self {
+        let program_id = match side {
+            Side::Native => {
+                let program_id = Pubkey::new_unique();
+                svm.add_program_from_file(program_id, NATIVE_SO).unwrap();
+                program_id
+            }
+            Side::Anchor => {
+                svm.add_program_from_file(ANCHOR_PROGRAM_ID, ANCHOR_SO)
+                    .unwrap();
+                ANCHOR_PROGRAM_ID
+            }
+        };
+
+        Self {
+            side,
+            program_id,
+            users: users.iter().map(|u| u.pubkey()).collect(),
+        }
+    }
+
+    fn gen_op(rng: &mut Rng) -> Op {
+        match rng.range(0, 10) {
+            0..4 => Op::Lock {
+                payer: rng.user(),
+                dst: rng.user(),
+                amt: rng.pick(&[0, 1, 1_000_000, 1_000_000_000]),
+                exp: START_TIME as u64 + rng.range(0, 300),
+            },
+            4..7 => Op::Unlock {
+                payer: rng.user(),
+                dst: rng.user(),
+            },
+            _ => Op::Warp(rng.range(0, 60) as i64),
+        }
+    }
+
+    fn action(&self, op: &Op, users: &[Keypair]) -> Action {
+        match *op {
+            Op::Lock {
+                payer,
+                dst,
+                amt,
+                exp,
+            } => {
+                let (pda, bump) =
+                    self.get_pda(&self.users[payer], &self.users[dst]);
+
+                let accounts = vec![
+                    AccountMeta::new(self.users[payer], true),
+                    AccountMeta::new(self.users[dst], true),
+                    AccountMeta::new(pda, false),
+                    AccountMeta::new_readonly(
+                        solana_sdk::system_program::id(),
+                        false,
+                    ),
+                ];
+
+                let data = match self.side {
+                    Side::Native => {
+                        borsh::to_vec(&piggy::Cmd::Lock { amt, exp, bump })
+                            .unwrap()
+                    }
+                    Side::Anchor => anchor_ix_data("lock", &(amt, exp)),
+                };
+
+                Action::Tx {
+                    ixs: vec![Instruction {
+                        program_id: self.program_id,
+                        accounts,
+                        data,
+                    }],
+                    signers: vec![
+                        users[payer].insecure_clone(),
+                        users[dst].insecure_clone(),
+                    ],
+                }
+            }
+            Op::Unlock { payer, dst } => {
+                let (pda, bump) =
+                    self.get_pda(&self.users[payer], &self.users[dst]);
+
+                let accounts = vec![
+                    AccountMeta::new(self.users[payer], true),
+                    AccountMeta::new(self.users[dst], true),
+                    AccountMeta::new(pda, false),
+                    AccountMeta::new_readonly(
+                        solana_sdk::system_program::id(),
+                        false,
+                    ),
+                ];
+
+                let data = match self.side {
+                    Side::Native => {
+                        borsh::to_vec(&piggy::Cmd::Unlock { bump }).unwrap()
+                    }
+                    Side::Anchor => anchor_ix_data("unlock", &()),
+                };
+
+                Action::Tx {
+                    ixs: vec![Instruction {
+                        program_id: self.program_id,
+                        accounts,
+                        data,
+                    }],
+                    signers: vec![
+                        users[payer].insecure_clone(),
+                        users[dst].insecure_clone(),
+                    ],
+                }
+            }
+            Op::Warp(secs) => Action::Warp(secs),
+        }
+    }
+
+    fn observe(&self, svm: &LiteSVM) -> Vec<(String, u64)> {
+        // Lock state starts after the 8 byte discriminator on Anchor
+        let offset = match self.side {
+            Side::Native => 0,
+            Side::Anchor => 8,
+        };
+
+        let mut obs = Vec::new();
+        for payer in 0..NUM_USERS {
+            for dst in 0..NUM_USERS {
+                let (pda, _) =
+                    self.get_pda(&self.users[payer], &self.users[dst]);
+                let key = format!("lock[{payer}->{dst}]");
+                obs.push((format!("{key}.exists"), exists(svm, &pda)));
+                obs.push((format!("{key}.locked"), excess_lamports(svm, &pda)));
+                obs.push((
+                    format!("{key}.exp"),
+                    read_u64(svm, &pda, offset + 32),
+                ));
+            }
+        }
+        obs
+    }
+}
@@ -0,0 +1,76 @@
+use litesvm::LiteSVM;
+use litesvm_token::{
+    CreateAssociatedTokenAccount, CreateMint, MintTo, get_spl_account,
+    spl_token::state::{Account as TokenAccount, Mint},
+};
+use solana_address::Address;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+use spl_associated_token_account_interface::address::get_associated_token_address;
+
+pub const DECIMALS: u8 = 6;
+
+pub fn token_program() -> Pubkey {
+    Pubkey::from(litesvm_token::spl_token::ID.to_bytes())
+}
+
+pub fn ata_program() -> Pubkey {
+    Pubkey::from(spl_associated_token_account_interface::program::ID.to_bytes())
+}
+
+pub fn create_mint(svm: &mut LiteSVM, payer: &Keypair) -> Pubkey {
+    CreateMint::new(svm, payer)
+        .authority(&payer.pubkey())
+        .decimals(DECIMALS)
+        .send()
+        .unwrap()
+}
+
+pub fn get_ata(mint: &Pubkey, owner: &Pubkey) -> Pubkey {
+    let ata_addr = get_associated_token_address(
+        &Address::from(owner.to_bytes()),
+        &Address::from(mint.to_bytes()),
+    );
+    Pubkey::from(ata_addr.to_bytes())
+}
+
+pub fn create_ata(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Pubkey {
+    CreateAssociatedTokenAccount::new(svm, payer, mint)
+        .owner(owner)
+        .send()
+        .unwrap()
+}
+
+pub fn mint_to(
+    svm: &mut LiteSVM,
+    payer: &Keypair,
+    mint: &Pubkey,
+    dst: &Pubkey,
+    amt: u64,
+) {
+    MintTo::new(svm, payer, mint, dst, amt)
+        .owner(payer)
+        .send()
+        .unwrap();
+}
+
+// 0 if the token account does not exist
+pub fn get_balance(svm: &LiteSVM, account: &Pubkey) -> u64 {
+    get_spl_account::<TokenAccount>(svm, account)
+        .map(|a| a.amount)
+        .unwrap_or(0)
+}
+
+// 0 if the mint does not exist
+pub fn get_supply(svm: &LiteSVM, mint: &Pubkey) -> u64 {
+    get_spl_account::<Mint>(svm, mint)
+        .map(|m| m.supply)
+        .unwrap_or(0)
+}
@@ -0,0 +1,53 @@
+use diff::{
+    App, amm::Amm, auction::Auction, cpi::Cpi, oracle::Oracle, piggy::Piggy,
+    run,
+};
+
+// DIFF_SEED=<seed> replays a single run
+// DIFF_RUNS and DIFF_STEPS control how much is explored
+fn env(key: &str, default: u64) -> u64 {
+    std::env::var(key)
+        .ok()
+        .map(|v| v.parse().expect("invalid number"))
+        .unwrap_or(default)
+}
+
+fn check<A: App>() {
+    let steps = env("DIFF_STEPS", 100) as usize;
+
+    let seeds: Vec<u64> = match std::env::var("DIFF_SEED") {
+        Ok(seed) => vec![seed.parse().expect("invalid seed")],
+        Err(_) => (0..env("DIFF_RUNS", 20)).collect(),
+    };
+
+    for seed in seeds {
+        if let Err(divergence) = run::<A>(seed, steps) {
+            panic!("{divergence}");
+        }
+    }
+}
+
+#[test]
+fn test_amm() {
+    check::<Amm>();
+}
+
+#[test]
+fn test_auction() {
+    check::<Auction>();
+}
+
+#[test]
+fn test_cpi() {
+    check::<Cpi>();
+}
+
+#[test]
+fn test_oracle() {
+    check::<Oracle>();
+}
+
+#[test]
+fn test_piggy() {
+    check::<Piggy>();
+}
@@ -27,5 +27,7 @@ pub struct Init<'info> {
 pub fn init(ctx: Context<Init>, price: u64) -> Result<()> {
     ctx.accounts.oracle.owner = ctx.accounts.owner.key();
     ctx.accounts.oracle.price = price;
+    ctx.accounts.oracle.updated_at =
+        Clock::get()?.unix_timestamp.try_into().unwrap();
     Ok(())
 }
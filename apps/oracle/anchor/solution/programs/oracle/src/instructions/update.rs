@@ -13,5 +13,7 @@ pub struct Update<'info> {
 
 pub fn update(ctx: Context<Update>, price: u64) -> Result<()> {
     ctx.accounts.oracle.price = price;
+    ctx.accounts.oracle.updated_at =
+        Clock::get()?.unix_timestamp.try_into().unwrap();
     Ok(())
 }
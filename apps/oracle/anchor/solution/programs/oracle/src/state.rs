@@ -6,4 +6,6 @@ use anchor_lang::prelude::*;
 pub struct Oracle {
     pub owner: Pubkey,
     pub price: u64,
+    // Unix timestamp of the last init or update
+    pub updated_at: u64,
 }
@@ -59,8 +59,8 @@ fn main() {
     // let oracle_account = keypair_from_seed(&seed).unwrap();
     let oracle_account = Keypair::new();
 
-    // 32 + 8
-    let space = 40;
+    // 32 + 8 + 8
+    let space = 48;
     let lamports = client
         .get_minimum_balance_for_rent_exemption(space)
         .unwrap();
@@ -3,6 +3,7 @@ use solana_program::{
     account_info::{AccountInfo, next_account_info},
     program_error::ProgramError,
     pubkey::Pubkey,
+    sysvar::{Sysvar, clock::Clock},
 };
 
 use crate::state::Oracle;
@@ -25,6 +26,7 @@ pub fn init(
 
     oracle.owner = owner;
     oracle.price = price;
+    oracle.updated_at = Clock::get()?.unix_timestamp.try_into().unwrap();
     oracle.serialize(&mut &mut data[..])?;
 
     Ok(())
@@ -2,6 +2,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{AccountInfo, next_account_info},
     program_error::ProgramError,
+    sysvar::{Sysvar, clock::Clock},
 };
 
 use crate::state::Oracle;
@@ -26,6 +27,7 @@ pub fn update(
     }
 
     oracle.price = price;
+    oracle.updated_at = Clock::get()?.unix_timestamp.try_into().unwrap();
     oracle.serialize(&mut &mut data[..])?;
 
     Ok(())
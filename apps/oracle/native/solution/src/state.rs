@@ -5,4 +5,6 @@ use solana_program::pubkey::Pubkey;
 pub struct Oracle {
     pub owner: Pubkey,
     pub price: u64,
+    // Unix timestamp of the last Init or Update
+    pub updated_at: u64,
 }
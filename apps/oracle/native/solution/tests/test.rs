@@ -1,5 +1,6 @@
 use borsh::BorshDeserialize;
 use litesvm::LiteSVM;
+use solana_program::sysvar::clock::Clock;
 use solana_sdk::{
     account::Account,
     instruction::{AccountMeta, Instruction},
@@ -91,6 +92,10 @@ fn test() {
     let data = svm.get_account(&oracle.pubkey()).unwrap().data;
     let oracle_state = Oracle::try_from_slice(&data).unwrap();
     assert_eq!(oracle_state.price, 1234);
+    assert_eq!(
+        oracle_state.updated_at,
+        svm.get_sysvar::<Clock>().unix_timestamp as u64
+    );
 
     // Invalid update (by attacker)
     let update_ix = Instruction {
@@ -38,10 +38,10 @@ pub fn unlock(
         return Err(ProgramError::InvalidAccountData);
     }
 
-    // Verify lock has expired
+    // Verify lock has expired, unlocks at exp same as Anchor
     let clock = Clock::get()?;
     let now: u64 = clock.unix_timestamp.try_into().unwrap();
-    if lock_exp >= now {
+    if now < lock_exp {
         return Err(ProgramError::InvalidArgument);
     }
 
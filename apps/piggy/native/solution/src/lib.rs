@@ -22,6 +22,7 @@ pub enum Cmd {
     },
 }
 
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
 pub fn process_instruction(
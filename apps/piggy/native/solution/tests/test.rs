@@ -101,10 +101,24 @@ fn test() {
         data: borsh::to_vec(&Cmd::Unlock { bump }).unwrap(),
     };
 
+    // Locked until exp
     let mut clock = svm.get_sysvar::<Clock>();
-    clock.unix_timestamp = (exp + 1) as i64;
+    clock.unix_timestamp = (exp - 1) as i64;
     svm.set_sysvar(&clock);
 
+    let res = svm.send_transaction(Transaction::new_signed_with_payer(
+        &[ix.clone()],
+        Some(&payer.pubkey()),
+        &[&payer],
+        svm.latest_blockhash(),
+    ));
+    assert!(res.is_err());
+
+    // Unlocks at exp
+    clock.unix_timestamp = exp as i64;
+    svm.set_sysvar(&clock);
+    svm.expire_blockhash();
+
     svm.send_transaction(Transaction::new_signed_with_payer(
         &[ix],
         Some(&payer.pubkey()),